@@ -0,0 +1,795 @@
+use std::{collections::HashMap, mem};
+
+use color_eyre::eyre::{eyre, Result};
+use engraver::model::{
+    self, annotation, duration, grace, time_signature, Alteration, Annotation, Barline, Clef,
+    Duration, KeySignature, Step, TimeSignature,
+};
+use nom::{
+    branch::alt,
+    bytes::complete::{is_not, tag, tag_no_case},
+    character::complete::{alpha0, char, multispace0, multispace1, one_of, u16, u8},
+    combinator::{eof, opt, verify},
+    multi::{many0, many1, separated_list1},
+    sequence::{delimited, pair, preceded, separated_pair, terminated},
+    Finish, IResult, Parser,
+};
+use strum::IntoEnumIterator;
+
+use super::{chord_symbol::chord_symbol, quoted, ws};
+
+/// Parses a tune in [ABC notation](https://abcnotation.com/wiki/abc:standard:v2.1)
+/// into a [`engraver::model::Staff`].
+///
+/// # Format
+///
+/// The header fields `X:` (reference number), `T:` (title), `M:` (meter, such
+/// as `3/4`, `C`, `C|` or `2+3/8`), `L:` (unit note length), `Q:` (tempo, such
+/// as `1/4=120` or `"Allegro" 3/8=60`) and `K:` (key) are supported, and the
+/// header ends at the `K:` field. Other fields are ignored, as are comments
+/// starting with `%`.
+///
+/// The `K:` field may be followed by a clef such as `clef=bass`, `clef=alto`,
+/// `clef=treble-8`, `clef=bass-8`, `clef=baritone`, `clef=soprano`,
+/// `clef=mezzo` or `clef=perc`, or may only have a clef, in which case there is
+/// no key signature. Notes are written pitches with octave clefs.
+///
+/// The `K:`, `M:` and `L:` fields may also appear in the body, either on a line
+/// of their own or inline such as `[K:D]`. Key and meter changes must come at
+/// the start of a bar, where they change the key or time signature, and a `K:`
+/// field with only a clef keeps the key signature and changes the clef, which
+/// may happen anywhere. `M:none` is not supported in the body, since a time
+/// signature cannot be removed.
+///
+/// The body may contain:
+///
+/// - Notes, with `C` to `B` in octave 4 and `c` to `b` in octave 5, raised an
+///   octave by each `'` and lowered by each `,`.
+/// - Accidentals `^`, `^^`, `_`, `__` and `=`, which apply until the end of the
+///   bar. Other notes are altered by the key signature.
+/// - Length multipliers of the unit note length, such as `2`, `3/2`, `/2`, `/`
+///   and `//`.
+/// - Chords such as `[CEG]2`, which last as long as their first note.
+/// - Rests `z`.
+/// - Grace notes such as `{g}A` and `{ga}A`, and acciaccaturas such as `{/g}A`,
+///   whose lengths are multiples of an eighth note for a single grace note and
+///   of a sixteenth note for several.
+/// - Chord symbols in quotes before a note, such as `"Am7"A`, in the notation
+///   of [`crate::parse_chord_symbol`].
+/// - Annotations in quotes starting with `^`, `_`, `<`, `>` or `@`, such as
+///   `"^dolce"`, which are drawn as expressions above the staff.
+/// - Barlines `|`, `||`, `|]`, and the repeat barlines `|:`, `:|` and `::`.
+///
+/// When there is no `L:` field, the unit note length is an eighth note, or a
+/// sixteenth note when the meter is less than 3/4.
+///
+/// # Examples
+///
+/// ```
+/// use engraver_parser::parse_abc;
+///
+/// parse_abc(
+///     "X:1
+///      T:Speed the Plough
+///      M:4/4
+///      L:1/8
+///      K:G
+///      GABc dedB | dedB dedB | c2ec B2dB | c2A2 A2BA |]",
+/// )
+/// .unwrap();
+/// ```
+pub fn parse_abc(input: &str) -> Result<model::Staff> {
+    let mut header = Header::default();
+    let mut body = String::new();
+
+    for line in input.lines() {
+        let line = line.split('%').next().unwrap_or_default().trim();
+
+        if let Some((name, value)) = field(line) {
+            if header.key.is_none() {
+                header.add_field(name, value.trim())?;
+            } else if matches!(name, 'K' | 'M' | 'L') {
+                body.push_str(&format!("[{name}:{value}]\n"));
+            }
+        } else if header.key.is_some() {
+            body.push_str(line);
+            body.push('\n');
+        }
+    }
+
+    let key = header
+        .key
+        .ok_or_else(|| eyre!("ABC tune has no K: field"))?;
+    let (_, measures) = measures(&body)
+        .finish()
+        .map_err(|error| eyre!("failed to parse ABC body at {:?}", error.input))?;
+
+    let mut context = Context {
+        unit: header.unit(),
+        key_signature: key.signature.flatten(),
+        accidentals: HashMap::new(),
+    };
+
+    let mut measures = measures
+        .into_iter()
+        .map(|measure| measure.into_model(&mut context))
+        .collect::<Result<Vec<_>>>()?;
+
+    if let (Some(tempo), Some(measure)) = (header.tempo, measures.first_mut()) {
+        measure.elements.insert(
+            0,
+            model::measure::Element::Annotation(Annotation::Tempo(tempo)),
+        );
+    }
+
+    Ok(model::Staff {
+        geometry: Default::default(),
+        clef: key.clef.unwrap_or_default(),
+        key_signature: key.signature.flatten(),
+        time_signature: header.meter.flatten(),
+        key_cancellation: Default::default(),
+        measures,
+    })
+}
+
+/// Returns the name and value of a field line such as `K:G`.
+fn field(line: &str) -> Option<(char, &str)> {
+    let mut chars = line.chars();
+
+    match (chars.next(), chars.next()) {
+        (Some(name), Some(':')) if name.is_ascii_alphabetic() => Some((name, &line[2..])),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Default)]
+struct Header {
+    meter: Option<Option<TimeSignature>>,
+    tempo: Option<annotation::Tempo>,
+    unit: Option<Duration>,
+    key: Option<Key>,
+}
+
+impl Header {
+    fn add_field(&mut self, name: char, value: &str) -> Result<()> {
+        match name {
+            'M' => self.meter = Some(parse_field(meter, value, "M:")?),
+            'L' => self.unit = Some(parse_field(unit, value, "L:")?),
+            'Q' => self.tempo = Some(parse_field(tempo, value, "Q:")?),
+            'K' => self.key = Some(parse_field(key, value, "K:")?),
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Returns the unit note length, which defaults to a sixteenth note if the
+    /// meter is less than 3/4 and otherwise to an eighth note.
+    fn unit(&self) -> Duration {
+        let value = match (self.unit, self.meter.as_ref().and_then(Option::as_ref)) {
+            (Some(unit), _) => return unit,
+            (None, Some(meter))
+                if meter
+                    .fraction()
+                    .is_some_and(|(numerator, denominator)| 4 * numerator < 3 * denominator) =>
+            {
+                duration::Value::Sixteenth
+            }
+            _ => duration::Value::Eighth,
+        };
+
+        Duration { value, dots: None }
+    }
+}
+
+fn parse_field<'a, O>(
+    parser: impl FnMut(&'a str) -> IResult<&'a str, O>,
+    value: &'a str,
+    name: &str,
+) -> Result<O> {
+    let (_, output) = terminated(ws(parser), eof)(value)
+        .finish()
+        .map_err(|_| eyre!("invalid ABC {name} field: {value:?}"))?;
+
+    Ok(output)
+}
+
+#[derive(Debug)]
+struct Context {
+    unit: Duration,
+    key_signature: Option<KeySignature>,
+    /// The accidentals applied to each step and octave in the current bar.
+    accidentals: HashMap<(Step, i8), Alteration>,
+}
+
+impl Context {
+    fn alteration(&self, step: Step, octave: i8) -> Alteration {
+        if let Some(alteration) = self.accidentals.get(&(step, octave)) {
+            return *alteration;
+        }
+
+        let fifths = self
+            .key_signature
+            .map(|key_signature| key_signature.fifths())
+            .unwrap_or_default();
+        let position = SHARPS.iter().position(|sharp| *sharp == step).unwrap() as i8;
+
+        if fifths > position {
+            Alteration::Sharp
+        } else if fifths < position - 6 {
+            Alteration::Flat
+        } else {
+            Alteration::Natural
+        }
+    }
+
+    fn duration(&self, length: Length) -> Result<Duration> {
+        let divisions = self.unit.divisions() * length.numerator;
+
+        duration::Value::iter()
+            .flat_map(|value| {
+                [
+                    None,
+                    Some(duration::Dots::Dot),
+                    Some(duration::Dots::DoubleDot),
+                ]
+                .map(|dots| Duration { value, dots })
+            })
+            .find(|duration| duration.divisions() * length.denominator == divisions)
+            .ok_or_else(|| {
+                eyre!(
+                    "ABC note length {}/{} is not a single note value",
+                    length.numerator,
+                    length.denominator
+                )
+            })
+    }
+}
+
+/// The steps in the order in which they are sharpened by key signatures.
+const SHARPS: [Step; 7] = [
+    Step::F,
+    Step::C,
+    Step::G,
+    Step::D,
+    Step::A,
+    Step::E,
+    Step::B,
+];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Key {
+    /// The key signature, which is `Some(None)` for a key without sharps or
+    /// flats and `None` when the field only has a clef.
+    signature: Option<Option<KeySignature>>,
+    clef: Option<Clef>,
+}
+
+/// A `K:`, `M:` or `L:` field in the body of a tune.
+#[derive(Clone, Debug, PartialEq)]
+enum Field {
+    Key(Key),
+    Meter(Option<TimeSignature>),
+    Unit(Duration),
+}
+
+/// A multiple of the unit note length.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Length {
+    numerator: u32,
+    denominator: u32,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Pitch {
+    step: Step,
+    alteration: Option<Alteration>,
+    octave: i8,
+}
+
+impl Pitch {
+    fn into_model(self, context: &mut Context) -> model::Pitch {
+        let alteration = match self.alteration {
+            Some(alteration) => {
+                context
+                    .accidentals
+                    .insert((self.step, self.octave), alteration);
+                alteration
+            }
+            None => context.alteration(self.step, self.octave),
+        };
+
+        model::Pitch {
+            step: self.step,
+            alteration,
+            octave: self.octave,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Note {
+    pitch: Pitch,
+    length: Length,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Element {
+    Note(Note),
+    Chord(Vec<Note>, Length),
+    Rest(Length),
+    Grace(grace::Kind, Vec<Note>),
+    ChordSymbol(model::ChordSymbol),
+    Annotation(Annotation),
+    Field(Field),
+}
+
+impl Element {
+    fn into_model(self, context: &mut Context) -> Result<model::measure::Element> {
+        Ok(match self {
+            Self::Note(note) => model::measure::Element::Note(model::Note {
+                pitch: note.pitch.into_model(context),
+                duration: context.duration(note.length)?,
+                articulations: vec![],
+                ornament: None,
+                id: None,
+            }),
+            Self::Chord(notes, length) => {
+                let length = Length {
+                    numerator: notes[0].length.numerator * length.numerator,
+                    denominator: notes[0].length.denominator * length.denominator,
+                };
+                let duration = context.duration(length)?;
+                let mut pitches: Vec<_> = notes
+                    .into_iter()
+                    .map(|note| note.pitch.into_model(context))
+                    .collect();
+                pitches.dedup();
+
+                if pitches.len() == 1 {
+                    model::measure::Element::Note(model::Note {
+                        pitch: pitches[0],
+                        duration,
+                        articulations: vec![],
+                        ornament: None,
+                        id: None,
+                    })
+                } else {
+                    model::measure::Element::Chord(model::Chord::new(
+                        pitches,
+                        duration,
+                        vec![],
+                        None,
+                    ))
+                }
+            }
+            Self::Rest(length) => model::measure::Element::Rest(model::Rest {
+                duration: context.duration(length)?,
+                id: None,
+            }),
+            Self::Grace(kind, notes) => {
+                // Grace note lengths are multiples of an eighth note for a
+                // single grace note, and of a sixteenth note for several.
+                let value = if notes.len() == 1 {
+                    duration::Value::Eighth
+                } else {
+                    duration::Value::Sixteenth
+                };
+                let unit = mem::replace(&mut context.unit, Duration { value, dots: None });
+
+                let notes = notes
+                    .into_iter()
+                    .map(|note| {
+                        Ok(model::Note {
+                            pitch: note.pitch.into_model(context),
+                            duration: context.duration(note.length)?,
+                            articulations: vec![],
+                            ornament: None,
+                            id: None,
+                        })
+                    })
+                    .collect::<Result<_>>();
+
+                context.unit = unit;
+
+                model::measure::Element::Grace(model::Grace {
+                    kind,
+                    notes: notes?,
+                })
+            }
+            Self::ChordSymbol(chord_symbol) => model::measure::Element::ChordSymbol(chord_symbol),
+            Self::Annotation(annotation) => model::measure::Element::Annotation(annotation),
+            Self::Field(_) => unreachable!("fields are applied by their measure"),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Measure {
+    start_barline: Option<Barline>,
+    elements: Vec<Element>,
+    barline: Barline,
+    /// Whether the next measure starts with a start repeat.
+    is_followed_by_start_repeat: bool,
+}
+
+impl Measure {
+    fn into_model(self, context: &mut Context) -> Result<model::Measure> {
+        context.accidentals.clear();
+
+        let mut measure = model::Measure {
+            start_barline: self.start_barline,
+            key_signature: None,
+            time_signature: None,
+            elements: vec![],
+            barline: self.barline,
+            ending: None,
+            navigation: vec![],
+            lyrics: vec![],
+            system_break: false,
+        };
+
+        for element in self.elements {
+            let Element::Field(field) = element else {
+                measure.elements.push(element.into_model(context)?);
+                continue;
+            };
+
+            // Key and time signatures only change at the start of measures.
+            let starts_measure = measure.elements.is_empty();
+
+            match field {
+                Field::Key(key) => {
+                    if let Some(signature) = key.signature {
+                        if !starts_measure {
+                            return Err(eyre!("ABC K: field in the middle of a bar"));
+                        }
+
+                        measure.key_signature = Some(signature);
+                        context.key_signature = signature;
+                    }
+
+                    if let Some(clef) = key.clef {
+                        measure.elements.push(model::measure::Element::Clef(clef));
+                    }
+                }
+                Field::Meter(meter) => {
+                    if !starts_measure {
+                        return Err(eyre!("ABC M: field in the middle of a bar"));
+                    }
+
+                    measure.time_signature =
+                        Some(meter.ok_or_else(|| eyre!("ABC M:none field in the body of a tune"))?);
+                }
+                Field::Unit(unit) => context.unit = unit,
+            }
+        }
+
+        Ok(measure)
+    }
+}
+
+fn meter(input: &str) -> IResult<&str, Option<TimeSignature>> {
+    alt((
+        tag("none").map(|_| None),
+        tag("C|").map(|_| Some(TimeSignature::Cut)),
+        tag("C").map(|_| Some(TimeSignature::Common)),
+        separated_pair(separated_list1(char('+'), u8), char('/'), u8).map(
+            |(numerators, denominator)| {
+                Some(TimeSignature::Meters(vec![time_signature::Meter {
+                    beats: time_signature::Beats::Numbers(numerators),
+                    unit: Some(time_signature::Unit::Number(denominator)),
+                }]))
+            },
+        ),
+    ))(input)
+}
+
+fn unit(input: &str) -> IResult<&str, Duration> {
+    let (remaining, denominator) = preceded(tag("1/"), u16)(input)?;
+
+    let value = duration::Value::iter()
+        .find(|value| value.divisions() * denominator as u32 == duration::Value::Whole.divisions())
+        .ok_or(nom::Err::Error(nom::error::Error::new(
+            input,
+            nom::error::ErrorKind::Verify,
+        )))?;
+
+    Ok((remaining, Duration { value, dots: None }))
+}
+
+/// Parses a tempo such as `1/4=120`, `"Allegro" 3/8=60` or `"Adagio"`.
+fn tempo(input: &str) -> IResult<&str, annotation::Tempo> {
+    let metronome_mark = separated_pair(beat, ws(char('=')), u16)
+        .map(|(beat, per_minute)| annotation::MetronomeMark { beat, per_minute });
+
+    verify(
+        pair(opt(terminated(quoted, multispace0)), opt(metronome_mark)).map(
+            |(text, metronome_mark)| annotation::Tempo {
+                text: text.map(str::to_owned),
+                metronome_mark,
+            },
+        ),
+        |tempo| tempo.text.is_some() || tempo.metronome_mark.is_some(),
+    )(input)
+}
+
+/// Parses the beat of a tempo, which is a fraction of a whole note such as
+/// `1/4`, or `3/8` for a dotted quarter note.
+fn beat(input: &str) -> IResult<&str, Duration> {
+    let (remaining, (numerator, denominator)) = separated_pair(u16, char('/'), u16)(input)?;
+
+    let beat = duration::Value::iter()
+        .flat_map(|value| {
+            [
+                None,
+                Some(duration::Dots::Dot),
+                Some(duration::Dots::DoubleDot),
+            ]
+            .map(|dots| Duration { value, dots })
+        })
+        .find(|duration| {
+            duration.divisions() * u32::from(denominator)
+                == duration::Value::Whole.divisions() * u32::from(numerator)
+        })
+        .ok_or(nom::Err::Error(nom::error::Error::new(
+            input,
+            nom::error::ErrorKind::Verify,
+        )))?;
+
+    Ok((remaining, beat))
+}
+
+fn key(input: &str) -> IResult<&str, Key> {
+    let (input, signature) = alt((
+        tag_no_case("none").map(|_| Some(0)),
+        key_signature.map(Some),
+        multispace0.map(|_| None),
+    ))(input)?;
+    // A clef is separated from the key, but may also be the whole field.
+    let (input, clef) = match signature {
+        Some(_) => opt(preceded(multispace1, clef))(input)?,
+        None => opt(clef)(input)?,
+    };
+
+    let signature = match signature {
+        Some(fifths) if !(-7..=7).contains(&fifths) => {
+            return Err(nom::Err::Error(nom::error::Error::new(
+                input,
+                nom::error::ErrorKind::Verify,
+            )))
+        }
+        Some(fifths) => Some(KeySignature::from_fifths(fifths)),
+        None => None,
+    };
+
+    Ok((input, Key { signature, clef }))
+}
+
+/// Parses a key such as `G`, `Bbm` or `D dorian` into its position on the
+/// circle of fifths.
+fn key_signature(input: &str) -> IResult<&str, i8> {
+    let (input, tonic) = one_of("CDEFGAB")(input)?;
+    let (input, alteration) = opt(alt((char('#').map(|_| 7), char('b').map(|_| -7))))(input)?;
+    let (input, mode) = opt(preceded(multispace0, mode))(input)?;
+
+    let tonic = match tonic {
+        'F' => -1,
+        'C' => 0,
+        'G' => 1,
+        'D' => 2,
+        'A' => 3,
+        'E' => 4,
+        'B' => 5,
+        _ => unreachable!(),
+    };
+
+    Ok((
+        input,
+        tonic + alteration.unwrap_or_default() + mode.unwrap_or_default(),
+    ))
+}
+
+/// Parses a mode into the difference between its key signature and that of the
+/// major key with the same tonic.
+fn mode(input: &str) -> IResult<&str, i8> {
+    let (input, mode) = alt((
+        tag_no_case("maj").map(|_| 0),
+        tag_no_case("ion").map(|_| 0),
+        tag_no_case("lyd").map(|_| 1),
+        tag_no_case("mix").map(|_| -1),
+        tag_no_case("dor").map(|_| -2),
+        tag_no_case("min").map(|_| -3),
+        tag_no_case("aeo").map(|_| -3),
+        tag_no_case("phr").map(|_| -4),
+        tag_no_case("loc").map(|_| -5),
+        tag("m").map(|_| -3),
+    ))(input)?;
+    let (input, _) = alpha0(input)?;
+
+    Ok((input, mode))
+}
+
+fn clef(input: &str) -> IResult<&str, Clef> {
+    preceded(
+        tag("clef="),
+        alt((
+            tag("treble-8").map(|_| Clef::Treble8vb),
+            tag("treble+8").map(|_| Clef::Treble8va),
+            tag("treble").map(|_| Clef::Treble),
+            tag("soprano").map(|_| Clef::Soprano),
+            tag("mezzo").map(|_| Clef::MezzoSoprano),
+            tag("alto").map(|_| Clef::Alto),
+            tag("tenor").map(|_| Clef::Tenor),
+            tag("baritone").map(|_| Clef::Baritone),
+            tag("bass-8").map(|_| Clef::Bass8vb),
+            tag("bass").map(|_| Clef::Bass),
+            tag("perc").map(|_| Clef::Percussion),
+        )),
+    )(input)
+}
+
+fn accidental(input: &str) -> IResult<&str, Alteration> {
+    alt((
+        tag("^^").map(|_| Alteration::DoubleSharp),
+        tag("^").map(|_| Alteration::Sharp),
+        tag("__").map(|_| Alteration::DoubleFlat),
+        tag("_").map(|_| Alteration::Flat),
+        tag("=").map(|_| Alteration::Natural),
+    ))(input)
+}
+
+fn step(input: &str) -> IResult<&str, (Step, i8)> {
+    let (input, c) = one_of("CDEFGABcdefgab")(input)?;
+
+    let step = match c.to_ascii_lowercase() {
+        'c' => Step::C,
+        'd' => Step::D,
+        'e' => Step::E,
+        'f' => Step::F,
+        'g' => Step::G,
+        'a' => Step::A,
+        'b' => Step::B,
+        _ => unreachable!(),
+    };
+    let octave = if c.is_ascii_uppercase() { 4 } else { 5 };
+
+    Ok((input, (step, octave)))
+}
+
+fn pitch(input: &str) -> IResult<&str, Pitch> {
+    let (input, alteration) = opt(accidental)(input)?;
+    let (input, (step, octave)) = step(input)?;
+    let (input, octave_marks) = many0(alt((char('\'').map(|_| 1), char(',').map(|_| -1))))(input)?;
+
+    Ok((
+        input,
+        Pitch {
+            step,
+            alteration,
+            octave: octave + octave_marks.into_iter().sum::<i8>(),
+        },
+    ))
+}
+
+fn length(input: &str) -> IResult<&str, Length> {
+    let (input, numerator) = opt(u16)(input)?;
+    let (input, slashes) = many0(preceded(char('/'), opt(u16)))(input)?;
+
+    let denominator = match slashes.as_slice() {
+        [] => 1,
+        [Some(denominator)] => *denominator as u32,
+        slashes => 1 << slashes.len(),
+    };
+
+    Ok((
+        input,
+        Length {
+            numerator: numerator.unwrap_or(1) as u32,
+            denominator: denominator.max(1),
+        },
+    ))
+}
+
+fn note(input: &str) -> IResult<&str, Note> {
+    let (input, pitch) = pitch(input)?;
+    let (input, length) = length(input)?;
+
+    Ok((input, Note { pitch, length }))
+}
+
+/// Parses an inline field such as `[K:D]`, `[M:3/4]` or `[L:1/4]`.
+fn inline_field(input: &str) -> IResult<&str, Field> {
+    delimited(
+        char('['),
+        alt((
+            preceded(tag("K:"), ws(key)).map(Field::Key),
+            preceded(tag("M:"), ws(meter)).map(Field::Meter),
+            preceded(tag("L:"), ws(unit)).map(Field::Unit),
+        )),
+        char(']'),
+    )(input)
+}
+
+fn element(input: &str) -> IResult<&str, Element> {
+    alt((
+        inline_field.map(Element::Field),
+        note.map(Element::Note),
+        delimited(char('['), many1(note), char(']'))
+            .and(length)
+            .map(|(notes, length)| Element::Chord(notes, length)),
+        preceded(char('z'), length).map(Element::Rest),
+        delimited(char('{'), opt(char('/')).and(many1(ws(note))), char('}')).map(
+            |(slash, notes)| {
+                let kind = match slash {
+                    Some(_) => grace::Kind::Acciaccatura,
+                    None => grace::Kind::Appoggiatura,
+                };
+
+                Element::Grace(kind, notes)
+            },
+        ),
+        delimited(char('"'), chord_symbol, char('"')).map(Element::ChordSymbol),
+        delimited(
+            char('"'),
+            preceded(one_of("^_<>@"), is_not("\"")),
+            char('"'),
+        )
+        .map(|text: &str| Element::Annotation(Annotation::Expression(text.to_owned()))),
+    ))(input)
+}
+
+/// Parses a barline, and whether the next measure starts with a start repeat.
+fn barline(input: &str) -> IResult<&str, (Barline, bool)> {
+    alt((
+        alt((tag("::"), tag(":|:"))).map(|_| (Barline::EndStartRepeat, false)),
+        tag(":|").map(|_| (Barline::EndRepeat, false)),
+        tag("|]").map(|_| (Barline::Final, false)),
+        tag("||").map(|_| (Barline::Double, false)),
+        tag("|:").map(|_| (Barline::Thin, true)),
+        char('|').map(|_| (Barline::Thin, false)),
+    ))(input)
+}
+
+fn measure(input: &str) -> IResult<&str, Measure> {
+    let (input, elements) = many0(ws(element))(input)?;
+    let (input, (barline, is_followed_by_start_repeat)) = ws(barline)(input)?;
+
+    Ok((
+        input,
+        Measure {
+            start_barline: None,
+            elements,
+            barline,
+            is_followed_by_start_repeat,
+        },
+    ))
+}
+
+/// Parses the body of a tune, where any notes after the last barline are ended
+/// by a thin barline.
+fn measures(input: &str) -> IResult<&str, Vec<Measure>> {
+    let (input, starts_with_repeat) = opt(ws(tag("|:")))(input)?;
+    let (input, mut measures) = many0(measure)(input)?;
+    let (input, elements) = many0(ws(element))(input)?;
+    let (input, _) = eof(input)?;
+
+    if !elements.is_empty() {
+        measures.push(Measure {
+            start_barline: None,
+            elements,
+            barline: Barline::default(),
+            is_followed_by_start_repeat: false,
+        });
+    }
+
+    let mut is_start_repeat = starts_with_repeat.is_some();
+    for measure in &mut measures {
+        if is_start_repeat {
+            measure.start_barline = Some(Barline::StartRepeat);
+        }
+        is_start_repeat = measure.is_followed_by_start_repeat;
+    }
+
+    Ok((input, measures))
+}
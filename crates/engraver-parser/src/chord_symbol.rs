@@ -0,0 +1,224 @@
+use color_eyre::eyre::{eyre, Result};
+use engraver::model::{
+    chord_symbol::{Change, Degree, Extension, Quality, Root},
+    Alteration, ChordSymbol, Step,
+};
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{char, u8},
+    combinator::{eof, not, opt},
+    multi::{many0, separated_list1},
+    sequence::{delimited, pair, preceded, terminated},
+    Finish, IResult, Parser,
+};
+
+/// Parses a chord symbol in a common notation, such as `Cmaj7`, `F#m7b5/E` or
+/// `Bb13(#11)`, into a [`engraver::model::ChordSymbol`].
+///
+/// # Format
+///
+/// ```text
+/// [root] [quality]? [extensions]? [sus]? [alterations]? [/ bass]?
+/// ```
+///
+/// The root and bass are a letter from `A` to `G`, followed by an optional `#`
+/// or `b` (or `♯` or `♭`).
+///
+/// ```text
+/// m min -         minor
+/// dim o °         diminished
+/// ø               half-diminished, implying a seventh
+/// aug +           augmented
+/// maj M Δ         major seventh, which applies to the extension after it,
+///                 such as maj9, and implies a seventh on its own
+/// ```
+///
+/// Extensions are a degree such as `6`, `7`, `9`, `11` or `13`, or `6/9` (also
+/// written `69`). `5` on its own is a power chord, and `sus2`, `sus4` or `sus`
+/// (a fourth) after any extension is a suspended chord.
+///
+/// Alterations are degrees after `b`, `#`, `add`, `no` or `omit`, such as `b5`,
+/// `add9` or `(b9,#11)`, with or without parentheses.
+///
+/// # Examples
+///
+/// ```
+/// use engraver::model::{
+///     chord_symbol::{Change, Degree, Extension, Quality, Root},
+///     Alteration, ChordSymbol, Step,
+/// };
+/// use engraver_parser::parse_chord_symbol;
+///
+/// assert_eq!(
+///     parse_chord_symbol("F#m7b5/E").unwrap(),
+///     ChordSymbol {
+///         root: Root {
+///             step: Step::F,
+///             alteration: Alteration::Sharp,
+///         },
+///         quality: Quality::Minor,
+///         extensions: vec![Extension {
+///             degree: 7,
+///             is_major: false,
+///         }],
+///         alterations: vec![Degree {
+///             degree: 5,
+///             change: Change::Flat,
+///         }],
+///         bass: Some(Root {
+///             step: Step::E,
+///             alteration: Alteration::Natural,
+///         }),
+///     }
+/// );
+/// ```
+pub fn parse_chord_symbol(input: &str) -> Result<ChordSymbol> {
+    let (_, chord_symbol) = terminated(chord_symbol, eof)(input)
+        .finish()
+        .map_err(|error| eyre!("failed to parse chord symbol at {:?}", error.input))?;
+
+    Ok(chord_symbol)
+}
+
+pub(crate) fn chord_symbol(input: &str) -> IResult<&str, ChordSymbol> {
+    let (input, root) = note_name(input)?;
+    let (input, quality) = opt(quality)(input)?;
+    // A major seventh may be in parentheses after a minor quality, as in
+    // `m(maj7)`.
+    let (input, (is_major, extensions)) = alt((
+        delimited(
+            char('('),
+            pair(major_seventh.map(Some), opt(extensions)),
+            char(')'),
+        ),
+        pair(opt(major_seventh), opt(extensions)),
+    ))(input)?;
+    let (input, suspended) = opt(suspended)(input)?;
+    let (input, alterations) = many0(alterations)(input)?;
+    let (input, bass) = opt(preceded(char('/'), note_name))(input)?;
+
+    let is_major = is_major.is_some();
+    let mut extensions = extensions.unwrap_or_default();
+    let mut quality = quality.unwrap_or_default();
+
+    if let Some(extension) = extensions.first_mut() {
+        extension.is_major = is_major;
+    } else if is_major || quality == Quality::HalfDiminished {
+        extensions.push(Extension {
+            degree: 7,
+            is_major,
+        });
+    }
+
+    if quality == Quality::Major && extensions == [extension(5)] {
+        quality = Quality::Power;
+        extensions.clear();
+    }
+
+    if let Some(suspended) = suspended {
+        quality = suspended;
+    }
+
+    Ok((
+        input,
+        ChordSymbol {
+            root,
+            quality,
+            extensions,
+            alterations: alterations.into_iter().flatten().collect(),
+            bass,
+        },
+    ))
+}
+
+fn note_name(input: &str) -> IResult<&str, Root> {
+    let step = alt((
+        char('C').map(|_| Step::C),
+        char('D').map(|_| Step::D),
+        char('E').map(|_| Step::E),
+        char('F').map(|_| Step::F),
+        char('G').map(|_| Step::G),
+        char('A').map(|_| Step::A),
+        char('B').map(|_| Step::B),
+    ));
+    let alteration = alt((
+        alt((char('#'), char('♯'))).map(|_| Alteration::Sharp),
+        alt((char('b'), char('♭'))).map(|_| Alteration::Flat),
+    ));
+
+    pair(step, opt(alteration))
+        .map(|(step, alteration)| Root {
+            step,
+            alteration: alteration.unwrap_or_default(),
+        })
+        .parse(input)
+}
+
+fn quality(input: &str) -> IResult<&str, Quality> {
+    alt((
+        alt((tag("dim"), tag("o"), tag("°"))).map(|_| Quality::Diminished),
+        tag("ø").map(|_| Quality::HalfDiminished),
+        alt((tag("aug"), tag("+"))).map(|_| Quality::Augmented),
+        // `maj` and `M` are major sevenths rather than minor.
+        terminated(alt((tag("min"), tag("m"), tag("-"))), not_major).map(|_| Quality::Minor),
+    ))(input)
+}
+
+/// Succeeds if the input does not continue with `aj`, so that the `m` of `maj`
+/// is not parsed as minor.
+fn not_major(input: &str) -> IResult<&str, ()> {
+    not(tag("aj"))(input)
+}
+
+fn major_seventh(input: &str) -> IResult<&str, ()> {
+    alt((tag("maj"), tag("Maj"), tag("M"), tag("Δ")))
+        .map(|_| ())
+        .parse(input)
+}
+
+fn extensions(input: &str) -> IResult<&str, Vec<Extension>> {
+    alt((
+        alt((tag("6/9"), tag("69"))).map(|_| vec![extension(6), extension(9)]),
+        u8.map(|degree| vec![extension(degree)]),
+    ))(input)
+}
+
+fn suspended(input: &str) -> IResult<&str, Quality> {
+    alt((
+        tag("sus2").map(|_| Quality::SuspendedSecond),
+        tag("sus4").map(|_| Quality::SuspendedFourth),
+        tag("sus").map(|_| Quality::SuspendedFourth),
+    ))(input)
+}
+
+fn alterations(input: &str) -> IResult<&str, Vec<Degree>> {
+    alt((
+        delimited(
+            char('('),
+            separated_list1(alt((char(','), char(' '))), alteration),
+            char(')'),
+        ),
+        alteration.map(|alteration| vec![alteration]),
+    ))(input)
+}
+
+fn alteration(input: &str) -> IResult<&str, Degree> {
+    let change = alt((
+        alt((tag("b"), tag("♭"))).map(|_| Change::Flat),
+        alt((tag("#"), tag("♯"))).map(|_| Change::Sharp),
+        tag("add").map(|_| Change::Add),
+        alt((tag("no"), tag("omit"))).map(|_| Change::Omit),
+    ));
+
+    pair(change, u8)
+        .map(|(change, degree)| Degree { degree, change })
+        .parse(input)
+}
+
+fn extension(degree: u8) -> Extension {
+    Extension {
+        degree,
+        is_major: false,
+    }
+}
@@ -1,20 +1,33 @@
+use std::mem;
+
 use color_eyre::eyre::Result;
 use engraver::model::{
-    self, duration, key_signature, Alteration, Barline, Clef, Duration, KeySignature, Step,
-    TimeSignature,
+    self, annotation, duration, grace, hairpin, key_signature, lyric, navigation, octave_line,
+    ornament, pedal, time_signature, Alteration, Annotation, Articulation, Barline, Clef, Duration,
+    Dynamic, Ending, Hairpin, KeySignature, Lyric, Navigation, OctaveLine, Ornament, Pedal,
+    StaffGeometry, Step, TimeSignature,
 };
 use nom::{
     branch::alt,
-    bytes::complete::tag,
-    character::complete::{char, i8, multispace0, u8},
-    combinator::opt,
+    bytes::complete::{is_not, tag},
+    character::complete::{alpha1, char, i8, multispace0, multispace1, u16, u8},
+    combinator::{map_opt, not, opt, verify},
     error::ParseError,
-    multi::{fold_many_m_n, many0, many_m_n, separated_list1},
-    sequence::{delimited, separated_pair},
+    multi::{fold_many0, fold_many_m_n, many0, many_m_n, separated_list1},
+    number::complete::double,
+    sequence::{delimited, pair, preceded, terminated},
     Finish, IResult, Parser,
 };
 use strum::EnumCount;
 
+mod abc;
+mod chord_symbol;
+mod lilypond;
+
+pub use abc::parse_abc;
+pub use chord_symbol::parse_chord_symbol;
+pub use lilypond::parse_lilypond;
+
 /// Parses a string into a [`engraver::model::Staff`].
 ///
 /// # Format
@@ -22,15 +35,249 @@ use strum::EnumCount;
 /// ## Staff
 ///
 /// ```text
-/// [clef]? [key signature]? [time signature]? [measure]*
+/// [\lines n]? [\scale n]? [clef]? [key signature]? [time signature]? [|:]? [measure]*
 /// ```
 ///
+/// `\lines` sets the number of staff lines, such as 1 for a percussion rhythm
+/// staff or 6 for a tablature staff, and `\scale` the size of the staff
+/// relative to a standard staff, such as 0.75 for a cue staff.
+///
 /// ## Measure
 ///
 /// ```text
-/// [element]* [barline]
+/// [ending]? [key change]? [time change]? [element | clef | navigation | \break]* [barline]
+/// ```
+///
+/// ## Barline
+///
+/// ```text
+/// |              thin
+/// ||             final
+/// |:             thin, followed by a start repeat at the start of the next
+///                measure, which replaces the thin barline
+/// :|             end repeat
+/// :|:            end and start repeat
+/// |[double]      double thin
+/// |[heavy]       heavy
+/// |[dashed]      dashed
+/// |[dotted]      dotted
+/// |[invisible]   invisible
+/// ```
+///
+/// A staff may also start with `|:` after any clef, key signature and time
+/// signature, which starts the first measure with a start repeat.
+///
+/// ## Ending
+///
+/// A first, second or later ending, such as `1.` or `1,2.`, which spans
+/// consecutive measures with the same ending.
+///
+/// ## Time signature
+///
+/// ```text
+/// 3/4         numerator and denominator
+/// C           common time
+/// C|          cut time
+/// 3+2+2/8     additive meter
+/// 3/4 6/8     interchangeable meters, written side by side
+/// 3           single number, counting quarter notes
+/// 3/[4.]      note denominator, here a dotted quarter note
+/// X/[4.]      unspecified number of beats
+/// ```
+///
+/// Consecutive eighth notes and chords are beamed together within each group
+/// of beats, such as 3+2+2 for `3+2+2/8` or two dotted quarter notes for
+/// `6/8`.
+///
+/// ## Key and time signature changes
+///
+/// A measure may start with a new key signature, such as `\key b` or
+/// `\key ###`, or `\key 0` for no sharps or flats, and a new time signature,
+/// such as `\time 3/4`. Naturals cancel the sharps or flats of the old key
+/// signature which are not in the new one.
+///
+/// ## Articulation
+///
+/// Notes and chords may be followed by articulations, after any duration:
+///
+/// ```text
+/// -.  staccato
+/// -!  staccatissimo
+/// --  tenuto
+/// -'  stress
+/// ->  accent
+/// -^  marcato
+/// ```
+///
+/// ## Ornaments
+///
+/// Notes and chords may have an ornament, after any articulations:
+///
+/// ```text
+/// -tr     trill
+/// -tr~    trill with a wavy line until the next note, chord or rest
+/// -mor    mordent
+/// -prall  inverted mordent
+/// -turn   turn
+/// -rturn  inverted turn
+/// ```
+///
+/// An ornament may be followed by an accidental above it, for its upper
+/// auxiliary note, and then one below it, for its lower auxiliary note, such
+/// as `c-turn^#_b`. Accidentals are written as `#`, `b`, `x`, `bb` and `n` for
+/// a natural.
+///
+/// ## Grace notes
+///
+/// Grace notes are written before their main note, as a single note or as
+/// several notes in parentheses, which are beamed together. Their duration
+/// defaults to an eighth note, and does not change the duration of the notes
+/// which follow them:
+///
+/// ```text
+/// \acciaccatura d c          acciaccatura, with a slashed stem
+/// \appoggiatura (d[16] e) c  appoggiaturas
+/// ```
+///
+/// ## Octave lines
+///
+/// Octave lines are elements which apply from the following note, chord or
+/// rest, and may continue across barlines:
+///
+/// ```text
+/// \8va    start an 8va line, above notes drawn an octave below their pitch
+/// \8vb    start an 8vb line, below notes drawn an octave above their pitch
+/// \15ma   start a 15ma line, above notes drawn two octaves below their pitch
+/// \15mb   start a 15mb line, below notes drawn two octaves above their pitch
+/// \loco   end an octave line
+/// ```
+///
+/// An octave line also ends at the start of the next one. Pitches are the
+/// pitches which sound, so `\8va c6` is drawn as C5.
+///
+/// ## Pedal marks
+///
+/// Pedal marks are elements which apply from the following note, chord or
+/// rest, and are drawn below the staff:
+///
+/// ```text
+/// \ped              press the sustain pedal
+/// \ped-half         press the sustain pedal halfway
+/// \ped-change       lift the sustain pedal and press it again
+/// \ped-change-half  lift the sustain pedal and press it again halfway
+/// \ped-up           lift the sustain pedal
+/// \una-corda        press the soft pedal
+/// \tre-corde        lift the soft pedal
+/// ```
+///
+/// ## Full-measure rests
+///
+/// `R` is a rest which lasts for the whole measure, whatever its time
+/// signature, and is centered in it. It is a breve rest when the measure lasts
+/// for a breve or longer, such as in 4/2, and a whole rest otherwise. It does
+/// not change the duration of the notes which follow it.
+///
+/// ## Lyrics
+///
+/// A measure may contain verses of lyrics, such as `\lyrics "Twin -- kle"`,
+/// whose syllables are sung in turn on the notes and chords of the measure.
+/// Each `\lyrics` in a measure is a further verse:
+///
+/// ```text
+/// word     a syllable, here a whole word
+/// --       a hyphen between the syllables of a word
+/// __       an extender after the last syllable of a word, held over a melisma
+/// _        a note of a melisma, without a syllable
+/// ```
+///
+/// ## Chord symbols
+///
+/// A chord symbol in quotes, such as `"Cmaj7"` or `"F#m7b5/E"`, is an element
+/// which applies from the following note, chord or rest, and is drawn above
+/// the staff. See [`parse_chord_symbol`] for the notation of chord symbols.
+///
+/// ## Annotations
+///
+/// Tempo markings, expressions and rehearsal marks are elements which apply
+/// from the following note, chord or rest, and are drawn above the staff:
+///
+/// ```text
+/// \tempo "Allegro"           tempo marking, in bold
+/// \tempo [4.] = 60           metronome mark
+/// \tempo "Allegro" [4] = 120 tempo marking with a metronome mark
+/// \text "dolce"              expression, in italics
+/// \mark "A"                  rehearsal mark, in bold in a box
+/// ```
+///
+/// ## Clef
+///
+/// ```text
+/// treble         G clef on the second line
+/// treble_8       G clef, sounding an octave lower
+/// treble^8       G clef, sounding an octave higher
+/// french         G clef on the first line
+/// soprano        C clef on the first line
+/// mezzosoprano   C clef on the second line
+/// alto           C clef on the third line
+/// tenor          C clef on the fourth line
+/// baritone       C clef on the fifth line
+/// bass           F clef on the fourth line
+/// bass_8         F clef, sounding an octave lower
+/// percussion     percussion clef
+/// tab            tablature clef
+/// ```
+///
+/// Pitches are written pitches, so `treble_8 c` is written as middle C and
+/// sounds an octave lower.
+///
+/// ## Clef change
+///
+/// A clef such as `bass` within a measure changes the clef for the notes which
+/// follow it. A clef change at the start of a measure is drawn before the
+/// barline of the previous measure.
+///
+/// ## System breaks
+///
+/// `\break` anywhere in a measure ends the system after the measure, so that
+/// the next measure starts a new system below it with the clef and key
+/// signature in effect. A clef change at the start of the next system is also
+/// drawn before the last barline of the previous system, as a courtesy clef,
+/// and key and time signature changes are also drawn after it, as courtesy
+/// signatures.
+///
+/// ## Navigation
+///
+/// Navigation markers may be written anywhere in a measure. Segno and coda
+/// signs are placed at the start of the measure, and the others at its end:
+///
+/// ```text
+/// \segno      segno sign
+/// \coda       coda sign, at the start of the coda
+/// \tocoda     To Coda
+/// \fine       Fine
+/// \dc         D.C.
+/// \dc-fine    D.C. al Fine
+/// \dc-coda    D.C. al Coda
+/// \ds         D.S.
+/// \ds-fine    D.S. al Fine
+/// \ds-coda    D.S. al Coda
 /// ```
 ///
+/// ## Dynamics
+///
+/// Dynamics and hairpins are elements which apply from the following note,
+/// chord or rest:
+///
+/// ```text
+/// \p \mf \ff ...  dynamics, from \pppppp to \ffffff, and \fp, \pf, \sf, \sfp,
+///                \sfpp, \sfz, \sfzp, \sffz, \fz, \rf, \rfz and \n
+/// \<             start a crescendo
+/// \>             start a diminuendo
+/// \!             end a hairpin
+/// ```
+///
+/// A hairpin also ends at the next dynamic or hairpin.
+///
 /// # Examples
 ///
 /// A staff with a treble clef, key signature with 2 sharps, 4/4 time signature,
@@ -48,6 +295,87 @@ use strum::EnumCount;
 /// use engraver_parser::parse_staff;
 ///
 /// parse_staff("c d e f ||").unwrap();
+/// ```
+///
+/// A note with staccato and accent articulations, and a tenuto chord:
+///
+/// ```
+/// use engraver_parser::parse_staff;
+///
+/// parse_staff("c[8]-.-> {e g}[4.]-- ||").unwrap();
+/// ```
+///
+/// A repeated section with first and second endings:
+///
+/// ```
+/// use engraver_parser::parse_staff;
+///
+/// parse_staff("|: c d e f | 1. g a b c5 :| 2. g4[1] ||").unwrap();
+/// ```
+///
+/// A D.S. al Coda, which returns to the segno and jumps to the coda:
+///
+/// ```
+/// use engraver_parser::parse_staff;
+///
+/// parse_staff(
+///     "c d e f | \\segno g a b c5 | d5 c5 b4 a \\tocoda | g[1] \\ds-coda || \\coda c[1] ||",
+/// )
+/// .unwrap();
+/// ```
+///
+/// A crescendo from piano to forte, crossing a barline:
+///
+/// ```
+/// use engraver_parser::parse_staff;
+///
+/// parse_staff("\\p \\< c d e f | g a b \\f c5 ||").unwrap();
+/// ```
+///
+/// A change from two sharps to one flat and from 4/4 to 3/4 time:
+///
+/// ```
+/// use engraver_parser::parse_staff;
+///
+/// parse_staff("## 4/4 d e f# g | \\key b \\time 3/4 f g a ||").unwrap();
+/// ```
+///
+/// Two verses of lyrics, with a melisma on "star":
+///
+/// ```
+/// use engraver_parser::parse_staff;
+///
+/// parse_staff(
+///     "c c g g \\lyrics \"Twin -- kle, twin -- kle\" \\lyrics \"Up a -- bove the\" | \
+///      a[8] b c5 a4 g[2] \\lyrics \"lit -- tle star __ _ _\" ||",
+/// )
+/// .unwrap();
+/// ```
+///
+/// An acciaccatura, and a group of appoggiaturas beamed as sixteenth notes:
+///
+/// ```
+/// use engraver_parser::parse_staff;
+///
+/// parse_staff("\\acciaccatura d c e \\appoggiatura (f[16] g) a[2] ||").unwrap();
+/// ```
+///
+/// A lead sheet with a chord symbol on each half of the measure:
+///
+/// ```
+/// use engraver_parser::parse_staff;
+///
+/// parse_staff("\"Dm7\" d f \"G7\" g b | \"Cmaj7\" c[1] ||").unwrap();
+/// ```
+///
+/// A rehearsal mark, a tempo marking with a metronome mark, and a ritardando:
+///
+/// ```
+/// use engraver_parser::parse_staff;
+///
+/// parse_staff("\\mark \"A\" \\tempo \"Allegro\" [4] = 120 c d e f | \\text \"rit.\" g a b c5 ||")
+///     .unwrap();
+/// ```
 pub fn parse_staff(input: &'static str) -> Result<model::Staff> {
     let (_, staff) = staff(input).finish()?;
     let staff = staff.into_model(&mut Context::default());
@@ -94,10 +422,12 @@ impl Pitch {
     }
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 struct Note {
     pitch: Pitch,
     duration: Option<Duration>,
+    articulations: Vec<Articulation>,
+    ornament: Option<Ornament>,
 }
 
 impl Note {
@@ -109,6 +439,8 @@ impl Note {
         model::Note {
             pitch: self.pitch.into_model(context),
             duration: context.duration,
+            articulations: self.articulations,
+            ornament: self.ornament,
             id: None,
         }
     }
@@ -118,6 +450,8 @@ impl Note {
 struct Chord {
     pitches: Vec<Pitch>,
     duration: Option<Duration>,
+    articulations: Vec<Articulation>,
+    ornament: Option<Ornament>,
 }
 
 impl Chord {
@@ -133,11 +467,46 @@ impl Chord {
                 .map(|pitch| pitch.into_model(context))
                 .collect(),
             duration: context.duration,
+            articulations: self.articulations,
+            ornament: self.ornament,
             id: None,
         }
     }
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Grace {
+    kind: grace::Kind,
+    notes: Vec<Note>,
+}
+
+impl Grace {
+    /// Converts the grace notes, which default to eighth notes and leave the
+    /// duration of the notes after them unchanged.
+    fn into_model(self, context: &mut Context) -> model::Grace {
+        let duration = mem::replace(
+            &mut context.duration,
+            Duration {
+                value: duration::Value::Eighth,
+                dots: None,
+            },
+        );
+
+        let notes = self
+            .notes
+            .into_iter()
+            .map(|note| note.into_model(context))
+            .collect();
+
+        context.duration = duration;
+
+        model::Grace {
+            kind: self.kind,
+            notes,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 struct Rest {
     duration: Option<Duration>,
@@ -161,6 +530,15 @@ enum Element {
     Note(Note),
     Chord(Chord),
     Rest(Rest),
+    FullMeasureRest,
+    Dynamic(Dynamic),
+    Hairpin(Hairpin),
+    OctaveLine(OctaveLine),
+    Pedal(Pedal),
+    Clef(Clef),
+    Grace(Grace),
+    ChordSymbol(model::ChordSymbol),
+    Annotation(Annotation),
 }
 
 impl Element {
@@ -169,31 +547,57 @@ impl Element {
             Self::Note(note) => model::measure::Element::Note(note.into_model(context)),
             Self::Chord(chord) => model::measure::Element::Chord(chord.into_model(context)),
             Self::Rest(note) => model::measure::Element::Rest(note.into_model(context)),
+            Self::FullMeasureRest => model::measure::Element::FullMeasureRest,
+            Self::Dynamic(dynamic) => model::measure::Element::Dynamic(dynamic),
+            Self::Hairpin(hairpin) => model::measure::Element::Hairpin(hairpin),
+            Self::OctaveLine(octave_line) => model::measure::Element::OctaveLine(octave_line),
+            Self::Pedal(pedal) => model::measure::Element::Pedal(pedal),
+            Self::Clef(clef) => model::measure::Element::Clef(clef),
+            Self::Grace(grace) => model::measure::Element::Grace(grace.into_model(context)),
+            Self::ChordSymbol(chord_symbol) => model::measure::Element::ChordSymbol(chord_symbol),
+            Self::Annotation(annotation) => model::measure::Element::Annotation(annotation),
         }
     }
 }
 
 #[derive(Clone, Debug, Default, PartialEq)]
 struct Measure {
+    start_barline: Option<Barline>,
+    ending: Option<Vec<u8>>,
+    key_signature: Option<Option<KeySignature>>,
+    time_signature: Option<TimeSignature>,
     elements: Vec<Element>,
+    navigation: Vec<Navigation>,
+    lyrics: Vec<Vec<Lyric>>,
     barline: Barline,
+    /// Whether the next measure starts with a start repeat.
+    is_followed_by_start_repeat: bool,
+    system_break: bool,
 }
 
 impl Measure {
     fn into_model(self, context: &mut Context) -> model::Measure {
         model::Measure {
+            start_barline: self.start_barline,
+            key_signature: self.key_signature,
+            time_signature: self.time_signature,
+            ending: self.ending.map(|numbers| Ending { numbers }),
+            navigation: self.navigation,
+            lyrics: self.lyrics,
             elements: self
                 .elements
                 .into_iter()
                 .map(|element| element.into_model(context))
                 .collect(),
             barline: self.barline,
+            system_break: self.system_break,
         }
     }
 }
 
 #[derive(Clone, Debug, PartialEq)]
 struct Staff {
+    geometry: StaffGeometry,
     clef: Option<Clef>,
     key_signature: Option<KeySignature>,
     time_signature: Option<TimeSignature>,
@@ -203,9 +607,11 @@ struct Staff {
 impl Staff {
     fn into_model(self, context: &mut Context) -> model::Staff {
         model::Staff {
+            geometry: self.geometry,
             clef: self.clef.unwrap_or_default(),
             time_signature: self.time_signature,
             key_signature: self.key_signature,
+            key_cancellation: Default::default(),
             measures: self
                 .measures
                 .into_iter()
@@ -263,16 +669,17 @@ fn pitch(input: &str) -> IResult<&str, Pitch> {
 }
 
 fn value(input: &str) -> IResult<&str, duration::Value> {
+    // Longer values are tried first, so that `16` is not parsed as `1`.
     alt((
+        tag("256").map(|_| duration::Value::TwoHundredFiftySixth),
+        tag("128").map(|_| duration::Value::OneHundredTwentyEighth),
+        tag("64").map(|_| duration::Value::SixtyFourth),
+        tag("32").map(|_| duration::Value::ThirtySecond),
+        tag("16").map(|_| duration::Value::Sixteenth),
         char('1').map(|_| duration::Value::Whole),
         char('2').map(|_| duration::Value::Half),
         char('4').map(|_| duration::Value::Quarter),
         char('8').map(|_| duration::Value::Eighth),
-        tag("16").map(|_| duration::Value::Sixteenth),
-        tag("32").map(|_| duration::Value::ThirtySecond),
-        tag("64").map(|_| duration::Value::SixtyFourth),
-        tag("128").map(|_| duration::Value::OneHundredTwentyEighth),
-        tag("256").map(|_| duration::Value::TwoHundredFiftySixth),
     ))(input)
 }
 
@@ -293,19 +700,74 @@ fn bracketed_duration(input: &str) -> IResult<&str, Duration> {
     delimited(char('['), duration, char(']'))(input)
 }
 
+fn articulation(input: &str) -> IResult<&str, Articulation> {
+    alt((
+        tag("-.").map(|_| Articulation::Staccato),
+        tag("-!").map(|_| Articulation::Staccatissimo),
+        tag("--").map(|_| Articulation::Tenuto),
+        tag("-'").map(|_| Articulation::Stress),
+        tag("->").map(|_| Articulation::Accent),
+        tag("-^").map(|_| Articulation::Marcato),
+    ))(input)
+}
+
+fn ornament(input: &str) -> IResult<&str, Ornament> {
+    let accidental = || alt((alteration, char('n').map(|_| Alteration::Natural)));
+
+    let (input, kind) = alt((
+        tag("-tr~").map(|_| ornament::Kind::Trill { extended: true }),
+        tag("-tr").map(|_| ornament::Kind::Trill { extended: false }),
+        tag("-mor").map(|_| ornament::Kind::Mordent),
+        tag("-prall").map(|_| ornament::Kind::InvertedMordent),
+        tag("-turn").map(|_| ornament::Kind::Turn),
+        tag("-rturn").map(|_| ornament::Kind::InvertedTurn),
+    ))(input)?;
+    let (input, accidental_above) = opt(preceded(char('^'), accidental()))(input)?;
+    let (input, accidental_below) = opt(preceded(char('_'), accidental()))(input)?;
+
+    Ok((
+        input,
+        Ornament {
+            kind,
+            accidental_above,
+            accidental_below,
+        },
+    ))
+}
+
 fn note(input: &str) -> IResult<&str, Note> {
     let (input, pitch) = pitch(input)?;
     let (input, duration) = opt(bracketed_duration)(input)?;
+    let (input, articulations) = many0(articulation)(input)?;
+    let (input, ornament) = opt(ornament)(input)?;
 
-    Ok((input, Note { pitch, duration }))
+    Ok((
+        input,
+        Note {
+            pitch,
+            duration,
+            articulations,
+            ornament,
+        },
+    ))
 }
 
 fn chord(input: &str) -> IResult<&str, Chord> {
     let (input, pitches) =
         delimited(char('{'), separated_list1(char(' '), pitch), char('}'))(input)?;
     let (input, duration) = opt(bracketed_duration)(input)?;
+    let (input, articulations) = many0(articulation)(input)?;
+    let (input, ornament) = opt(ornament)(input)?;
 
-    Ok((input, Chord { pitches, duration }))
+    Ok((
+        input,
+        Chord {
+            pitches,
+            duration,
+            articulations,
+            ornament,
+        },
+    ))
 }
 
 fn rest(input: &str) -> IResult<&str, Rest> {
@@ -315,47 +777,315 @@ fn rest(input: &str) -> IResult<&str, Rest> {
     Ok((input, Rest { duration }))
 }
 
-fn barline(input: &str) -> IResult<&str, Barline> {
+pub(crate) fn dynamic(input: &str) -> IResult<&str, Dynamic> {
+    map_opt(preceded(char('\\'), alpha1), |name| {
+        let dynamic = match name {
+            "pppppp" => Dynamic::Pppppp,
+            "ppppp" => Dynamic::Ppppp,
+            "pppp" => Dynamic::Pppp,
+            "ppp" => Dynamic::Ppp,
+            "pp" => Dynamic::Pp,
+            "p" => Dynamic::P,
+            "mp" => Dynamic::Mp,
+            "mf" => Dynamic::Mf,
+            "f" => Dynamic::F,
+            "ff" => Dynamic::Ff,
+            "fff" => Dynamic::Fff,
+            "ffff" => Dynamic::Ffff,
+            "fffff" => Dynamic::Fffff,
+            "ffffff" => Dynamic::Ffffff,
+            "fp" => Dynamic::Fp,
+            "pf" => Dynamic::Pf,
+            "sf" => Dynamic::Sf,
+            "sfp" => Dynamic::Sfp,
+            "sfpp" => Dynamic::Sfpp,
+            "sfz" => Dynamic::Sfz,
+            "sfzp" => Dynamic::Sfzp,
+            "sffz" => Dynamic::Sffz,
+            "fz" => Dynamic::Fz,
+            "rf" => Dynamic::Rf,
+            "rfz" => Dynamic::Rfz,
+            "n" => Dynamic::N,
+            _ => return None,
+        };
+
+        Some(dynamic)
+    })(input)
+}
+
+pub(crate) fn hairpin(input: &str) -> IResult<&str, Hairpin> {
+    alt((
+        tag("\\<").map(|_| Hairpin::Start(hairpin::Kind::Crescendo)),
+        tag("\\>").map(|_| Hairpin::Start(hairpin::Kind::Diminuendo)),
+        tag("\\!").map(|_| Hairpin::End),
+    ))(input)
+}
+
+/// Parses a barline, and whether the next measure starts with a start repeat.
+fn barline(input: &str) -> IResult<&str, (Barline, bool)> {
+    let named = |name, barline: Barline| {
+        delimited(tag("|["), tag(name), char(']')).map(move |_| barline.clone())
+    };
+
+    alt((
+        tag(":|:").map(|_| (Barline::EndStartRepeat, false)),
+        tag(":|").map(|_| (Barline::EndRepeat, false)),
+        tag("||").map(|_| (Barline::Final, false)),
+        tag("|:").map(|_| (Barline::Thin, true)),
+        alt((
+            named("double", Barline::Double),
+            named("heavy", Barline::Heavy),
+            named("dashed", Barline::Dashed),
+            named("dotted", Barline::Dotted),
+            named("invisible", Barline::Invisible),
+        ))
+        .map(|barline| (barline, false)),
+        char('|').map(|_| (Barline::Thin, false)),
+    ))(input)
+}
+
+fn navigation(input: &str) -> IResult<&str, Navigation> {
+    use navigation::Target;
+
     alt((
-        tag("||").map(|_| Barline::Final),
-        char('|').map(|_| Barline::Thin),
+        tag("\\segno").map(|_| Navigation::Segno),
+        tag("\\coda").map(|_| Navigation::Coda),
+        tag("\\tocoda").map(|_| Navigation::ToCoda),
+        tag("\\fine").map(|_| Navigation::Fine),
+        tag("\\dc-fine").map(|_| Navigation::DaCapo(Some(Target::Fine))),
+        tag("\\dc-coda").map(|_| Navigation::DaCapo(Some(Target::Coda))),
+        tag("\\dc").map(|_| Navigation::DaCapo(None)),
+        tag("\\ds-fine").map(|_| Navigation::DalSegno(Some(Target::Fine))),
+        tag("\\ds-coda").map(|_| Navigation::DalSegno(Some(Target::Coda))),
+        tag("\\ds").map(|_| Navigation::DalSegno(None)),
     ))(input)
 }
 
+/// Parses a text in quotes, which may be empty.
+pub(crate) fn quoted(input: &str) -> IResult<&str, &str> {
+    delimited(char('"'), opt(is_not("\"")), char('"'))
+        .map(Option::unwrap_or_default)
+        .parse(input)
+}
+
+/// Parses a verse of lyrics in quotes after `\lyrics`.
+fn verse(input: &str) -> IResult<&str, Vec<Lyric>> {
+    preceded(pair(tag("\\lyrics"), multispace0), quoted)
+        .map(lyrics)
+        .parse(input)
+}
+
+/// Splits lyrics into syllables separated by whitespace, where `--` is a
+/// hyphen between the syllables of a word, `__` an extender after a syllable
+/// and `_` a note of a melisma without a syllable.
+pub(crate) fn lyrics(text: &str) -> Vec<Lyric> {
+    let mut lyrics = vec![];
+
+    for word in text.split_whitespace() {
+        let connector = match word {
+            "--" => lyric::Connector::Hyphen,
+            "__" => lyric::Connector::Extender,
+            "_" => {
+                lyrics.push(Lyric::Melisma);
+                continue;
+            }
+            text => {
+                lyrics.push(Lyric::Syllable(lyric::Syllable {
+                    text: text.to_owned(),
+                    connector: None,
+                }));
+                continue;
+            }
+        };
+
+        if let Some(Lyric::Syllable(syllable)) = lyrics.last_mut() {
+            syllable.connector = Some(connector);
+        }
+    }
+
+    lyrics
+}
+
+fn ending(input: &str) -> IResult<&str, Vec<u8>> {
+    terminated(separated_list1(char(','), u8), char('.'))(input)
+}
+
 fn element(input: &str) -> IResult<&str, Element> {
     alt((
+        clef.map(Element::Clef),
         note.map(Element::Note),
         chord.map(Element::Chord),
         rest.map(Element::Rest),
+        char('R').map(|_| Element::FullMeasureRest),
+        grace.map(Element::Grace),
+        octave_line.map(Element::OctaveLine),
+        pedal.map(Element::Pedal),
+        dynamic.map(Element::Dynamic),
+        hairpin.map(Element::Hairpin),
+        delimited(char('"'), chord_symbol::chord_symbol, char('"')).map(Element::ChordSymbol),
+        annotation.map(Element::Annotation),
+    ))(input)
+}
+
+fn octave_line(input: &str) -> IResult<&str, OctaveLine> {
+    alt((
+        tag("\\8va").map(|_| OctaveLine::Start(octave_line::Kind::OctaveAbove)),
+        tag("\\8vb").map(|_| OctaveLine::Start(octave_line::Kind::OctaveBelow)),
+        tag("\\15ma").map(|_| OctaveLine::Start(octave_line::Kind::TwoOctavesAbove)),
+        tag("\\15mb").map(|_| OctaveLine::Start(octave_line::Kind::TwoOctavesBelow)),
+        tag("\\loco").map(|_| OctaveLine::End),
     ))(input)
 }
 
+fn pedal(input: &str) -> IResult<&str, Pedal> {
+    alt((
+        tag("\\ped-change-half").map(|_| Pedal::Change(pedal::Depth::Half)),
+        tag("\\ped-change").map(|_| Pedal::Change(pedal::Depth::Full)),
+        tag("\\ped-half").map(|_| Pedal::Down(pedal::Depth::Half)),
+        tag("\\ped-up").map(|_| Pedal::Up),
+        tag("\\ped").map(|_| Pedal::Down(pedal::Depth::Full)),
+        tag("\\una-corda").map(|_| Pedal::UnaCorda),
+        tag("\\tre-corde").map(|_| Pedal::TreCorde),
+    ))(input)
+}
+
+/// Parses a tempo marking, expression or rehearsal mark.
+fn annotation(input: &str) -> IResult<&str, Annotation> {
+    alt((
+        preceded(pair(tag("\\tempo"), multispace0), tempo).map(Annotation::Tempo),
+        preceded(pair(tag("\\text"), multispace0), quoted)
+            .map(|text| Annotation::Expression(text.to_owned())),
+        preceded(pair(tag("\\mark"), multispace0), quoted)
+            .map(|text| Annotation::RehearsalMark(text.to_owned())),
+    ))(input)
+}
+
+/// Parses the text of a tempo marking in quotes, a metronome mark such as
+/// `[4.] = 60`, or both.
+fn tempo(input: &str) -> IResult<&str, annotation::Tempo> {
+    verify(
+        pair(opt(quoted), opt(preceded(multispace0, metronome_mark))).map(
+            |(text, metronome_mark)| annotation::Tempo {
+                text: text.map(str::to_owned),
+                metronome_mark,
+            },
+        ),
+        |tempo| tempo.text.is_some() || tempo.metronome_mark.is_some(),
+    )(input)
+}
+
+fn metronome_mark(input: &str) -> IResult<&str, annotation::MetronomeMark> {
+    pair(bracketed_duration, preceded(ws(char('=')), u16))
+        .map(|(beat, per_minute)| annotation::MetronomeMark { beat, per_minute })
+        .parse(input)
+}
+
+/// Parses grace notes, which are a single note or notes in parentheses.
+fn grace(input: &str) -> IResult<&str, Grace> {
+    let (input, kind) = terminated(
+        alt((
+            tag("\\acciaccatura").map(|_| grace::Kind::Acciaccatura),
+            tag("\\appoggiatura").map(|_| grace::Kind::Appoggiatura),
+        )),
+        multispace0,
+    )(input)?;
+    let (input, notes) = alt((
+        delimited(char('('), ws(separated_list1(multispace1, note)), char(')')),
+        note.map(|note| vec![note]),
+    ))(input)?;
+
+    Ok((input, Grace { kind, notes }))
+}
+
 fn measure(input: &str) -> IResult<&str, Measure> {
-    let (input, elements) = many0(ws(element))(input)?;
-    let (input, barline) = ws(barline)(input)?;
+    enum Item {
+        Element(Element),
+        Navigation(Navigation),
+        Lyrics(Vec<Lyric>),
+        Break,
+    }
+
+    let (input, ending) = opt(ws(ending))(input)?;
+    let (input, key_signature) = opt(ws(key_change))(input)?;
+    let (input, time_signature) = opt(ws(time_change))(input)?;
+    let (input, (elements, navigation, lyrics, system_break)) = fold_many0(
+        ws(alt((
+            element.map(Item::Element),
+            navigation.map(Item::Navigation),
+            verse.map(Item::Lyrics),
+            tag("\\break").map(|_| Item::Break),
+        ))),
+        || (vec![], vec![], vec![], false),
+        |(mut elements, mut navigation, mut lyrics, mut system_break), item| {
+            match item {
+                Item::Element(element) => elements.push(element),
+                Item::Navigation(marker) => navigation.push(marker),
+                Item::Lyrics(verse) => lyrics.push(verse),
+                Item::Break => system_break = true,
+            }
+            (elements, navigation, lyrics, system_break)
+        },
+    )(input)?;
+    let (input, (barline, is_followed_by_start_repeat)) = ws(barline)(input)?;
 
-    Ok((input, Measure { elements, barline }))
+    Ok((
+        input,
+        Measure {
+            start_barline: None,
+            ending,
+            key_signature,
+            time_signature,
+            elements,
+            navigation,
+            lyrics,
+            barline,
+            is_followed_by_start_repeat,
+            system_break,
+        },
+    ))
 }
 
 fn clef(input: &str) -> IResult<&str, Clef> {
     alt((
+        tag("treble_8").map(|_| Clef::Treble8vb),
+        tag("treble^8").map(|_| Clef::Treble8va),
         tag("treble").map(|_| Clef::Treble),
+        tag("french").map(|_| Clef::FrenchViolin),
+        tag("soprano").map(|_| Clef::Soprano),
+        tag("mezzosoprano").map(|_| Clef::MezzoSoprano),
         tag("alto").map(|_| Clef::Alto),
         tag("tenor").map(|_| Clef::Tenor),
+        tag("baritone").map(|_| Clef::Baritone),
+        tag("bass_8").map(|_| Clef::Bass8vb),
         tag("bass").map(|_| Clef::Bass),
+        tag("percussion").map(|_| Clef::Percussion),
+        tag("tab").map(|_| Clef::Tab),
     ))(input)
 }
 
 fn time_signature(input: &str) -> IResult<&str, TimeSignature> {
-    let (input, (numerator, denominator)) = separated_pair(u8, char('/'), u8)(input)?;
+    alt((
+        tag("C|").map(|_| TimeSignature::Cut),
+        tag("C").map(|_| TimeSignature::Common),
+        separated_list1(multispace1, meter).map(TimeSignature::Meters),
+    ))(input)
+}
 
-    Ok((
-        input,
-        TimeSignature {
-            numerator,
-            denominator,
-        },
-    ))
+/// Parses a meter such as `3/4`, `3+2+2/8`, `X/[4.]` or a single number, which
+/// is not followed by `.` so that it is not mistaken for an ending.
+fn meter(input: &str) -> IResult<&str, time_signature::Meter> {
+    let beats = alt((
+        char('X').map(|_| time_signature::Beats::X),
+        separated_list1(char('+'), u8).map(time_signature::Beats::Numbers),
+    ));
+    let unit = alt((
+        bracketed_duration.map(time_signature::Unit::Note),
+        u8.map(time_signature::Unit::Number),
+    ));
+    let (input, (beats, unit)) =
+        terminated(pair(beats, opt(preceded(char('/'), unit))), not(char('.')))(input)?;
+
+    Ok((input, time_signature::Meter { beats, unit }))
 }
 
 fn key_signature(input: &str) -> IResult<&str, KeySignature> {
@@ -370,15 +1100,53 @@ fn key_signature(input: &str) -> IResult<&str, KeySignature> {
     ))(input)
 }
 
+/// Parses a key signature change, which is `None` for no sharps or flats.
+fn key_change(input: &str) -> IResult<&str, Option<KeySignature>> {
+    preceded(
+        pair(tag("\\key"), multispace1),
+        alt((char('0').map(|_| None), key_signature.map(Some))),
+    )(input)
+}
+
+fn time_change(input: &str) -> IResult<&str, TimeSignature> {
+    preceded(pair(tag("\\time"), multispace1), time_signature)(input)
+}
+
+fn staff_geometry(input: &str) -> IResult<&str, StaffGeometry> {
+    let default = StaffGeometry::default();
+
+    let (input, lines) = opt(ws(preceded(pair(tag("\\lines"), multispace1), u8)))(input)?;
+    let (input, scale) = opt(ws(preceded(pair(tag("\\scale"), multispace1), double)))(input)?;
+
+    Ok((
+        input,
+        StaffGeometry {
+            lines: lines.unwrap_or(default.lines),
+            scale: scale.unwrap_or(default.scale),
+        },
+    ))
+}
+
 fn staff(input: &str) -> IResult<&str, Staff> {
+    let (input, geometry) = staff_geometry(input)?;
     let (input, clef) = opt(ws(clef))(input)?;
     let (input, key_signature) = opt(ws(key_signature))(input)?;
     let (input, time_signature) = opt(ws(time_signature))(input)?;
-    let (input, measures) = ws(many0(measure))(input)?;
+    let (input, starts_with_repeat) = opt(ws(tag("|:")))(input)?;
+    let (input, mut measures) = ws(many0(measure))(input)?;
+
+    let mut is_start_repeat = starts_with_repeat.is_some();
+    for measure in &mut measures {
+        if is_start_repeat {
+            measure.start_barline = Some(Barline::StartRepeat);
+        }
+        is_start_repeat = measure.is_followed_by_start_repeat;
+    }
 
     Ok((
         input,
         Staff {
+            geometry,
             clef,
             key_signature,
             time_signature,
@@ -0,0 +1,885 @@
+use color_eyre::eyre::{bail, eyre, Result};
+use engraver::model::{
+    self, annotation, duration, grace, octave_line, ornament, pedal, time_signature, Alteration,
+    Annotation, Articulation, Barline, Clef, Duration, KeySignature, Lyric, OctaveLine, Ornament,
+    Pedal, Step, TimeSignature,
+};
+use nom::{
+    branch::alt,
+    bytes::complete::{is_not, tag},
+    character::complete::{char, i8, multispace0, multispace1, one_of, u16, u8},
+    combinator::{eof, map_opt, opt, verify},
+    multi::{fold_many_m_n, many0, many1, separated_list1},
+    sequence::{delimited, pair, preceded, separated_pair, terminated, tuple},
+    Finish, IResult, Parser,
+};
+use strum::IntoEnumIterator;
+
+use super::{dynamic, hairpin, lyrics, quoted, ws};
+
+/// Parses a subset of [LilyPond](https://lilypond.org/) input into a
+/// [`engraver::model::Staff`].
+///
+/// # Format
+///
+/// The music is a sequence expression `{ ... }`, optionally preceded by a
+/// `\version` statement and `\relative` with an optional starting pitch. In
+/// relative mode, each note is placed in the octave closest to the previous
+/// note, the first note of a chord, or the starting pitch. Without a starting
+/// pitch, the first note is absolute.
+///
+/// The music may contain:
+///
+/// - Notes with Dutch pitch names, such as `c`, `fis`, `bes` and `eeses`,
+///   followed by octave marks `'` and `,` relative to `c` (C3).
+/// - Durations such as `4`, `8.` and `2..`, which apply to the following notes
+///   until another duration is given, starting with a quarter note.
+/// - Chords such as `<c e g>4`.
+/// - Rests `r`, and full-measure rests `R` such as `R1` or `R2.`, which are
+///   centered in their measure. Their duration is otherwise ignored, and
+///   multipliers such as `R1*4` are not supported.
+/// - Articulations `-.`, `-!`, `--`, `->`, `-^` and `-_`, which may also be
+///   written with `^` or `_` in place of `-`, though their direction is
+///   ignored.
+/// - Dynamics such as `\p`, `\mf` and `\sfz`, and hairpins `\<`, `\>` and `\!`,
+///   after notes, chords and rests.
+/// - Grace notes `\acciaccatura` and `\slashedGrace`, which are slashed, and
+///   `\appoggiatura` and `\grace`, followed by a note or notes in braces, such
+///   as `\acciaccatura d8 c4` or `\grace { d16 e }`. Dynamics and hairpins
+///   after grace notes are ignored.
+/// - Octave lines `\ottava #1`, `#-1`, `#2` and `#-2` for 8va, 8vb, 15ma and
+///   15mb, which end at `\ottava #0`. The notes under them keep their sounding
+///   pitches and are drawn an octave or two away from them.
+/// - Pedal marks `\sustainOn`, `\sustainOff`, `\unaCorda` and `\treCorde` after
+///   notes, chords and rests, where `\sustainOff\sustainOn` is a pedal change.
+/// - Ornaments `\trill`, `\mordent`, `\prall`, `\turn` and `\reverseturn` after
+///   notes and chords. `\startTrillSpan` is a trill with a wavy line until the
+///   next note, chord or rest, so `\stopTrillSpan` is ignored.
+/// - Tempo markings such as `\tempo "Allegro" 4 = 120`, with text in quotes, a
+///   metronome mark, or both.
+/// - Rehearsal marks `\mark "A"`, or `\mark \default` for the letters `A`, `B`
+///   and so on, skipping `I`, which marks with text do not affect.
+/// - `\clef` with a clef such as `treble`, `"treble_8"`, `bass`, `alto`,
+///   `soprano`, `percussion` or `tab`, which changes the clef after the first
+///   note. Pitches are written an octave away from their sounding pitch with
+///   octave clefs.
+/// - `\key` (with `\major`, `\minor` or another mode) and `\time`, which change
+///   the key and time signatures after the first note, but only at the start of
+///   a measure.
+/// - Bar checks `|`, which end measures with a thin barline, and `\bar "|"`,
+///   `\bar "||"`, `\bar "|."`, `\bar "."`, `\bar "!"`, `\bar ";"`, `\bar ""`,
+///   `\bar ":|."` and `\bar ":..:"`, which end measures with the given barline.
+///
+/// The music may be followed by verses of lyrics such as
+/// `\addlyrics { Twin -- kle __ _ }`, whose syllables are sung in turn on the
+/// notes and chords, with `--` for hyphens, `__` for extenders and `_` for
+/// notes without syllables.
+///
+/// Ties `~` and slurs `(` and `)` are errors, since the staff has no way to
+/// represent them, and the notes they join would otherwise lose their melisma
+/// in the lyrics and be played as separate notes.
+///
+/// Comments starting with `%` are ignored.
+///
+/// # Examples
+///
+/// ```
+/// use engraver_parser::{parse_lilypond, parse_staff};
+///
+/// let staff = parse_lilypond(
+///     r#"\version "2.24.0"
+///        \relative c' {
+///          \clef treble \key d \major \time 4/4
+///          d4 e fis g | a b cis cis | <d fis a>1 \bar "|."
+///        }"#,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(
+///     staff,
+///     parse_staff("treble ## 4/4 d4 e f# g | a b c#5 c# | {d f# a}[1] ||").unwrap()
+/// );
+/// ```
+pub fn parse_lilypond(input: &str) -> Result<model::Staff> {
+    let input: String = input
+        .lines()
+        .map(|line| line.split('%').next().unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let (_, ((relative, items), verses)) =
+        terminated(pair(ws(score), many0(ws(verse))), eof)(&input)
+            .finish()
+            .map_err(|error| match error.input.trim_start().chars().next() {
+                Some('~') => eyre!("LilyPond ties are not supported"),
+                Some('(' | ')') => eyre!("LilyPond slurs are not supported"),
+                _ => eyre!("failed to parse LilyPond input at {:?}", error.input),
+            })?;
+
+    let mut context = Context {
+        relative: relative.map(|start| start.map(|pitch| pitch.absolute())),
+        ..Default::default()
+    };
+    let mut staff = model::Staff {
+        geometry: Default::default(),
+        clef: Clef::default(),
+        key_signature: None,
+        time_signature: None,
+        key_cancellation: Default::default(),
+        measures: vec![],
+    };
+    let mut elements = vec![];
+    let mut num_marks = 0;
+    let mut is_ottava = false;
+    // The key and time signature changes at the start of the next measure.
+    let mut changes = model::Measure::default();
+
+    for item in items {
+        let has_started = !elements.is_empty() || !staff.measures.is_empty();
+
+        match item {
+            Item::Key(_) | Item::Time(_) if !elements.is_empty() => {
+                bail!("LilyPond \\key and \\time are only supported at the start of a measure")
+            }
+            Item::Clef(clef) if has_started => {
+                context.clef = clef;
+                elements.push(model::measure::Element::Clef(clef));
+            }
+            Item::Clef(clef) => {
+                context.clef = clef;
+                staff.clef = clef;
+            }
+            Item::Key(fifths) => {
+                if !(-7..=7).contains(&fifths) {
+                    bail!("LilyPond key has more than 7 sharps or flats");
+                }
+
+                if has_started {
+                    changes.key_signature = Some(KeySignature::from_fifths(fifths));
+                } else {
+                    staff.key_signature = KeySignature::from_fifths(fifths);
+                }
+            }
+            Item::Time(time_signature) if has_started => {
+                changes.time_signature = Some(time_signature)
+            }
+            Item::Time(time_signature) => staff.time_signature = Some(time_signature),
+            Item::BarCheck if elements.is_empty() => {}
+            Item::BarCheck => staff.measures.push(model::Measure {
+                elements: std::mem::take(&mut elements),
+                barline: Barline::Thin,
+                ..std::mem::take(&mut changes)
+            }),
+            Item::Bar(barline) => match staff.measures.last_mut() {
+                Some(measure) if elements.is_empty() => measure.barline = barline,
+                _ => staff.measures.push(model::Measure {
+                    elements: std::mem::take(&mut elements),
+                    barline,
+                    ..std::mem::take(&mut changes)
+                }),
+            },
+            Item::Grace(kind, notes) => {
+                let notes = notes
+                    .into_iter()
+                    .flat_map(|note| note.into_model(&mut context))
+                    .filter_map(|element| match element {
+                        model::measure::Element::Note(note) => Some(note),
+                        _ => None,
+                    })
+                    .collect();
+
+                elements.push(model::measure::Element::Grace(model::Grace { kind, notes }));
+            }
+            Item::Ottava(kind) => {
+                // `\ottava #0` may be written when no octave line is in
+                // progress, which there is nothing to end.
+                if kind.is_some() || is_ottava {
+                    elements.push(model::measure::Element::OctaveLine(
+                        kind.map_or(OctaveLine::End, OctaveLine::Start),
+                    ));
+                }
+                is_ottava = kind.is_some();
+            }
+            Item::Tempo(tempo) => elements.push(model::measure::Element::Annotation(
+                Annotation::Tempo(tempo),
+            )),
+            Item::Mark(text) => {
+                let text = text.unwrap_or_else(|| {
+                    num_marks += 1;
+                    mark_letters(num_marks - 1)
+                });
+
+                elements.push(model::measure::Element::Annotation(
+                    Annotation::RehearsalMark(text),
+                ));
+            }
+            Item::Break => match staff.measures.last_mut() {
+                Some(measure) if elements.is_empty() => measure.system_break = true,
+                _ => changes.system_break = true,
+            },
+            Item::Element(element) => elements.append(&mut element.into_model(&mut context)),
+        }
+    }
+
+    if !elements.is_empty() {
+        staff.measures.push(model::Measure {
+            elements,
+            ..changes
+        });
+    }
+
+    for verse in verses {
+        add_verse(&mut staff, verse);
+    }
+
+    Ok(staff)
+}
+
+/// Adds a verse of lyrics to the measures of the staff, where each measure
+/// takes the lyrics of its notes and chords.
+fn add_verse(staff: &mut model::Staff, verse: Vec<Lyric>) {
+    let mut verse = verse.into_iter();
+
+    for measure in &mut staff.measures {
+        let num_sung = measure
+            .elements
+            .iter()
+            .filter(|element| {
+                matches!(
+                    element,
+                    model::measure::Element::Note(_) | model::measure::Element::Chord(_)
+                )
+            })
+            .count();
+
+        measure.lyrics.push(verse.by_ref().take(num_sung).collect());
+    }
+}
+
+#[derive(Debug)]
+struct Context {
+    /// The pitch to which the next note is relative, which is `None` outside
+    /// relative mode, or `Some(None)` if the next note is absolute.
+    relative: Option<Option<model::Pitch>>,
+    duration: Duration,
+    /// The current clef, since LilyPond pitches are sounding pitches, which
+    /// are written an octave away with octave clefs.
+    clef: Clef,
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Self {
+            relative: None,
+            duration: Duration {
+                value: duration::Value::Quarter,
+                dots: None,
+            },
+            clef: Clef::default(),
+        }
+    }
+}
+
+impl Context {
+    /// Returns the written pitch of a sounding pitch with the current clef.
+    fn written(&self, pitch: model::Pitch) -> model::Pitch {
+        model::Pitch {
+            octave: pitch.octave - self.clef.octave_shift(),
+            ..pitch
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Pitch {
+    step: Step,
+    alteration: Alteration,
+    /// The number of octaves raised by `'` or lowered by `,`.
+    octave_marks: i8,
+}
+
+impl Pitch {
+    fn absolute(self) -> model::Pitch {
+        model::Pitch {
+            step: self.step,
+            alteration: self.alteration,
+            octave: 3 + self.octave_marks,
+        }
+    }
+
+    /// Returns the pitch in the octave closest to `previous`, ignoring
+    /// alterations, then moved by the octave marks.
+    fn relative_to(self, previous: model::Pitch) -> model::Pitch {
+        let previous = previous.octave as i32 * 7 + step_index(previous.step);
+        let mut position = previous - previous.rem_euclid(7) + step_index(self.step);
+
+        if position - previous > 3 {
+            position -= 7;
+        } else if previous - position > 3 {
+            position += 7;
+        }
+
+        model::Pitch {
+            step: self.step,
+            alteration: self.alteration,
+            octave: (position.div_euclid(7) + self.octave_marks as i32) as i8,
+        }
+    }
+
+    fn into_model(self, context: &mut Context) -> model::Pitch {
+        match &mut context.relative {
+            None => self.absolute(),
+            Some(previous) => {
+                let pitch = match previous {
+                    Some(previous) => self.relative_to(*previous),
+                    None => self.absolute(),
+                };
+                *previous = Some(pitch);
+                pitch
+            }
+        }
+    }
+}
+
+fn step_index(step: Step) -> i32 {
+    match step {
+        Step::C => 0,
+        Step::D => 1,
+        Step::E => 2,
+        Step::F => 3,
+        Step::G => 4,
+        Step::A => 5,
+        Step::B => 6,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Element {
+    Note(Pitch, Option<Duration>, PostEvents),
+    Chord(Vec<Pitch>, Option<Duration>, PostEvents),
+    Rest(Option<Duration>, PostEvents),
+    FullMeasureRest(Option<Duration>, PostEvents),
+}
+
+/// The articulations, ornament, dynamics and hairpins written after a note,
+/// chord or rest.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct PostEvents {
+    articulations: Vec<Articulation>,
+    /// The ornament of a note or chord, which is ignored after a rest.
+    ornament: Option<Ornament>,
+    /// The dynamics and hairpins, which are placed before the element in the
+    /// staff since they apply from it.
+    directions: Vec<model::measure::Element>,
+}
+
+impl Element {
+    /// Returns the element in the staff, preceded by its dynamics and hairpins.
+    fn into_model(self, context: &mut Context) -> Vec<model::measure::Element> {
+        let (duration, post_events) = match &self {
+            Self::Note(_, duration, post_events)
+            | Self::Chord(_, duration, post_events)
+            | Self::Rest(duration, post_events)
+            | Self::FullMeasureRest(duration, post_events) => (*duration, post_events.clone()),
+        };
+        if let Some(duration) = duration {
+            context.duration = duration;
+        }
+
+        let articulations = post_events.articulations;
+        let ornament = post_events.ornament;
+        let element = match self {
+            Self::Note(pitch, ..) => model::measure::Element::Note(model::Note {
+                pitch: {
+                    let pitch = pitch.into_model(context);
+                    context.written(pitch)
+                },
+                duration: context.duration,
+                articulations,
+                ornament,
+                id: None,
+            }),
+            Self::Chord(pitches, ..) => {
+                let first = context.relative;
+                let pitches: Vec<_> = pitches
+                    .into_iter()
+                    .map(|pitch| pitch.into_model(context))
+                    .collect();
+
+                // The note after a chord is relative to its first note.
+                if first.is_some() {
+                    context.relative = Some(pitches.first().copied());
+                }
+
+                let pitches: Vec<_> = pitches
+                    .into_iter()
+                    .map(|pitch| context.written(pitch))
+                    .collect();
+
+                if pitches.len() == 1 {
+                    model::measure::Element::Note(model::Note {
+                        pitch: pitches[0],
+                        duration: context.duration,
+                        articulations,
+                        ornament,
+                        id: None,
+                    })
+                } else {
+                    model::measure::Element::Chord(model::Chord {
+                        ornament,
+                        ..model::Chord::new(pitches, context.duration, articulations, None)
+                    })
+                }
+            }
+            Self::Rest(..) => model::measure::Element::Rest(model::Rest {
+                duration: context.duration,
+                id: None,
+            }),
+            Self::FullMeasureRest(..) => model::measure::Element::FullMeasureRest,
+        };
+
+        let mut elements = post_events.directions;
+        elements.push(element);
+
+        elements
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Item {
+    Clef(Clef),
+    Key(i8),
+    Time(TimeSignature),
+    BarCheck,
+    Bar(Barline),
+    Grace(grace::Kind, Vec<Element>),
+    /// The start of an octave line, or `None` for the end.
+    Ottava(Option<octave_line::Kind>),
+    Tempo(annotation::Tempo),
+    /// A rehearsal mark with its text, or `None` for the next letter.
+    Mark(Option<String>),
+    Break,
+    Element(Element),
+}
+
+fn step(input: &str) -> IResult<&str, Step> {
+    alt((
+        char('c').map(|_| Step::C),
+        char('d').map(|_| Step::D),
+        char('e').map(|_| Step::E),
+        char('f').map(|_| Step::F),
+        char('g').map(|_| Step::G),
+        char('a').map(|_| Step::A),
+        char('b').map(|_| Step::B),
+    ))(input)
+}
+
+fn alteration(step: Step) -> impl FnMut(&str) -> IResult<&str, Alteration> {
+    move |input| {
+        let (input, alteration) = alt((
+            tag("isis").map(|_| Alteration::DoubleSharp),
+            tag("is").map(|_| Alteration::Sharp),
+            tag("eses").map(|_| Alteration::DoubleFlat),
+            tag("es").map(|_| Alteration::Flat),
+        ))(input)
+        .or_else(|error| match step {
+            // `es` and `as` are shortened forms of `ees` and `aes`.
+            Step::E | Step::A => alt((
+                tag("ses").map(|_| Alteration::DoubleFlat),
+                tag("s").map(|_| Alteration::Flat),
+            ))(input),
+            _ => Err(error),
+        })?;
+
+        Ok((input, alteration))
+    }
+}
+
+fn pitch(input: &str) -> IResult<&str, Pitch> {
+    let (input, step) = step(input)?;
+    let (input, alteration) = opt(alteration(step))(input)?;
+    let (input, octave_marks) = many0(alt((char('\'').map(|_| 1), char(',').map(|_| -1))))(input)?;
+    let (input, _) = opt(one_of("!?"))(input)?;
+
+    Ok((
+        input,
+        Pitch {
+            step,
+            alteration: alteration.unwrap_or(Alteration::Natural),
+            octave_marks: octave_marks.into_iter().sum(),
+        },
+    ))
+}
+
+fn duration(input: &str) -> IResult<&str, Duration> {
+    let (remaining, denominator) = u16(input)?;
+    let value = duration::Value::iter()
+        .find(|value| value.divisions() * denominator as u32 == duration::Value::Whole.divisions())
+        .ok_or(nom::Err::Error(nom::error::Error::new(
+            input,
+            nom::error::ErrorKind::Verify,
+        )))?;
+    let (remaining, num_dots) =
+        fold_many_m_n(0, 2, char('.'), || 0, |count, _| count + 1)(remaining)?;
+    let dots = match num_dots {
+        0 => None,
+        1 => Some(duration::Dots::Dot),
+        2 => Some(duration::Dots::DoubleDot),
+        _ => unreachable!(),
+    };
+
+    Ok((remaining, Duration { value, dots }))
+}
+
+/// Parses an articulation, which may be two articulations for `-_` (portato).
+fn articulation(input: &str) -> IResult<&str, Vec<Articulation>> {
+    preceded(
+        one_of("-^_"),
+        alt((
+            char('.').map(|_| vec![Articulation::Staccato]),
+            char('!').map(|_| vec![Articulation::Staccatissimo]),
+            char('-').map(|_| vec![Articulation::Tenuto]),
+            char('>').map(|_| vec![Articulation::Accent]),
+            char('^').map(|_| vec![Articulation::Marcato]),
+            char('_').map(|_| vec![Articulation::Tenuto, Articulation::Staccato]),
+        )),
+    )(input)
+}
+
+/// Parses the articulations, ornaments, dynamics and hairpins following a note,
+/// chord or rest.
+fn post_events(input: &str) -> IResult<&str, PostEvents> {
+    enum PostEvent {
+        Articulations(Vec<Articulation>),
+        Ornament(ornament::Kind),
+        Direction(model::measure::Element),
+        Ignored,
+    }
+
+    many0(ws(alt((
+        articulation.map(PostEvent::Articulations),
+        ornament.map(PostEvent::Ornament),
+        tag("\\stopTrillSpan").map(|_| PostEvent::Ignored),
+        dynamic.map(|dynamic| PostEvent::Direction(model::measure::Element::Dynamic(dynamic))),
+        hairpin.map(|hairpin| PostEvent::Direction(model::measure::Element::Hairpin(hairpin))),
+        pedal.map(|pedal| PostEvent::Direction(model::measure::Element::Pedal(pedal))),
+    ))))
+    .map(|events| {
+        events
+            .into_iter()
+            .fold(PostEvents::default(), |mut post_events, event| {
+                match event {
+                    PostEvent::Articulations(mut articulations) => {
+                        post_events.articulations.append(&mut articulations)
+                    }
+                    PostEvent::Ornament(kind) => {
+                        // `\trill\startTrillSpan` is a single trill with a wavy line.
+                        let kind = match (post_events.ornament, kind) {
+                            (
+                                Some(Ornament {
+                                    kind: ornament::Kind::Trill { extended: true },
+                                    ..
+                                }),
+                                ornament::Kind::Trill { .. },
+                            ) => ornament::Kind::Trill { extended: true },
+                            _ => kind,
+                        };
+
+                        post_events.ornament = Some(Ornament {
+                            kind,
+                            accidental_above: None,
+                            accidental_below: None,
+                        });
+                    }
+                    PostEvent::Direction(model::measure::Element::Pedal(Pedal::Down(depth)))
+                        if post_events.directions.last()
+                            == Some(&model::measure::Element::Pedal(Pedal::Up)) =>
+                    {
+                        post_events.directions.pop();
+                        post_events
+                            .directions
+                            .push(model::measure::Element::Pedal(Pedal::Change(depth)));
+                    }
+                    PostEvent::Direction(direction) => post_events.directions.push(direction),
+                    PostEvent::Ignored => {}
+                }
+                post_events
+            })
+    })
+    .parse(input)
+}
+
+fn ornament(input: &str) -> IResult<&str, ornament::Kind> {
+    alt((
+        tag("\\trill").map(|_| ornament::Kind::Trill { extended: false }),
+        tag("\\startTrillSpan").map(|_| ornament::Kind::Trill { extended: true }),
+        tag("\\mordent").map(|_| ornament::Kind::Mordent),
+        tag("\\prall").map(|_| ornament::Kind::InvertedMordent),
+        tag("\\turn").map(|_| ornament::Kind::Turn),
+        tag("\\reverseturn").map(|_| ornament::Kind::InvertedTurn),
+    ))(input)
+}
+
+fn pedal(input: &str) -> IResult<&str, Pedal> {
+    alt((
+        tag("\\sustainOn").map(|_| Pedal::Down(pedal::Depth::Full)),
+        tag("\\sustainOff").map(|_| Pedal::Up),
+        tag("\\unaCorda").map(|_| Pedal::UnaCorda),
+        tag("\\treCorde").map(|_| Pedal::TreCorde),
+    ))(input)
+}
+
+fn note(input: &str) -> IResult<&str, Element> {
+    tuple((pitch, opt(duration), post_events))
+        .map(|(pitch, duration, post_events)| Element::Note(pitch, duration, post_events))
+        .parse(input)
+}
+
+fn element(input: &str) -> IResult<&str, Element> {
+    alt((
+        note,
+        tuple((
+            delimited(char('<'), many1(ws(pitch)), char('>')),
+            opt(duration),
+            post_events,
+        ))
+        .map(|(pitches, duration, post_events)| Element::Chord(pitches, duration, post_events)),
+        tuple((preceded(char('r'), opt(duration)), post_events))
+            .map(|(duration, post_events)| Element::Rest(duration, post_events)),
+        tuple((preceded(char('R'), opt(duration)), post_events))
+            .map(|(duration, post_events)| Element::FullMeasureRest(duration, post_events)),
+    ))(input)
+}
+
+/// Parses grace notes, which are a single note or notes in braces.
+fn grace(input: &str) -> IResult<&str, (grace::Kind, Vec<Element>)> {
+    let (input, kind) = terminated(
+        alt((
+            tag("\\acciaccatura").map(|_| grace::Kind::Acciaccatura),
+            tag("\\slashedGrace").map(|_| grace::Kind::Acciaccatura),
+            tag("\\appoggiatura").map(|_| grace::Kind::Appoggiatura),
+            tag("\\grace").map(|_| grace::Kind::Appoggiatura),
+        )),
+        multispace0,
+    )(input)?;
+    let (input, notes) = alt((
+        delimited(char('{'), many1(ws(note)), char('}')),
+        note.map(|note| vec![note]),
+    ))(input)?;
+
+    Ok((input, (kind, notes)))
+}
+
+fn clef(input: &str) -> IResult<&str, Clef> {
+    let name = |input| {
+        alt((
+            tag("treble_8").map(|_| Clef::Treble8vb),
+            tag("treble^8").map(|_| Clef::Treble8va),
+            tag("treble").map(|_| Clef::Treble),
+            tag("french").map(|_| Clef::FrenchViolin),
+            tag("soprano").map(|_| Clef::Soprano),
+            tag("mezzosoprano").map(|_| Clef::MezzoSoprano),
+            tag("alto").map(|_| Clef::Alto),
+            tag("tenor").map(|_| Clef::Tenor),
+            tag("baritone").map(|_| Clef::Baritone),
+            tag("bass_8").map(|_| Clef::Bass8vb),
+            tag("bass").map(|_| Clef::Bass),
+            tag("percussion").map(|_| Clef::Percussion),
+            tag("tab").map(|_| Clef::Tab),
+        ))(input)
+    };
+
+    preceded(
+        pair(tag("\\clef"), multispace1),
+        alt((delimited(char('"'), name, char('"')), name)),
+    )(input)
+}
+
+/// Parses a `\key` command into the position of the key signature on the
+/// circle of fifths.
+fn key(input: &str) -> IResult<&str, i8> {
+    let (input, _) = pair(tag("\\key"), multispace1)(input)?;
+    let (input, tonic) = pitch(input)?;
+    let (input, mode) = preceded(
+        multispace1,
+        alt((
+            tag("\\major").map(|_| 0),
+            tag("\\minor").map(|_| -3),
+            tag("\\ionian").map(|_| 0),
+            tag("\\dorian").map(|_| -2),
+            tag("\\phrygian").map(|_| -4),
+            tag("\\lydian").map(|_| 1),
+            tag("\\mixolydian").map(|_| -1),
+            tag("\\aeolian").map(|_| -3),
+            tag("\\locrian").map(|_| -5),
+        )),
+    )(input)?;
+
+    let tonic_fifths = match tonic.step {
+        Step::F => -1,
+        Step::C => 0,
+        Step::G => 1,
+        Step::D => 2,
+        Step::A => 3,
+        Step::E => 4,
+        Step::B => 5,
+    };
+
+    Ok((
+        input,
+        tonic_fifths + 7 * tonic.alteration.semitones() + mode,
+    ))
+}
+
+/// Parses `\time`, with an additive numerator such as `2+3` if there is one.
+fn time_signature(input: &str) -> IResult<&str, TimeSignature> {
+    let (input, (numerators, denominator)) = preceded(
+        pair(tag("\\time"), multispace1),
+        separated_pair(separated_list1(char('+'), u8), char('/'), u8),
+    )(input)?;
+
+    Ok((
+        input,
+        TimeSignature::Meters(vec![time_signature::Meter {
+            beats: time_signature::Beats::Numbers(numerators),
+            unit: Some(time_signature::Unit::Number(denominator)),
+        }]),
+    ))
+}
+
+fn bar(input: &str) -> IResult<&str, Barline> {
+    preceded(
+        pair(tag("\\bar"), multispace1),
+        delimited(
+            char('"'),
+            alt((
+                tag(":|.").map(|_| Barline::EndRepeat),
+                tag(":..:").map(|_| Barline::EndStartRepeat),
+                tag("|.").map(|_| Barline::Final),
+                tag("||").map(|_| Barline::Double),
+                tag("|").map(|_| Barline::Thin),
+                tag(".").map(|_| Barline::Heavy),
+                tag("!").map(|_| Barline::Dashed),
+                tag(";").map(|_| Barline::Dotted),
+                tag("").map(|_| Barline::Invisible),
+            )),
+            char('"'),
+        ),
+    )(input)
+}
+
+fn item(input: &str) -> IResult<&str, Item> {
+    alt((
+        clef.map(Item::Clef),
+        key.map(Item::Key),
+        time_signature.map(Item::Time),
+        bar.map(Item::Bar),
+        char('|').map(|_| Item::BarCheck),
+        grace.map(|(kind, notes)| Item::Grace(kind, notes)),
+        ottava.map(Item::Ottava),
+        tempo.map(Item::Tempo),
+        mark.map(Item::Mark),
+        tag("\\break").map(|_| Item::Break),
+        element.map(Item::Element),
+    ))(input)
+}
+
+/// Parses `\ottava` followed by the number of octaves by which the notes sound
+/// higher (or lower, if negative) than they are drawn, such as `#1` for 8va, or
+/// `#0` for the end of an octave line.
+fn ottava(input: &str) -> IResult<&str, Option<octave_line::Kind>> {
+    map_opt(
+        preceded(pair(tag("\\ottava"), multispace0), preceded(char('#'), i8)),
+        |octaves| match octaves {
+            0 => Some(None),
+            1 => Some(Some(octave_line::Kind::OctaveAbove)),
+            -1 => Some(Some(octave_line::Kind::OctaveBelow)),
+            2 => Some(Some(octave_line::Kind::TwoOctavesAbove)),
+            -2 => Some(Some(octave_line::Kind::TwoOctavesBelow)),
+            _ => None,
+        },
+    )(input)
+}
+
+/// Parses `\tempo` followed by text in quotes, a metronome mark such as
+/// `4. = 60`, or both.
+fn tempo(input: &str) -> IResult<&str, annotation::Tempo> {
+    let metronome_mark = separated_pair(duration, ws(char('=')), u16)
+        .map(|(beat, per_minute)| annotation::MetronomeMark { beat, per_minute });
+
+    preceded(
+        pair(tag("\\tempo"), multispace0),
+        verify(
+            pair(opt(quoted), opt(preceded(multispace0, metronome_mark))).map(
+                |(text, metronome_mark)| annotation::Tempo {
+                    text: text.map(str::to_owned),
+                    metronome_mark,
+                },
+            ),
+            |tempo| tempo.text.is_some() || tempo.metronome_mark.is_some(),
+        ),
+    )(input)
+}
+
+/// Parses `\mark` followed by text in quotes, or by `\default` for the next
+/// letter.
+fn mark(input: &str) -> IResult<&str, Option<String>> {
+    preceded(
+        pair(tag("\\mark"), multispace0),
+        alt((
+            quoted.map(|text| Some(text.to_owned())),
+            tag("\\default").map(|_| None),
+        )),
+    )(input)
+}
+
+/// Returns the letters of the rehearsal mark with the given index, which are
+/// `A` to `Z` without `I`, followed by `AA`, `AB` and so on.
+fn mark_letters(index: usize) -> String {
+    const LETTERS: &[u8] = b"ABCDEFGHJKLMNOPQRSTUVWXYZ";
+
+    let mut letters = vec![];
+    let mut remaining = index + 1;
+
+    while remaining > 0 {
+        remaining -= 1;
+        letters.push(char::from(LETTERS[remaining % LETTERS.len()]));
+        remaining /= LETTERS.len();
+    }
+
+    letters.iter().rev().collect()
+}
+
+fn version(input: &str) -> IResult<&str, ()> {
+    let (input, _) = pair(tag("\\version"), multispace1)(input)?;
+    let (input, _) = delimited(char('"'), opt(is_not("\"")), char('"'))(input)?;
+
+    Ok((input, ()))
+}
+
+/// Parses `\relative` and its optional starting pitch.
+fn relative(input: &str) -> IResult<&str, Option<Pitch>> {
+    preceded(tag("\\relative"), opt(preceded(multispace1, pitch)))(input)
+}
+
+/// Parses a verse of lyrics, which is `\addlyrics` followed by lyrics in
+/// braces.
+fn verse(input: &str) -> IResult<&str, Vec<Lyric>> {
+    preceded(
+        pair(tag("\\addlyrics"), multispace0),
+        delimited(char('{'), opt(is_not("}")), char('}')),
+    )
+    .map(|text| lyrics(text.unwrap_or_default()))
+    .parse(input)
+}
+
+/// Parses the music, returning whether it is relative, with its starting
+/// pitch, and its items.
+fn score(input: &str) -> IResult<&str, (Option<Option<Pitch>>, Vec<Item>)> {
+    let (input, _) = opt(ws(version))(input)?;
+    let (input, relative) = opt(ws(relative))(input)?;
+    let (input, items) =
+        delimited(char('{'), preceded(multispace0, many0(ws(item))), char('}'))(input)?;
+
+    Ok((input, (relative, items)))
+}
@@ -11,6 +11,39 @@ pub enum Error {
 
     #[error("Cannot end a beam when no beam is in progress")]
     EndedBeamWhileNoBeamInProgress,
+
+    #[error("Cannot end a hairpin when no hairpin is in progress")]
+    EndedHairpinWhileNoHairpinInProgress,
+
+    #[error("Cannot end an octave line when no octave line is in progress")]
+    EndedOctaveLineWhileNoOctaveLineInProgress,
+
+    #[error("MIDI file ended unexpectedly")]
+    UnexpectedEndOfMidiFile,
+
+    #[error("Expected MIDI chunk of type `{expected}`, found `{found}`")]
+    InvalidMidiChunkType { expected: String, found: String },
+
+    #[error("Unsupported MIDI file format `{0}`")]
+    UnsupportedMidiFormat(u16),
+
+    #[error("MIDI files with SMPTE time divisions are not supported")]
+    UnsupportedMidiTimeDivision,
+
+    #[error("MIDI event has no status byte and no running status is in effect")]
+    MissingMidiRunningStatus,
+
+    #[error("Invalid MIDI time signature with {numerator} beats and denominator 2^{exponent}")]
+    InvalidMidiTimeSignature { numerator: u8, exponent: u8 },
+
+    #[error(
+        "Invalid MusicXML id `{0}`, which must start with a letter or an underscore and only \
+         contain letters, digits, hyphens, underscores and periods"
+    )]
+    InvalidMusicXmlId(String),
+
+    #[error("Duplicate MusicXML id `{0}`")]
+    DuplicateMusicXmlId(String),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -1,6 +1,8 @@
 #![deny(rustdoc::broken_intra_doc_links)]
 
+pub mod midi;
 pub mod model;
+pub mod musicxml;
 pub mod render;
 pub mod svg;
 
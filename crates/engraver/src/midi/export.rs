@@ -0,0 +1,486 @@
+use std::collections::HashMap;
+
+use super::{
+    CONTROLLER_SOFT_PEDAL, CONTROLLER_SUSTAIN_PEDAL, CONTROL_CHANGE, HEADER_CHUNK_TYPE,
+    META_END_OF_TRACK, META_EVENT, META_KEY_SIGNATURE, META_TEMPO, META_TIME_SIGNATURE, NOTE_OFF,
+    NOTE_ON, TRACK_CHUNK_TYPE,
+};
+use crate::model::{
+    annotation::Annotation, duration::DIVISIONS_PER_QUARTER, grace, measure, ornament, pedal,
+    Alteration, Clef, Grace, KeySignature, Ornament, Pedal, Pitch, Staff, Step, TimeSignature,
+};
+
+const MICROSECONDS_PER_MINUTE: u32 = 60_000_000;
+const MIDI_CLOCKS_PER_QUARTER: u8 = 24;
+const THIRTY_SECONDS_PER_QUARTER: u8 = 8;
+
+/// The length of an acciaccatura, which is a thirty-second note.
+const ACCIACCATURA_LENGTH: u32 = DIVISIONS_PER_QUARTER / 8;
+
+/// The length of each note of a realized trill, which is a thirty-second note.
+const TRILL_NOTE_LENGTH: u32 = DIVISIONS_PER_QUARTER / 8;
+
+#[derive(Debug)]
+pub struct Options {
+    /// The tempo, in quarter notes per minute, until the first metronome mark.
+    pub tempo: u16,
+
+    /// The MIDI channel (0-15) on which notes are played.
+    pub channel: u8,
+
+    /// The velocity (1-127) with which notes are played.
+    pub velocity: u8,
+
+    /// Whether notes with trills are played as trills, rather than as the
+    /// written note.
+    pub realize_trills: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            tempo: 120,
+            channel: 0,
+            velocity: 80,
+            realize_trills: false,
+        }
+    }
+}
+
+/// Returns the contents of a format 0 Standard MIDI File which plays the
+/// staff, with its measures in their [playback order](Staff::playback_order).
+///
+/// Notes sound at the pitch given by their [`Pitch`], regardless of which
+/// accidentals would be engraved for them, moved by the octave of any octave
+/// clef. Grace notes are played on the beat, taking time from the start of the
+/// note which follows them, even in a later measure, or from the end of the
+/// last note when no note follows them. Metronome marks change the tempo, and
+/// pedal marks press and lift the sustain and soft pedals. With
+/// [`realize_trills`](Options::realize_trills), notes with trills alternate
+/// with their upper auxiliary note in thirty-second notes. The file has
+/// [`DIVISIONS_PER_QUARTER`] ticks per quarter note, so every duration is
+/// represented exactly.
+pub fn staff_to_midi_file(staff: &Staff, options: &Options) -> Vec<u8> {
+    let mut events = Vec::new();
+
+    events.push(Event::meta(0, META_TEMPO, &tempo_data(options.tempo)));
+
+    if let Some(time_signature) = &staff.time_signature {
+        if let Some(data) = time_signature_data(time_signature) {
+            events.push(Event::meta(0, META_TIME_SIGNATURE, &data));
+        }
+    }
+
+    if let Some(key_signature) = staff.key_signature {
+        events.push(Event::meta(
+            0,
+            META_KEY_SIGNATURE,
+            &key_signature_data(Some(key_signature)),
+        ));
+    }
+
+    let playback_order = staff.playback_order();
+    let (last, trailing_graces) = trailing_graces(staff, &playback_order);
+
+    let mut tick = 0;
+    let mut key_signature = staff.key_signature;
+    let mut time_signature = staff.time_signature.as_ref();
+    let mut graces = vec![];
+
+    for (position, index) in playback_order.into_iter().enumerate() {
+        // Signature changes are written whenever the signature in effect
+        // changes, which includes jumping back to before a change.
+        if staff.time_signature_at(index) != time_signature {
+            time_signature = staff.time_signature_at(index);
+
+            if let Some(data) = time_signature.and_then(time_signature_data) {
+                events.push(Event::meta(tick, META_TIME_SIGNATURE, &data));
+            }
+        }
+
+        if staff.key_signature_at(index) != key_signature {
+            key_signature = staff.key_signature_at(index);
+            events.push(Event::meta(
+                tick,
+                META_KEY_SIGNATURE,
+                &key_signature_data(key_signature),
+            ));
+        }
+
+        let mut clef = staff.clef_at(index);
+        let mut alterations = HashMap::new();
+
+        for (element_index, element) in staff.measures[index].elements.iter().enumerate() {
+            let mut trill = None;
+            let (pitches, length) = match element {
+                measure::Element::Note(note) => {
+                    if options.realize_trills {
+                        trill = note
+                            .ornament
+                            .filter(|ornament| {
+                                matches!(ornament.kind, ornament::Kind::Trill { .. })
+                            })
+                            .map(|ornament| {
+                                upper_auxiliary(
+                                    note.pitch,
+                                    ornament,
+                                    &alterations,
+                                    staff.key_signature_at(index),
+                                )
+                            });
+                    }
+
+                    add_alterations(&mut alterations, [note.pitch]);
+
+                    (vec![note.pitch], note.duration.divisions())
+                }
+                measure::Element::Chord(chord) => {
+                    add_alterations(&mut alterations, chord.pitches.iter().copied());
+
+                    (chord.pitches.clone(), chord.duration.divisions())
+                }
+                measure::Element::Rest(rest) => (vec![], rest.duration.divisions()),
+                measure::Element::FullMeasureRest => (vec![], staff.measure_divisions_at(index)),
+                measure::Element::Clef(change) => {
+                    clef = *change;
+                    continue;
+                }
+                measure::Element::Grace(grace) => {
+                    add_alterations(&mut alterations, grace.notes.iter().map(|note| note.pitch));
+                    graces.push(grace);
+                    continue;
+                }
+                measure::Element::Annotation(Annotation::Tempo(tempo)) => {
+                    if let Some(metronome_mark) = &tempo.metronome_mark {
+                        events.push(Event::meta(
+                            tick,
+                            META_TEMPO,
+                            &tempo_data(metronome_mark.quarters_per_minute()),
+                        ));
+                    }
+                    continue;
+                }
+                measure::Element::Pedal(pedal) => {
+                    events.extend(
+                        pedal_controls(*pedal)
+                            .into_iter()
+                            .map(|(controller, value)| {
+                                Event::channel(tick, CONTROL_CHANGE, options, controller, value)
+                            }),
+                    );
+                    continue;
+                }
+                measure::Element::Dynamic(_)
+                | measure::Element::Hairpin(_)
+                | measure::Element::ChordSymbol(_)
+                | measure::Element::Annotation(_)
+                | measure::Element::OctaveLine(_) => continue,
+            };
+            let mut start = tick;
+
+            for (pitch, grace_length) in grace_lengths(&graces, length) {
+                events.extend(Event::note(
+                    start,
+                    start + grace_length,
+                    key(pitch, clef),
+                    options,
+                ));
+                start += grace_length;
+            }
+
+            graces.clear();
+
+            let mut end = tick + length;
+
+            if last == Some((position, element_index)) {
+                let lengths = grace_lengths(&trailing_graces, end - start);
+                let mut grace_start = end - lengths.iter().map(|(_, length)| length).sum::<u32>();
+                end = grace_start;
+
+                for (pitch, grace_length) in lengths {
+                    events.extend(Event::note(
+                        grace_start,
+                        grace_start + grace_length,
+                        key(pitch, clef),
+                        options,
+                    ));
+                    grace_start += grace_length;
+                }
+            }
+
+            match trill {
+                Some(upper) => {
+                    let keys = [key(pitches[0], clef), key(upper, clef)];
+
+                    for (index, start) in
+                        (start..end).step_by(TRILL_NOTE_LENGTH as usize).enumerate()
+                    {
+                        let end = end.min(start + TRILL_NOTE_LENGTH);
+                        events.extend(Event::note(start, end, keys[index % 2], options));
+                    }
+                }
+                None => {
+                    for pitch in pitches {
+                        events.extend(Event::note(start, end, key(pitch, clef), options));
+                    }
+                }
+            }
+
+            tick += length;
+        }
+    }
+
+    events.push(Event::meta(tick, META_END_OF_TRACK, &[]));
+
+    // Meta events are placed ahead of notes at the start of the track, and notes
+    // ending at a tick are released before notes starting at the same tick.
+    events.sort_by_key(|event| (event.tick, event.order()));
+
+    let mut track = Vec::new();
+    let mut previous_tick = 0;
+
+    for event in events {
+        write_variable_length_quantity(&mut track, event.tick - previous_tick);
+        track.extend_from_slice(&event.data);
+        previous_tick = event.tick;
+    }
+
+    let mut file = Vec::with_capacity(22 + track.len());
+
+    file.extend_from_slice(HEADER_CHUNK_TYPE);
+    file.extend_from_slice(&6u32.to_be_bytes());
+    file.extend_from_slice(&0u16.to_be_bytes());
+    file.extend_from_slice(&1u16.to_be_bytes());
+    file.extend_from_slice(&(DIVISIONS_PER_QUARTER as u16).to_be_bytes());
+
+    file.extend_from_slice(TRACK_CHUNK_TYPE);
+    file.extend_from_slice(&(track.len() as u32).to_be_bytes());
+    file.append(&mut track);
+
+    file
+}
+
+#[derive(Debug)]
+struct Event {
+    tick: u32,
+    data: Vec<u8>,
+}
+
+impl Event {
+    fn meta(tick: u32, meta_type: u8, data: &[u8]) -> Self {
+        let mut bytes = vec![META_EVENT, meta_type];
+        write_variable_length_quantity(&mut bytes, data.len() as u32);
+        bytes.extend_from_slice(data);
+
+        Self { tick, data: bytes }
+    }
+
+    fn channel(tick: u32, status: u8, options: &Options, key: u8, velocity: u8) -> Self {
+        Self {
+            tick,
+            data: vec![status | (options.channel & 0x0f), key, velocity],
+        }
+    }
+
+    /// Returns the note on and note off events of a note which sounds from
+    /// `start` until `end`.
+    fn note(start: u32, end: u32, key: u8, options: &Options) -> [Self; 2] {
+        [
+            Self::channel(start, NOTE_ON, options, key, options.velocity),
+            Self::channel(end, NOTE_OFF, options, key, 0),
+        ]
+    }
+
+    fn order(&self) -> u8 {
+        match self.data[0] {
+            META_EVENT if self.data[1] == META_END_OF_TRACK => 3,
+            META_EVENT => 0,
+            status if status & 0xf0 == NOTE_OFF => 1,
+            _ => 2,
+        }
+    }
+}
+
+/// Returns the position in the playback order and the index of the last note,
+/// chord or rest of the staff, along with the grace notes after it, which have
+/// no note to take time from the start of.
+fn trailing_graces<'s>(
+    staff: &'s Staff,
+    playback_order: &[usize],
+) -> (Option<(usize, usize)>, Vec<&'s Grace>) {
+    let mut last = None;
+    let mut graces = vec![];
+
+    for (position, index) in playback_order.iter().enumerate() {
+        for (element_index, element) in staff.measures[*index].elements.iter().enumerate() {
+            match element {
+                measure::Element::Note(_)
+                | measure::Element::Chord(_)
+                | measure::Element::Rest(_)
+                | measure::Element::FullMeasureRest => {
+                    last = Some((position, element_index));
+                    graces.clear();
+                }
+                measure::Element::Grace(grace) => graces.push(grace),
+                _ => {}
+            }
+        }
+    }
+
+    (last, graces)
+}
+
+/// Returns the pitches and lengths of the grace notes of a note of the given
+/// length. Acciaccaturas are short and appoggiaturas last their written value,
+/// but together they take at most half of the note. Each grace note lasts at
+/// least one division, and in the unlikely case that there are so many that
+/// the note would not last a division of its own, the last ones are left out.
+fn grace_lengths(graces: &[&Grace], length: u32) -> Vec<(Pitch, u32)> {
+    let notes: Vec<_> = graces
+        .iter()
+        .flat_map(|grace| {
+            grace.notes.iter().map(|note| match grace.kind {
+                grace::Kind::Acciaccatura => (note.pitch, ACCIACCATURA_LENGTH),
+                grace::Kind::Appoggiatura => (note.pitch, note.duration.divisions()),
+            })
+        })
+        .collect();
+
+    let total: u32 = notes.iter().map(|(_, length)| length).sum();
+    let available = length / 2;
+
+    if total <= available {
+        return notes;
+    }
+
+    let shortened = (available / notes.len() as u32).max(1);
+
+    notes
+        .into_iter()
+        .take(length.saturating_sub(1) as usize)
+        .map(|(pitch, _)| (pitch, shortened))
+        .collect()
+}
+
+/// Returns the controllers and values of the control changes which press or
+/// lift a pedal, such as lifting the sustain pedal and pressing it again for a
+/// pedal change.
+fn pedal_controls(pedal: Pedal) -> Vec<(u8, u8)> {
+    let pressed = |depth| match depth {
+        pedal::Depth::Full => 127,
+        pedal::Depth::Half => 64,
+    };
+
+    match pedal {
+        Pedal::Down(depth) => vec![(CONTROLLER_SUSTAIN_PEDAL, pressed(depth))],
+        Pedal::Change(depth) => vec![
+            (CONTROLLER_SUSTAIN_PEDAL, 0),
+            (CONTROLLER_SUSTAIN_PEDAL, pressed(depth)),
+        ],
+        Pedal::Up => vec![(CONTROLLER_SUSTAIN_PEDAL, 0)],
+        Pedal::UnaCorda => vec![(CONTROLLER_SOFT_PEDAL, 127)],
+        Pedal::TreCorde => vec![(CONTROLLER_SOFT_PEDAL, 0)],
+    }
+}
+
+/// Adds the alterations of pitches played in a measure, which later notes of
+/// the same step and octave in the measure keep.
+fn add_alterations(
+    alterations: &mut HashMap<(Step, i8), Alteration>,
+    pitches: impl IntoIterator<Item = Pitch>,
+) {
+    for pitch in pitches {
+        alterations.insert((pitch.step, pitch.octave), pitch.alteration);
+    }
+}
+
+/// Returns the note a step above the pitch, which is altered by the accidental
+/// above the ornament, or otherwise by an earlier note of the same step and
+/// octave in the measure, or otherwise by the key signature.
+fn upper_auxiliary(
+    pitch: Pitch,
+    ornament: Ornament,
+    alterations: &HashMap<(Step, i8), Alteration>,
+    key_signature: Option<KeySignature>,
+) -> Pitch {
+    let (step, octave) = match pitch.step {
+        Step::C => (Step::D, pitch.octave),
+        Step::D => (Step::E, pitch.octave),
+        Step::E => (Step::F, pitch.octave),
+        Step::F => (Step::G, pitch.octave),
+        Step::G => (Step::A, pitch.octave),
+        Step::A => (Step::B, pitch.octave),
+        Step::B => (Step::C, pitch.octave + 1),
+    };
+
+    let alteration = ornament
+        .accidental_above
+        .or_else(|| alterations.get(&(step, octave)).copied())
+        .unwrap_or_else(|| {
+            key_signature
+                .and_then(|key_signature| key_signature.alterations().get(&step).copied())
+                .unwrap_or(Alteration::Natural)
+        });
+
+    Pitch {
+        step,
+        alteration,
+        octave,
+    }
+}
+
+/// Returns the key which sounds the pitch written with the clef.
+fn key(pitch: Pitch, clef: Clef) -> u8 {
+    let key = pitch.midi_note_number() + 12 * i16::from(clef.octave_shift());
+
+    key.clamp(0, 127) as u8
+}
+
+fn tempo_data(tempo: u16) -> [u8; 3] {
+    let microseconds_per_quarter = MICROSECONDS_PER_MINUTE / u32::from(tempo.max(1));
+    let [_, data @ ..] = microseconds_per_quarter.to_be_bytes();
+
+    data
+}
+
+/// Returns the data of a time signature meta event, which can only represent
+/// measures with a known length and a denominator which is a power of two.
+fn time_signature_data(time_signature: &TimeSignature) -> Option<[u8; 4]> {
+    let (numerator, denominator) = time_signature.fraction()?;
+
+    if !denominator.is_power_of_two() {
+        return None;
+    }
+
+    Some([
+        u8::try_from(numerator).ok()?,
+        denominator.trailing_zeros() as u8,
+        MIDI_CLOCKS_PER_QUARTER,
+        THIRTY_SECONDS_PER_QUARTER,
+    ])
+}
+
+/// Returns the data of a key signature meta event, which has no sharps or
+/// flats if `key_signature` is `None`.
+fn key_signature_data(key_signature: Option<KeySignature>) -> [u8; 2] {
+    [
+        key_signature.map_or(0, |key_signature| key_signature.fifths()) as u8,
+        0,
+    ]
+}
+
+fn write_variable_length_quantity(bytes: &mut Vec<u8>, mut value: u32) {
+    let mut buffer = [0u8; 5];
+    let mut index = buffer.len() - 1;
+
+    buffer[index] = (value & 0x7f) as u8;
+    value >>= 7;
+
+    while value > 0 {
+        index -= 1;
+        buffer[index] = (value & 0x7f) as u8 | 0x80;
+        value >>= 7;
+    }
+
+    bytes.extend_from_slice(&buffer[index..]);
+}
@@ -0,0 +1,578 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use strum::IntoEnumIterator;
+
+use super::{
+    HEADER_CHUNK_TYPE, META_EVENT, META_KEY_SIGNATURE, META_TIME_SIGNATURE, NOTE_OFF, NOTE_ON,
+    TRACK_CHUNK_TYPE,
+};
+use crate::{
+    model::{
+        duration::{self, DIVISIONS_PER_QUARTER},
+        measure, Alteration, Barline, Chord, Clef, Duration, KeySignature, Measure, Note, Pitch,
+        Rest, Staff, Step, TimeSignature,
+    },
+    Error, Result,
+};
+
+const SYSTEM_EXCLUSIVE: u8 = 0xf0;
+const SYSTEM_EXCLUSIVE_ESCAPE: u8 = 0xf7;
+
+const MIDDLE_C: u8 = 60;
+
+const STEPS: [Step; 7] = [
+    Step::C,
+    Step::D,
+    Step::E,
+    Step::F,
+    Step::G,
+    Step::A,
+    Step::B,
+];
+
+#[derive(Debug)]
+pub struct ImportOptions {
+    /// The shortest duration to which note onsets and lengths are quantized.
+    pub grid: duration::Value,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self {
+            grid: duration::Value::Sixteenth,
+        }
+    }
+}
+
+/// Returns a staff containing the notes of a Standard MIDI File.
+///
+/// Note onsets and lengths are quantized to the `grid` in `options`, and the
+/// notes of every track are merged onto the staff. Notes which start together
+/// are grouped into chords, gaps between notes become rests, and notes are
+/// split at the barlines given by the time signature meta events (4/4 if there
+/// are none). The last bar is completed with rests.
+///
+/// Staves have no ties, so the parts of a note which is split at a barline, or
+/// whose length is not a single duration, are separate notes which are played
+/// again rather than held.
+///
+/// Pitches are spelled using the key signature meta event in effect when they
+/// start, preferring the steps of the key and otherwise sharps in sharp keys
+/// and flats in flat keys. The clef is bass if the notes are mostly below
+/// middle C, otherwise treble.
+pub fn midi_file_to_staff(bytes: &[u8], options: &ImportOptions) -> Result<Staff> {
+    let file = File::read(bytes)?;
+
+    let grid = options.grid.divisions();
+    let quantize = |tick: u32| -> u32 {
+        let divisions = tick as f64 * DIVISIONS_PER_QUARTER as f64 / file.ticks_per_quarter as f64;
+        (divisions / grid as f64).round() as u32 * grid
+    };
+
+    let mut notes = vec![];
+    let mut time_signatures = BTreeMap::new();
+    let mut key_signatures = BTreeMap::new();
+    let mut open_notes: HashMap<u8, VecDeque<u32>> = HashMap::new();
+
+    for (tick, event) in file.events() {
+        match event {
+            Event::NoteOn { key } => open_notes.entry(key).or_default().push_back(tick),
+            Event::NoteOff { key } => {
+                if let Some(start) = open_notes.get_mut(&key).and_then(VecDeque::pop_front) {
+                    let start = quantize(start);
+                    let end = quantize(tick).max(start + grid);
+                    notes.push(ImportedNote { start, end, key });
+                }
+            }
+            Event::TimeSignature(time_signature) => {
+                time_signatures.insert(quantize(tick), time_signature);
+            }
+            Event::KeySignature(key_signature) => {
+                key_signatures.insert(quantize(tick), key_signature);
+            }
+        }
+    }
+
+    let mut spans = spans(&notes);
+    let length = spans.last().map(|span| span.end).unwrap_or_default();
+    let bars = bars(length, &time_signatures);
+
+    if let Some(&(_, end)) = bars.last().filter(|(_, end)| *end > length) {
+        spans.push(Span {
+            start: length,
+            end,
+            keys: vec![],
+        });
+    }
+
+    let key_signature_at = |position: u32| {
+        key_signatures
+            .range(..=position)
+            .next_back()
+            .and_then(|(_, key_signature)| *key_signature)
+    };
+
+    let time_signature_at = |position: u32| {
+        time_signatures
+            .range(..=position)
+            .next_back()
+            .map(|(_, time_signature)| time_signature)
+    };
+
+    let mut measures: Vec<_> = bars
+        .iter()
+        .enumerate()
+        .map(|(index, &(bar_start, bar_end))| {
+            let mut elements = vec![];
+
+            for span in &spans {
+                let start = span.start.max(bar_start);
+                let end = span.end.min(bar_end);
+
+                if start >= end {
+                    continue;
+                }
+
+                let key_signature = key_signature_at(span.start);
+
+                for duration in durations(end - start) {
+                    elements.push(element(&span.keys, duration, key_signature));
+                }
+            }
+
+            // Signature changes are placed in the bar in which they start.
+            let previous_start = index.checked_sub(1).map(|index| bars[index].0);
+            let key_signature = key_signature_at(bar_start);
+            let time_signature = time_signature_at(bar_start);
+
+            Measure {
+                key_signature: previous_start
+                    .filter(|start| key_signature_at(*start) != key_signature)
+                    .map(|_| key_signature),
+                time_signature: previous_start
+                    .filter(|start| time_signature_at(*start) != time_signature)
+                    .and(time_signature.cloned()),
+                elements,
+                barline: Barline::Thin,
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    if let Some(measure) = measures.last_mut() {
+        measure.barline = Barline::Final;
+    }
+
+    let clef = if notes.iter().map(|note| note.key as usize).sum::<usize>()
+        < notes.len() * MIDDLE_C as usize
+    {
+        Clef::Bass
+    } else {
+        Clef::Treble
+    };
+
+    Ok(Staff {
+        geometry: Default::default(),
+        clef,
+        key_signature: key_signature_at(0),
+        time_signature: time_signatures.get(&0).cloned(),
+        key_cancellation: Default::default(),
+        measures,
+    })
+}
+
+/// A note, with its start and end quantized to divisions of a quarter note.
+#[derive(Debug)]
+struct ImportedNote {
+    start: u32,
+    end: u32,
+    key: u8,
+}
+
+/// A span of the staff, which is a rest if it has no keys.
+#[derive(Debug)]
+struct Span {
+    start: u32,
+    end: u32,
+    keys: Vec<u8>,
+}
+
+/// Returns the spans of notes and rests which fill the staff.
+///
+/// Notes which start together are grouped into one span, which lasts until the
+/// longest of them ends or the next notes start, whichever comes first.
+fn spans(notes: &[ImportedNote]) -> Vec<Span> {
+    let mut onsets: BTreeMap<u32, Vec<&ImportedNote>> = BTreeMap::new();
+    for note in notes {
+        onsets.entry(note.start).or_default().push(note);
+    }
+
+    let starts: Vec<_> = onsets.keys().copied().collect();
+    let mut spans = vec![];
+    let mut position = 0;
+
+    for (index, (start, notes)) in onsets.into_iter().enumerate() {
+        let longest_end = notes.iter().map(|note| note.end).max().unwrap();
+        let end = starts
+            .get(index + 1)
+            .map_or(longest_end, |next_start| longest_end.min(*next_start));
+
+        if start > position {
+            spans.push(Span {
+                start: position,
+                end: start,
+                keys: vec![],
+            });
+        }
+
+        let mut keys: Vec<_> = notes.iter().map(|note| note.key).collect();
+        keys.sort();
+        keys.dedup();
+
+        spans.push(Span { start, end, keys });
+        position = end;
+    }
+
+    spans
+}
+
+/// Returns the start and end of each bar needed to contain `length`
+/// divisions, using the time signature in effect at the start of each bar.
+fn bars(length: u32, time_signatures: &BTreeMap<u32, TimeSignature>) -> Vec<(u32, u32)> {
+    let mut bars = vec![];
+    let mut start = 0;
+    let default = TimeSignature::new(4, 4);
+    let mut time_signature = &default;
+
+    while start < length {
+        if let Some((_, current)) = time_signatures.range(..=start).next_back() {
+            time_signature = current;
+        }
+
+        let end = start + bar_length(time_signature);
+        bars.push((start, end));
+        start = end;
+    }
+
+    bars
+}
+
+fn bar_length(time_signature: &TimeSignature) -> u32 {
+    time_signature
+        .divisions()
+        .unwrap_or(DIVISIONS_PER_QUARTER * 4)
+}
+
+/// Returns the durations, longest first, which together last `length`
+/// divisions.
+fn durations(mut length: u32) -> Vec<Duration> {
+    let mut candidates: Vec<_> = duration::Value::iter()
+        .flat_map(|value| {
+            [
+                None,
+                Some(duration::Dots::Dot),
+                Some(duration::Dots::DoubleDot),
+            ]
+            .map(|dots| Duration { value, dots })
+        })
+        .collect();
+    candidates.sort_by_key(|duration| std::cmp::Reverse(duration.divisions()));
+
+    let mut durations = vec![];
+
+    while let Some(duration) = candidates
+        .iter()
+        .find(|duration| duration.divisions() <= length)
+    {
+        durations.push(*duration);
+        length -= duration.divisions();
+    }
+
+    durations
+}
+
+fn element(
+    keys: &[u8],
+    duration: Duration,
+    key_signature: Option<KeySignature>,
+) -> measure::Element {
+    match keys {
+        [] => measure::Element::Rest(Rest { duration, id: None }),
+        [key] => measure::Element::Note(Note {
+            pitch: spell(*key, key_signature),
+            duration,
+            articulations: vec![],
+            ornament: None,
+            id: None,
+        }),
+        _ => measure::Element::Chord(Chord::new(
+            keys.iter().map(|key| spell(*key, key_signature)),
+            duration,
+            vec![],
+            None,
+        )),
+    }
+}
+
+/// Returns the spelling of a MIDI note number in a key.
+fn spell(key: u8, key_signature: Option<KeySignature>) -> Pitch {
+    let key_alterations = key_signature
+        .map(|key_signature| key_signature.alterations())
+        .unwrap_or_default();
+    let prefers_flats = key_signature.is_some_and(|key_signature| key_signature.fifths() < 0);
+
+    let (step, alteration) = STEPS
+        .into_iter()
+        .filter_map(|step| {
+            let semitones = (key as i8 - step.semitones()).rem_euclid(12);
+            let semitones = if semitones > 6 {
+                semitones - 12
+            } else {
+                semitones
+            };
+
+            alteration(semitones).map(|alteration| (step, alteration))
+        })
+        .min_by_key(|(step, alteration)| {
+            let key_alteration = key_alterations
+                .get(step)
+                .copied()
+                .unwrap_or(Alteration::Natural);
+
+            match alteration {
+                _ if *alteration == key_alteration => 0,
+                Alteration::Natural => 1,
+                Alteration::Sharp if !prefers_flats => 2,
+                Alteration::Flat if prefers_flats => 2,
+                Alteration::Sharp | Alteration::Flat => 3,
+                Alteration::DoubleSharp | Alteration::DoubleFlat => 4,
+            }
+        })
+        .expect("every key can be spelled with at most two sharps or flats");
+
+    let octave =
+        (key as i16 - step.semitones() as i16 - alteration.semitones() as i16).div_euclid(12) - 1;
+
+    Pitch {
+        step,
+        alteration,
+        octave: octave as i8,
+    }
+}
+
+fn alteration(semitones: i8) -> Option<Alteration> {
+    match semitones {
+        -2 => Some(Alteration::DoubleFlat),
+        -1 => Some(Alteration::Flat),
+        0 => Some(Alteration::Natural),
+        1 => Some(Alteration::Sharp),
+        2 => Some(Alteration::DoubleSharp),
+        _ => None,
+    }
+}
+
+/// The parts of a Standard MIDI File needed to build a staff.
+#[derive(Debug)]
+struct File {
+    ticks_per_quarter: u16,
+    tracks: Vec<Vec<(u32, Event)>>,
+}
+
+#[derive(Clone, Debug)]
+enum Event {
+    NoteOn { key: u8 },
+    NoteOff { key: u8 },
+    TimeSignature(TimeSignature),
+    KeySignature(Option<KeySignature>),
+}
+
+impl File {
+    fn read(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader { bytes };
+
+        let (chunk_type, mut header) = reader.chunk()?;
+        if chunk_type != HEADER_CHUNK_TYPE {
+            return Err(Error::InvalidMidiChunkType {
+                expected: String::from_utf8_lossy(HEADER_CHUNK_TYPE).into_owned(),
+                found: String::from_utf8_lossy(chunk_type).into_owned(),
+            });
+        }
+
+        let format = header.u16()?;
+        if format > 1 {
+            return Err(Error::UnsupportedMidiFormat(format));
+        }
+
+        let num_tracks = header.u16()?;
+
+        let ticks_per_quarter = header.u16()?;
+        if ticks_per_quarter & 0x8000 != 0 || ticks_per_quarter == 0 {
+            return Err(Error::UnsupportedMidiTimeDivision);
+        }
+
+        let mut tracks = Vec::with_capacity(num_tracks as usize);
+
+        while tracks.len() < num_tracks as usize {
+            let (chunk_type, chunk) = reader.chunk()?;
+
+            // Chunks of unknown types must be ignored.
+            if chunk_type == TRACK_CHUNK_TYPE {
+                tracks.push(Self::read_track(chunk)?);
+            }
+        }
+
+        Ok(Self {
+            ticks_per_quarter,
+            tracks,
+        })
+    }
+
+    fn read_track(mut reader: Reader) -> Result<Vec<(u32, Event)>> {
+        let mut events = vec![];
+        let mut tick = 0;
+        let mut running_status = None;
+
+        while !reader.is_empty() {
+            tick += reader.variable_length_quantity()?;
+
+            let status = match reader.peek()? {
+                byte if byte & 0x80 != 0 => reader.u8()?,
+                _ => running_status.ok_or(Error::MissingMidiRunningStatus)?,
+            };
+
+            match status {
+                META_EVENT => {
+                    running_status = None;
+
+                    let meta_type = reader.u8()?;
+                    let length = reader.variable_length_quantity()?;
+                    let data = reader.take(length as usize)?;
+
+                    match (meta_type, data) {
+                        (META_TIME_SIGNATURE, [numerator, exponent, ..]) => {
+                            // The denominator is a power of two, from whole
+                            // notes to 128th notes.
+                            if *numerator == 0 || *exponent > 7 {
+                                return Err(Error::InvalidMidiTimeSignature {
+                                    numerator: *numerator,
+                                    exponent: *exponent,
+                                });
+                            }
+
+                            events.push((
+                                tick,
+                                Event::TimeSignature(TimeSignature::new(*numerator, 1 << exponent)),
+                            ));
+                        }
+                        (META_KEY_SIGNATURE, [fifths, ..]) => {
+                            let fifths = (*fifths as i8).clamp(-7, 7);
+                            events.push((
+                                tick,
+                                Event::KeySignature(KeySignature::from_fifths(fifths)),
+                            ));
+                        }
+                        _ => {}
+                    }
+                }
+                SYSTEM_EXCLUSIVE | SYSTEM_EXCLUSIVE_ESCAPE => {
+                    running_status = None;
+
+                    let length = reader.variable_length_quantity()?;
+                    reader.take(length as usize)?;
+                }
+                _ => {
+                    running_status = Some(status);
+
+                    let num_data_bytes = match status & 0xf0 {
+                        0xc0 | 0xd0 => 1,
+                        _ => 2,
+                    };
+                    let data = reader.take(num_data_bytes)?;
+
+                    match (status & 0xf0, data) {
+                        (NOTE_ON, [key, velocity]) if *velocity > 0 => {
+                            events.push((tick, Event::NoteOn { key: *key }))
+                        }
+                        (NOTE_ON | NOTE_OFF, [key, _]) => {
+                            events.push((tick, Event::NoteOff { key: *key }))
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        Ok(events)
+    }
+
+    /// Returns the events of every track, ordered by tick.
+    fn events(&self) -> Vec<(u32, Event)> {
+        let mut events: Vec<_> = self.tracks.iter().flatten().cloned().collect();
+        events.sort_by_key(|(tick, _)| *tick);
+
+        events
+    }
+}
+
+#[derive(Debug)]
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8]> {
+        if self.bytes.len() < length {
+            return Err(Error::UnexpectedEndOfMidiFile);
+        }
+
+        let (taken, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+
+        Ok(taken)
+    }
+
+    /// Returns the type and a reader for the data of the next chunk.
+    fn chunk(&mut self) -> Result<(&'a [u8], Reader<'a>)> {
+        let chunk_type = self.take(4)?;
+        let length = self.u32()?;
+        let bytes = self.take(length as usize)?;
+
+        Ok((chunk_type, Reader { bytes }))
+    }
+
+    fn peek(&self) -> Result<u8> {
+        self.bytes
+            .first()
+            .copied()
+            .ok_or(Error::UnexpectedEndOfMidiFile)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        Ok(u16::from_be_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn variable_length_quantity(&mut self) -> Result<u32> {
+        let mut value = 0;
+
+        loop {
+            let byte = self.u8()?;
+            value = (value << 7) | u32::from(byte & 0x7f);
+
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+    }
+}
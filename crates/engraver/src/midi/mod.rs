@@ -0,0 +1,21 @@
+mod export;
+mod import;
+
+pub use export::{staff_to_midi_file, Options};
+pub use import::{midi_file_to_staff, ImportOptions};
+
+const HEADER_CHUNK_TYPE: &[u8; 4] = b"MThd";
+const TRACK_CHUNK_TYPE: &[u8; 4] = b"MTrk";
+
+const NOTE_OFF: u8 = 0x80;
+const NOTE_ON: u8 = 0x90;
+const CONTROL_CHANGE: u8 = 0xb0;
+const META_EVENT: u8 = 0xff;
+
+const CONTROLLER_SUSTAIN_PEDAL: u8 = 64;
+const CONTROLLER_SOFT_PEDAL: u8 = 67;
+
+const META_END_OF_TRACK: u8 = 0x2f;
+const META_TEMPO: u8 = 0x51;
+const META_TIME_SIGNATURE: u8 = 0x58;
+const META_KEY_SIGNATURE: u8 = 0x59;
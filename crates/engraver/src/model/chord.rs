@@ -1,15 +1,22 @@
-use super::{Context, Duration, Pitch};
+use super::{Articulation, Context, Duration, Ornament, Pitch};
 use crate::render;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Chord {
     pub pitches: Vec<Pitch>,
     pub duration: Duration,
+    pub articulations: Vec<Articulation>,
+    pub ornament: Option<Ornament>,
     pub id: Option<String>,
 }
 
 impl Chord {
-    pub fn new<Pitches>(pitches: Pitches, duration: Duration, id: Option<String>) -> Self
+    pub fn new<Pitches>(
+        pitches: Pitches,
+        duration: Duration,
+        articulations: Vec<Articulation>,
+        id: Option<String>,
+    ) -> Self
     where
         Pitches: IntoIterator<Item = Pitch>,
     {
@@ -19,6 +26,8 @@ impl Chord {
         Self {
             pitches,
             duration,
+            articulations,
+            ornament: None,
             id,
         }
     }
@@ -29,6 +38,9 @@ impl Chord {
             render::input::chord::Note { y, accidental }
         });
 
-        render::input::Chord::new(notes, self.duration, None, self.id)
+        let mut chord =
+            render::input::Chord::new(notes, self.duration, self.articulations, None, self.id);
+        chord.ornament = self.ornament.map(Ornament::into_input);
+        chord
     }
 }
@@ -0,0 +1,168 @@
+use smufl::Glyph;
+
+use super::{Alteration, Step};
+use crate::render::{self, input::chord_symbol::Part};
+
+/// A chord symbol such as `Cmaj7`, `F#m7b5/E` or `Bb13(#11)`, which applies
+/// from the note, chord or rest which follows it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ChordSymbol {
+    pub root: Root,
+    pub quality: Quality,
+    /// The extensions of the chord, such as the 7 of `C7`, or the 6 and 9 of
+    /// `C6/9`. The highest extension implies the ones below it.
+    pub extensions: Vec<Extension>,
+    /// Degrees which are altered, added or omitted, such as the `#11` of
+    /// `Bb13(#11)`.
+    pub alterations: Vec<Degree>,
+    /// A bass note other than the root, such as the E of `F#m7b5/E`.
+    pub bass: Option<Root>,
+}
+
+/// The root or bass note of a chord symbol.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Root {
+    pub step: Step,
+    pub alteration: Alteration,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Quality {
+    #[default]
+    Major,
+    Minor,
+    Augmented,
+    Diminished,
+    HalfDiminished,
+    /// A suspended chord with a second in place of the third.
+    SuspendedSecond,
+    /// A suspended chord with a fourth in place of the third.
+    SuspendedFourth,
+    /// A power chord of the root and fifth.
+    Power,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Extension {
+    /// The degree, such as 6, 7, 9, 11 or 13.
+    pub degree: u8,
+    /// Whether the seventh of the chord is a major seventh, as in `Cmaj7` and
+    /// `Cmaj9`.
+    pub is_major: bool,
+}
+
+/// A degree which is altered, added or omitted.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Degree {
+    pub degree: u8,
+    pub change: Change,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Change {
+    Flat,
+    Sharp,
+    Add,
+    Omit,
+}
+
+impl Root {
+    fn push_parts(&self, parts: &mut Vec<Part>) {
+        let name = match self.step {
+            Step::C => "C",
+            Step::D => "D",
+            Step::E => "E",
+            Step::F => "F",
+            Step::G => "G",
+            Step::A => "A",
+            Step::B => "B",
+        };
+        parts.push(Part::Text(name.to_owned()));
+
+        if let Some(glyph) = accidental_glyph(self.alteration) {
+            parts.push(Part::Glyph(glyph));
+        }
+    }
+}
+
+impl ChordSymbol {
+    /// Converts the chord symbol into the text and chord symbol glyphs which
+    /// are drawn for it.
+    ///
+    /// Accidentals and the minor, diminished, half-diminished, augmented and
+    /// major seventh qualities are drawn with glyphs, and alterations are drawn
+    /// in parentheses after the extensions.
+    pub(crate) fn into_input(self) -> render::input::ChordSymbol {
+        let mut parts = vec![];
+
+        self.root.push_parts(&mut parts);
+
+        match self.quality {
+            Quality::Major
+            | Quality::SuspendedSecond
+            | Quality::SuspendedFourth
+            | Quality::Power => {}
+            Quality::Minor => parts.push(Part::Glyph(Glyph::CsymMinor)),
+            Quality::Augmented => parts.push(Part::Glyph(Glyph::CsymAugmented)),
+            Quality::Diminished => parts.push(Part::Glyph(Glyph::CsymDiminished)),
+            Quality::HalfDiminished => parts.push(Part::Glyph(Glyph::CsymHalfDiminished)),
+        }
+
+        for (index, extension) in self.extensions.iter().enumerate() {
+            if index > 0 {
+                parts.push(Part::Glyph(Glyph::CsymDiagonalArrangementSlash));
+            }
+
+            if extension.is_major {
+                parts.push(Part::Glyph(Glyph::CsymMajorSeventh));
+            }
+
+            parts.push(Part::Text(extension.degree.to_string()));
+        }
+
+        match self.quality {
+            Quality::SuspendedSecond => parts.push(Part::Text("sus2".to_owned())),
+            Quality::SuspendedFourth => parts.push(Part::Text("sus4".to_owned())),
+            Quality::Power => parts.push(Part::Text("5".to_owned())),
+            _ => {}
+        }
+
+        if !self.alterations.is_empty() {
+            parts.push(Part::Glyph(Glyph::CsymParensLeftTall));
+
+            for (index, alteration) in self.alterations.iter().enumerate() {
+                if index > 0 {
+                    parts.push(Part::Text(",".to_owned()));
+                }
+
+                match alteration.change {
+                    Change::Flat => parts.push(Part::Glyph(Glyph::CsymAccidentalFlat)),
+                    Change::Sharp => parts.push(Part::Glyph(Glyph::CsymAccidentalSharp)),
+                    Change::Add => parts.push(Part::Text("add".to_owned())),
+                    Change::Omit => parts.push(Part::Text("no".to_owned())),
+                }
+
+                parts.push(Part::Text(alteration.degree.to_string()));
+            }
+
+            parts.push(Part::Glyph(Glyph::CsymParensRightTall));
+        }
+
+        if let Some(bass) = self.bass {
+            parts.push(Part::Text("/".to_owned()));
+            bass.push_parts(&mut parts);
+        }
+
+        render::input::ChordSymbol { parts }
+    }
+}
+
+fn accidental_glyph(alteration: Alteration) -> Option<Glyph> {
+    match alteration {
+        Alteration::DoubleFlat => Some(Glyph::CsymAccidentalDoubleFlat),
+        Alteration::Flat => Some(Glyph::CsymAccidentalFlat),
+        Alteration::Natural => None,
+        Alteration::Sharp => Some(Glyph::CsymAccidentalSharp),
+        Alteration::DoubleSharp => Some(Glyph::CsymAccidentalDoubleSharp),
+    }
+}
@@ -3,12 +3,36 @@ use smufl::{Glyph, StaffSpaces};
 use super::Context;
 use crate::render;
 
+/// A clef, which positions pitches on the staff.
+///
+/// Pitches are written pitches, so notes on a staff with an octave clef sound
+/// an octave above or below their pitch.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Clef {
     Treble,
+    /// A treble clef sounding an octave lower, as used for tenor voices.
+    Treble8vb,
+    /// A treble clef sounding an octave higher.
+    Treble8va,
+    /// A G clef on the bottom line.
+    FrenchViolin,
+    /// A C clef on the bottom line.
+    Soprano,
+    /// A C clef on the second line.
+    MezzoSoprano,
     Alto,
     Tenor,
+    /// A C clef on the top line.
+    Baritone,
     Bass,
+    /// A bass clef sounding an octave lower.
+    Bass8vb,
+    /// An unpitched percussion clef, whose staff positions are those of the
+    /// treble clef.
+    Percussion,
+    /// A tablature clef for six-string instruments, whose staff positions are
+    /// those of the treble clef.
+    Tab,
 }
 
 impl Default for Clef {
@@ -18,16 +42,124 @@ impl Default for Clef {
 }
 
 impl Clef {
+    /// Returns the number of octaves by which notes sound above their written
+    /// pitch.
+    pub fn octave_shift(&self) -> i8 {
+        match self {
+            Self::Treble8va => 1,
+            Self::Treble8vb | Self::Bass8vb => -1,
+            _ => 0,
+        }
+    }
+
     pub(crate) fn into_input(self, context: &mut Context) -> render::input::Clef {
-        let (glyph, y, middle_c_position) = match self {
-            Clef::Treble => (Glyph::GClef, StaffSpaces(1.0), StaffSpaces(-1.0)),
-            Clef::Alto => (Glyph::CClef, StaffSpaces(2.0), StaffSpaces(2.0)),
-            Clef::Tenor => (Glyph::CClef, StaffSpaces(3.0), StaffSpaces(3.0)),
-            Clef::Bass => (Glyph::FClef, StaffSpaces(3.0), StaffSpaces(5.0)),
+        self.into_input_with_size(false, context)
+    }
+
+    /// Converts a clef change within the staff, which is drawn at a reduced
+    /// size, and positions the pitches which follow it.
+    pub(crate) fn into_change_input(self, context: &mut Context) -> render::input::Clef {
+        self.into_input_with_size(true, context)
+    }
+
+    fn into_input_with_size(self, is_change: bool, context: &mut Context) -> render::input::Clef {
+        // Clefs without a glyph for clef changes use the same glyph for both.
+        let (glyph, change_glyph, y, middle_c_position) = match self {
+            Clef::Treble => (
+                Glyph::GClef,
+                Glyph::GClefChange,
+                StaffSpaces(1.0),
+                StaffSpaces(-1.0),
+            ),
+            Clef::Treble8vb => (
+                Glyph::GClef8Vb,
+                Glyph::GClef8Vb,
+                StaffSpaces(1.0),
+                StaffSpaces(-1.0),
+            ),
+            Clef::Treble8va => (
+                Glyph::GClef8Va,
+                Glyph::GClef8Va,
+                StaffSpaces(1.0),
+                StaffSpaces(-1.0),
+            ),
+            Clef::FrenchViolin => (
+                Glyph::GClef,
+                Glyph::GClefChange,
+                StaffSpaces(0.0),
+                StaffSpaces(-2.0),
+            ),
+            Clef::Soprano => (
+                Glyph::CClef,
+                Glyph::CClefChange,
+                StaffSpaces(0.0),
+                StaffSpaces(0.0),
+            ),
+            Clef::MezzoSoprano => (
+                Glyph::CClef,
+                Glyph::CClefChange,
+                StaffSpaces(1.0),
+                StaffSpaces(1.0),
+            ),
+            Clef::Alto => (
+                Glyph::CClef,
+                Glyph::CClefChange,
+                StaffSpaces(2.0),
+                StaffSpaces(2.0),
+            ),
+            Clef::Tenor => (
+                Glyph::CClef,
+                Glyph::CClefChange,
+                StaffSpaces(3.0),
+                StaffSpaces(3.0),
+            ),
+            Clef::Baritone => (
+                Glyph::CClef,
+                Glyph::CClefChange,
+                StaffSpaces(4.0),
+                StaffSpaces(4.0),
+            ),
+            Clef::Bass => (
+                Glyph::FClef,
+                Glyph::FClefChange,
+                StaffSpaces(3.0),
+                StaffSpaces(5.0),
+            ),
+            Clef::Bass8vb => (
+                Glyph::FClef8Vb,
+                Glyph::FClef8Vb,
+                StaffSpaces(3.0),
+                StaffSpaces(5.0),
+            ),
+            // Percussion and tab clefs are centered on the staff, whatever
+            // its number of lines.
+            Clef::Percussion => (
+                Glyph::UnpitchedPercussionClef1,
+                Glyph::UnpitchedPercussionClef1,
+                context.staff_geometry.middle(),
+                StaffSpaces(-1.0),
+            ),
+            Clef::Tab => {
+                let glyph = if context.staff_geometry.lines <= 4 {
+                    Glyph::_4StringTabClef
+                } else {
+                    Glyph::_6StringTabClef
+                };
+
+                (
+                    glyph,
+                    glyph,
+                    context.staff_geometry.middle(),
+                    StaffSpaces(-1.0),
+                )
+            }
         };
 
         context.middle_c_position = middle_c_position;
 
-        render::input::Clef { glyph, y }
+        render::input::Clef {
+            glyph: if is_change { change_glyph } else { glyph },
+            y,
+        }
     }
 }
@@ -2,11 +2,17 @@ use std::{collections::HashMap, mem};
 
 use smufl::StaffSpaces;
 
-use super::{Alteration, KeySignature, Pitch, Step};
+use super::{Alteration, KeySignature, Pitch, StaffGeometry, Step, TimeSignature};
 
 #[derive(Debug)]
 pub struct Context {
     pub middle_c_position: StaffSpaces,
+    pub staff_geometry: StaffGeometry,
+    /// The time signature in effect, which changes at the start of measures.
+    pub time_signature: Option<TimeSignature>,
+    /// The number of octaves by which the notes of the octave line in progress
+    /// sound higher (or lower, if negative) than they are drawn.
+    pub octave_shift: i8,
     key_signature: Option<KeySignature>,
     previous_measure_alterations: HashMap<(Step, i8), Alteration>,
     current_measure_alterations: HashMap<(Step, i8), Alteration>,
@@ -22,6 +28,9 @@ impl Default for Context {
     fn default() -> Self {
         Self {
             middle_c_position: StaffSpaces(-1.0),
+            staff_geometry: Default::default(),
+            time_signature: Default::default(),
+            octave_shift: 0,
             key_signature: Default::default(),
             previous_measure_alterations: Default::default(),
             current_measure_alterations: Default::default(),
@@ -30,8 +39,17 @@ impl Default for Context {
 }
 
 impl Context {
-    pub fn start_key_signature(&mut self, key_signature: KeySignature) {
-        self.key_signature = Some(key_signature)
+    /// Starts a key signature, or no key signature if `key_signature` is
+    /// `None`, after which accidentals of earlier measures no longer carry
+    /// over.
+    pub fn start_key_signature(&mut self, key_signature: Option<KeySignature>) {
+        self.key_signature = key_signature;
+        self.previous_measure_alterations.clear();
+        self.current_measure_alterations.clear();
+    }
+
+    pub fn key_signature(&self) -> Option<KeySignature> {
+        self.key_signature
     }
 
     pub fn start_measure(&mut self) {
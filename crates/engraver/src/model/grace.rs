@@ -0,0 +1,24 @@
+use super::{Context, Note};
+use crate::render;
+pub use crate::render::input::grace::Kind;
+
+/// A group of grace notes before the next note or chord, which take no time in
+/// the measure.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Grace {
+    pub kind: Kind,
+    pub notes: Vec<Note>,
+}
+
+impl Grace {
+    pub(crate) fn into_input(self, context: &mut Context) -> render::input::Grace {
+        render::input::Grace {
+            kind: self.kind,
+            notes: self
+                .notes
+                .into_iter()
+                .map(|note| note.into_input(context))
+                .collect(),
+        }
+    }
+}
@@ -19,52 +19,42 @@ impl KeySignature {
         Self { kind, num }
     }
 
-    pub(crate) fn alterations(self) -> HashMap<Step, Alteration> {
-        let alteration = match self.kind {
-            Kind::Sharps => Alteration::Sharp,
-            Kind::Flats => Alteration::Flat,
-        };
+    /// Returns the key signature at the given position on the circle of fifths,
+    /// with sharps counted as positive and flats as negative, or `None` if
+    /// there are no sharps or flats.
+    ///
+    /// # Panics
+    ///
+    /// The function will panic if `fifths` has more sharps or flats than there
+    /// are steps.
+    pub fn from_fifths(fifths: i8) -> Option<Self> {
+        match fifths {
+            0 => None,
+            1.. => Some(Self::new(Kind::Sharps, fifths as u8)),
+            _ => Some(Self::new(Kind::Flats, fifths.unsigned_abs())),
+        }
+    }
 
+    /// Returns the position of the key signature on the circle of fifths,
+    /// with sharps counted as positive and flats as negative.
+    pub fn fifths(&self) -> i8 {
         match self.kind {
-            Kind::Sharps => [
-                Step::F,
-                Step::C,
-                Step::G,
-                Step::D,
-                Step::A,
-                Step::E,
-                Step::B,
-            ],
-            Kind::Flats => [
-                Step::B,
-                Step::E,
-                Step::A,
-                Step::D,
-                Step::G,
-                Step::C,
-                Step::F,
-            ],
+            Kind::Sharps => self.num as i8,
+            Kind::Flats => -(self.num as i8),
         }
-        .into_iter()
-        .map(|step| (step, alteration))
-        .take(self.num as usize)
-        .collect()
     }
 
-    pub(crate) fn into_input(self, context: &mut Context) -> crate::render::input::KeySignature {
-        context.start_key_signature(self);
+    pub(crate) fn alterations(self) -> HashMap<Step, Alteration> {
+        let alteration = self.kind.alteration();
 
-        let octave_offset = if context.middle_c_position < StaffSpaces::zero() {
-            4
-        } else if context.middle_c_position >= StaffSpaces::zero()
-            && context.middle_c_position <= StaffSpaces(4.0)
-        {
-            3
-        } else {
-            2
-        };
+        self.steps().map(|(step, _)| (step, alteration)).collect()
+    }
 
-        let pitches = match self.kind {
+    /// Returns the steps of the sharps or flats in the order in which they are
+    /// drawn, with the octave above the lowest octave of the key signature in
+    /// which each is drawn.
+    fn steps(self) -> impl Iterator<Item = (Step, i8)> {
+        match self.kind {
             Kind::Sharps => [
                 (Step::F, 1),
                 (Step::C, 1),
@@ -85,13 +75,109 @@ impl KeySignature {
             ],
         }
         .into_iter()
-        .map(|(step, octave)| step.y(octave + octave_offset, context.middle_c_position))
         .take(self.num as usize)
-        .collect();
+    }
+
+    /// Returns the positions of the sharps or flats on the staff.
+    fn positions(self, middle_c_position: StaffSpaces) -> Vec<StaffSpaces> {
+        let octave_offset = if middle_c_position < StaffSpaces::zero() {
+            4
+        } else if middle_c_position >= StaffSpaces::zero() && middle_c_position <= StaffSpaces(4.0)
+        {
+            3
+        } else {
+            2
+        };
+
+        self.steps()
+            .map(|(step, octave)| step.y(octave + octave_offset, middle_c_position))
+            .collect()
+    }
+
+    pub(crate) fn into_input(self, context: &mut Context) -> crate::render::input::KeySignature {
+        context.start_key_signature(Some(self));
 
         crate::render::input::KeySignature {
             kind: self.kind,
-            pitches,
+            pitches: self.positions(context.middle_c_position),
+            naturals: vec![],
         }
     }
+
+    /// Returns the key signature which changes from the current key signature
+    /// of the context to `new`, with naturals which cancel the old key
+    /// signature following the `cancellation` convention.
+    pub(crate) fn change_into_input(
+        new: Option<Self>,
+        cancellation: Cancellation,
+        context: &mut Context,
+    ) -> crate::render::input::KeySignature {
+        let old = context.key_signature();
+        context.start_key_signature(new);
+
+        let naturals = match old {
+            Some(old) => {
+                let cancelled = cancellation.cancelled_steps(old, new);
+                let positions = old.positions(context.middle_c_position);
+
+                old.steps()
+                    .zip(positions)
+                    .filter(|((step, _), _)| cancelled.contains(step))
+                    .map(|(_, position)| position)
+                    .collect()
+            }
+            None => vec![],
+        };
+
+        crate::render::input::KeySignature {
+            kind: new.map(|new| new.kind).unwrap_or_default(),
+            pitches: new
+                .map(|new| new.positions(context.middle_c_position))
+                .unwrap_or_default(),
+            naturals,
+        }
+    }
+}
+
+impl Kind {
+    fn alteration(self) -> Alteration {
+        match self {
+            Kind::Sharps => Alteration::Sharp,
+            Kind::Flats => Alteration::Flat,
+        }
+    }
+}
+
+/// The convention for which sharps or flats of the old key signature are
+/// cancelled with naturals at a key signature change.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Cancellation {
+    /// Cancels each sharp or flat of the old key signature which is not in the
+    /// new key signature, such as all four sharps when changing from four
+    /// sharps to one flat, or two of them when changing to two sharps.
+    #[default]
+    Removed,
+    /// Cancels the old key signature only when the new key signature has no
+    /// sharps or flats, which is the modern convention.
+    OpenKeyOnly,
+    /// Never cancels the old key signature.
+    Never,
+}
+
+impl Cancellation {
+    /// Returns the steps of the old key signature which are cancelled when
+    /// changing to the new key signature.
+    pub(crate) fn cancelled_steps(self, old: KeySignature, new: Option<KeySignature>) -> Vec<Step> {
+        let new_alterations = new.map(|new| new.alterations()).unwrap_or_default();
+        let alteration = old.kind.alteration();
+
+        old.steps()
+            .map(|(step, _)| step)
+            .filter(|step| match self {
+                Self::Removed => new_alterations.get(step) != Some(&alteration),
+                Self::OpenKeyOnly => new.is_none(),
+                Self::Never => false,
+            })
+            .collect()
+    }
 }
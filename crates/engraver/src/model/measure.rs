@@ -1,23 +1,160 @@
-use super::{Barline, Chord, Context, Note, Rest};
-use crate::render;
+use super::{
+    duration, key_signature::Cancellation, Annotation, Barline, Chord, ChordSymbol, Clef, Context,
+    Duration, Dynamic, Ending, Grace, Hairpin, KeySignature, Lyric, Navigation, Note, OctaveLine,
+    Pedal, Rest, TimeSignature,
+};
+use crate::render::{
+    self,
+    input::{Beam, FullMeasureRest},
+};
 
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct Measure {
+    pub start_barline: Option<Barline>,
+    /// A key signature change at the start of the measure, which is
+    /// `Some(None)` for a change to a key signature without sharps or flats.
+    pub key_signature: Option<Option<KeySignature>>,
+    /// A time signature change at the start of the measure.
+    pub time_signature: Option<TimeSignature>,
     pub elements: Vec<Element>,
     pub barline: Barline,
+    pub ending: Option<Ending>,
+    pub navigation: Vec<Navigation>,
+    /// The lyrics of each verse, whose lyrics belong in turn to the notes and
+    /// chords of the measure.
+    pub lyrics: Vec<Vec<Lyric>>,
+    /// Whether the measure ends its system, so that the next measure starts a
+    /// new system below it.
+    pub system_break: bool,
 }
 
 impl Measure {
-    pub(crate) fn into_input(self, context: &mut Context) -> render::input::Measure {
+    /// Returns the length of the notes, chords and rests of the measure, in
+    /// [`DIVISIONS_PER_QUARTER`](duration::DIVISIONS_PER_QUARTER) divisions of
+    /// a quarter note, not counting any full-measure rest.
+    pub fn divisions(&self) -> u32 {
+        self.elements
+            .iter()
+            .filter_map(Element::duration)
+            .map(|duration| duration.divisions())
+            .sum()
+    }
+
+    /// Returns whether the measure has a full-measure rest.
+    pub fn has_full_measure_rest(&self) -> bool {
+        self.elements
+            .iter()
+            .any(|element| matches!(element, Element::FullMeasureRest))
+    }
+
+    pub(crate) fn into_input(
+        self,
+        context: &mut Context,
+        cancellation: Cancellation,
+    ) -> render::input::Measure {
         context.start_measure();
 
+        if let Some(time_signature) = &self.time_signature {
+            context.time_signature = Some(time_signature.clone());
+        }
+
+        let key_signature = self
+            .key_signature
+            .map(|new| KeySignature::change_into_input(new, cancellation, context))
+            .filter(|key_signature| {
+                !key_signature.pitches.is_empty() || !key_signature.naturals.is_empty()
+            });
+
+        let mut elements: Vec<_> = self
+            .elements
+            .into_iter()
+            .map(|element| element.into_input(context))
+            .collect();
+
+        if let Some(beat_groups) = context
+            .time_signature
+            .as_ref()
+            .and_then(TimeSignature::beat_groups)
+        {
+            beam_eighths(&mut elements, &beat_groups);
+        }
+
         render::input::Measure {
-            elements: self
-                .elements
-                .into_iter()
-                .map(|element| element.into_input(context))
-                .collect(),
+            start_barline: self.start_barline,
+            key_signature,
+            time_signature: self.time_signature,
+            elements,
             barline: self.barline,
+            ending: self.ending,
+            navigation: self.navigation,
+            lyrics: self.lyrics,
+            system_break: self.system_break,
+        }
+    }
+}
+
+/// Beams together the consecutive eighth notes and chords of a measure which
+/// are in the same group of beats, given by the lengths of the groups.
+///
+/// Rests, clef changes and grace notes break a beam.
+fn beam_eighths(elements: &mut [render::input::measure::Element], beat_groups: &[u32]) {
+    let eighth = Duration {
+        value: duration::Value::Eighth,
+        dots: None,
+    };
+    let group_ends: Vec<u32> = beat_groups
+        .iter()
+        .scan(0, |end, length| {
+            *end += length;
+            Some(*end)
+        })
+        .collect();
+
+    let mut runs: Vec<Vec<usize>> = vec![];
+    let mut run: Vec<usize> = vec![];
+    let mut run_group = None;
+    let mut position = 0;
+
+    for (index, element) in elements.iter().enumerate() {
+        let (duration, is_note) = match element {
+            render::input::measure::Element::Note(note) => (note.duration, true),
+            render::input::measure::Element::Chord(chord) => (chord.duration, true),
+            render::input::measure::Element::Rest(rest) => (rest.duration, false),
+            render::input::measure::Element::Clef(_)
+            | render::input::measure::Element::Grace(_) => {
+                runs.push(std::mem::take(&mut run));
+                continue;
+            }
+            _ => continue,
+        };
+
+        let start = position;
+        position += duration.divisions();
+
+        let group = group_ends.iter().position(|end| start < *end);
+        let is_beamable = is_note
+            && duration == eighth
+            && group.is_some_and(|group| position <= group_ends[group]);
+
+        if !is_beamable || group != run_group {
+            runs.push(std::mem::take(&mut run));
+        }
+
+        if is_beamable {
+            run.push(index);
+            run_group = group;
+        }
+    }
+
+    runs.push(run);
+
+    for run in runs.into_iter().filter(|run| run.len() > 1) {
+        for (index, beam) in [(run[0], Beam::Begin), (run[run.len() - 1], Beam::End)] {
+            match &mut elements[index] {
+                render::input::measure::Element::Note(note) => note.beam = Some(beam),
+                render::input::measure::Element::Chord(chord) => chord.beam = Some(beam),
+                _ => {}
+            }
         }
     }
 }
@@ -27,9 +164,42 @@ pub enum Element {
     Note(Note),
     Chord(Chord),
     Rest(Rest),
+    Dynamic(Dynamic),
+    Hairpin(Hairpin),
+    /// A clef change, which applies to the pitches which follow it.
+    Clef(Clef),
+    /// Grace notes before the next note or chord, which take no time.
+    Grace(Grace),
+    ChordSymbol(ChordSymbol),
+    Annotation(Annotation),
+    /// The start or end of an octave line, under which pitches are drawn an
+    /// octave or two away from where they sound.
+    OctaveLine(OctaveLine),
+    Pedal(Pedal),
+    /// A rest which lasts for the whole measure, whatever its time signature,
+    /// and is the only note, chord or rest in it.
+    FullMeasureRest,
 }
 
 impl Element {
+    /// Returns the duration of a note, chord or rest.
+    pub fn duration(&self) -> Option<Duration> {
+        match self {
+            Element::Note(note) => Some(note.duration),
+            Element::Chord(chord) => Some(chord.duration),
+            Element::Rest(rest) => Some(rest.duration),
+            Element::Dynamic(_)
+            | Element::Hairpin(_)
+            | Element::ChordSymbol(_)
+            | Element::Annotation(_)
+            | Element::OctaveLine(_)
+            | Element::Pedal(_)
+            | Element::Clef(_)
+            | Element::Grace(_)
+            | Element::FullMeasureRest => None,
+        }
+    }
+
     pub(crate) fn into_input(self, context: &mut Context) -> render::input::measure::Element {
         match self {
             Element::Note(note) => render::input::measure::Element::Note(note.into_input(context)),
@@ -37,6 +207,44 @@ impl Element {
                 render::input::measure::Element::Chord(chord.into_input(context))
             }
             Element::Rest(rest) => render::input::measure::Element::Rest(rest),
+            Element::Dynamic(dynamic) => render::input::measure::Element::Dynamic(dynamic),
+            Element::Hairpin(hairpin) => render::input::measure::Element::Hairpin(hairpin),
+            Element::Clef(clef) => {
+                render::input::measure::Element::Clef(clef.into_change_input(context))
+            }
+            Element::Grace(grace) => {
+                render::input::measure::Element::Grace(grace.into_input(context))
+            }
+            Element::ChordSymbol(chord_symbol) => {
+                render::input::measure::Element::ChordSymbol(chord_symbol.into_input())
+            }
+            Element::Annotation(annotation) => {
+                render::input::measure::Element::Annotation(annotation)
+            }
+            Element::OctaveLine(octave_line) => {
+                context.octave_shift = match octave_line {
+                    OctaveLine::Start(kind) => kind.octaves(),
+                    OctaveLine::End => 0,
+                };
+
+                render::input::measure::Element::OctaveLine(octave_line)
+            }
+            Element::Pedal(pedal) => render::input::measure::Element::Pedal(pedal),
+            Element::FullMeasureRest => {
+                // Measures of a breve or longer, such as 4/2, are filled by a
+                // breve rest rather than a whole rest.
+                let is_breve = context
+                    .time_signature
+                    .as_ref()
+                    .and_then(TimeSignature::divisions)
+                    .is_some_and(|divisions| divisions >= 2 * duration::Value::Whole.divisions());
+
+                render::input::measure::Element::FullMeasureRest(if is_breve {
+                    FullMeasureRest::Breve
+                } else {
+                    FullMeasureRest::Whole
+                })
+            }
         }
     }
 }
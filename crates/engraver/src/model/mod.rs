@@ -1,5 +1,8 @@
+pub mod chord_symbol;
+pub mod grace;
 pub mod key_signature;
 pub mod measure;
+pub mod ornament;
 
 mod chord;
 mod clef;
@@ -9,12 +12,19 @@ mod pitch;
 mod staff;
 
 pub use chord::Chord;
+pub use chord_symbol::ChordSymbol;
 pub use clef::Clef;
-pub(crate) use context::Context;
+pub(crate) use context::{AccidentalState, Context};
+pub use grace::Grace;
 pub use key_signature::KeySignature;
 pub use measure::Measure;
 pub use note::Note;
+pub use ornament::Ornament;
 pub use pitch::{Alteration, Pitch, Step};
 pub use staff::Staff;
 
-pub use crate::render::input::{duration, Barline, Duration, Rest, TimeSignature};
+pub use crate::render::input::{
+    annotation, duration, hairpin, lyric, navigation, octave_line, pedal, time_signature,
+    Annotation, Articulation, Barline, Duration, Dynamic, Ending, Hairpin, Lyric, Navigation,
+    OctaveLine, Pedal, Rest, StaffGeometry, TimeSignature,
+};
@@ -1,10 +1,12 @@
-use super::{Context, Duration, Pitch};
+use super::{Articulation, Context, Duration, Ornament, Pitch};
 use crate::render;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Note {
     pub pitch: Pitch,
     pub duration: Duration,
+    pub articulations: Vec<Articulation>,
+    pub ornament: Option<Ornament>,
     pub id: Option<String>,
 }
 
@@ -16,6 +18,8 @@ impl Note {
             y,
             accidental,
             duration: self.duration,
+            articulations: self.articulations,
+            ornament: self.ornament.map(Ornament::into_input),
             beam: None,
             id: self.id,
         }
@@ -0,0 +1,24 @@
+use super::Alteration;
+use crate::render;
+pub use crate::render::input::ornament::Kind;
+
+/// An ornament attached to a note or chord, whose accidentals alter its
+/// auxiliary notes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Ornament {
+    pub kind: Kind,
+    /// The alteration of the upper auxiliary note, drawn above the ornament.
+    pub accidental_above: Option<Alteration>,
+    /// The alteration of the lower auxiliary note, drawn below the ornament.
+    pub accidental_below: Option<Alteration>,
+}
+
+impl Ornament {
+    pub(crate) fn into_input(self) -> render::input::Ornament {
+        render::input::Ornament {
+            kind: self.kind,
+            accidental_above: self.accidental_above.map(Into::into),
+            accidental_below: self.accidental_below.map(Into::into),
+        }
+    }
+}
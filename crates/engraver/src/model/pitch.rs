@@ -1,7 +1,7 @@
 use smufl::StaffSpaces;
 use strum_macros::EnumCount;
 
-use super::{context::AccidentalState, Context};
+use super::{AccidentalState, Context};
 use crate::render::input::Accidental;
 
 #[derive(Clone, Copy, Debug, EnumCount, Eq, Hash, PartialEq)]
@@ -16,6 +16,19 @@ pub enum Step {
 }
 
 impl Step {
+    /// Returns the number of semitones between C and the step.
+    pub fn semitones(&self) -> i8 {
+        match self {
+            Self::C => 0,
+            Self::D => 2,
+            Self::E => 4,
+            Self::F => 5,
+            Self::G => 7,
+            Self::A => 9,
+            Self::B => 11,
+        }
+    }
+
     pub(crate) fn y(&self, octave: i8, middle_c_position: StaffSpaces) -> StaffSpaces {
         StaffSpaces(match self {
             Self::C => 0.0,
@@ -39,6 +52,20 @@ pub enum Alteration {
     DoubleSharp,
 }
 
+impl Alteration {
+    /// Returns the number of semitones by which the alteration raises (or
+    /// lowers, if negative) a step.
+    pub fn semitones(&self) -> i8 {
+        match self {
+            Self::DoubleFlat => -2,
+            Self::Flat => -1,
+            Self::Natural => 0,
+            Self::Sharp => 1,
+            Self::DoubleSharp => 2,
+        }
+    }
+}
+
 impl Default for Alteration {
     fn default() -> Self {
         Self::Natural
@@ -67,8 +94,21 @@ pub struct Pitch {
 const OCTAVE_IN_STAFF_SPACES: StaffSpaces = StaffSpaces(3.5);
 
 impl Pitch {
+    /// Returns the MIDI note number of the pitch, where middle C (C4) is 60.
+    pub fn midi_note_number(&self) -> i16 {
+        (self.octave as i16 + 1) * 12
+            + self.step.semitones() as i16
+            + self.alteration.semitones() as i16
+    }
+
+    /// Returns the position of the pitch on the staff, which is drawn an octave
+    /// or two away from where it sounds under an octave line, and its
+    /// accidental, if it needs one.
     pub(crate) fn into_input(self, context: &mut Context) -> (StaffSpaces, Option<Accidental>) {
-        let y = self.step.y(self.octave, context.middle_c_position);
+        let y = self.step.y(
+            self.octave - context.octave_shift,
+            context.middle_c_position,
+        );
 
         let accidental = match context.add_pitch(self) {
             AccidentalState::NeedsAccidental => Some(self.alteration.into()),
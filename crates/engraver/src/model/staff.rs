@@ -1,19 +1,246 @@
-use super::{Clef, Context, KeySignature, Measure, TimeSignature};
+use super::{
+    duration, key_signature::Cancellation, measure, navigation::Target, Barline, Clef, Context,
+    KeySignature, Measure, Navigation, StaffGeometry, TimeSignature,
+};
 use crate::render;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Staff {
+    /// The lines and size of the staff.
+    pub geometry: StaffGeometry,
     pub clef: Clef,
     pub key_signature: Option<KeySignature>,
     pub time_signature: Option<TimeSignature>,
+    /// Which sharps or flats are cancelled at key signature changes.
+    pub key_cancellation: Cancellation,
     pub measures: Vec<Measure>,
 }
 
+impl Staff {
+    /// Returns the key signature in effect in the measure at the given index,
+    /// following any key signature changes up to and including it.
+    pub fn key_signature_at(&self, index: usize) -> Option<KeySignature> {
+        self.measures[..=index]
+            .iter()
+            .rev()
+            .find_map(|measure| measure.key_signature)
+            .unwrap_or(self.key_signature)
+    }
+
+    /// Returns the clef in effect at the start of the measure at the given
+    /// index, following any clef changes in earlier measures.
+    pub fn clef_at(&self, index: usize) -> Clef {
+        self.measures[..index]
+            .iter()
+            .flat_map(|measure| &measure.elements)
+            .rev()
+            .find_map(|element| match element {
+                measure::Element::Clef(clef) => Some(*clef),
+                _ => None,
+            })
+            .unwrap_or(self.clef)
+    }
+
+    /// Returns the time signature in effect in the measure at the given index,
+    /// following any time signature changes up to and including it.
+    pub fn time_signature_at(&self, index: usize) -> Option<&TimeSignature> {
+        self.measures[..=index]
+            .iter()
+            .rev()
+            .find_map(|measure| measure.time_signature.as_ref())
+            .or(self.time_signature.as_ref())
+    }
+
+    /// Returns the length of the measure at the given index, in
+    /// [`DIVISIONS_PER_QUARTER`](duration::DIVISIONS_PER_QUARTER) divisions of
+    /// a quarter note, which is that of a full-measure rest.
+    ///
+    /// The length comes from the time signature in effect, or is a whole note
+    /// when there is none or its number of beats is unspecified.
+    pub fn measure_divisions_at(&self, index: usize) -> u32 {
+        self.time_signature_at(index)
+            .and_then(TimeSignature::divisions)
+            .unwrap_or(duration::Value::Whole.divisions())
+    }
+
+    /// Returns the indices of the measures whose notes, chords and rests do not
+    /// add up to the length of a measure in the time signature in effect.
+    ///
+    /// The first and last measures may be shorter, as a pickup measure and the
+    /// measure which completes it. Measures without a time signature, or whose
+    /// number of beats is unspecified, are not checked, nor are measures with
+    /// a full-measure rest or without notes, chords or rests.
+    pub fn measures_of_wrong_length(&self) -> Vec<usize> {
+        let last = self.measures.len().saturating_sub(1);
+
+        self.measures
+            .iter()
+            .enumerate()
+            .filter(|(index, measure)| {
+                let Some(expected) = self
+                    .time_signature_at(*index)
+                    .and_then(TimeSignature::divisions)
+                else {
+                    return false;
+                };
+                let divisions = measure.divisions();
+                let may_be_shorter = *index == 0 || *index == last;
+
+                !measure.has_full_measure_rest()
+                    && divisions != 0
+                    && (divisions > expected || (divisions < expected && !may_be_shorter))
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Returns the indices of the measures in the order in which they are
+    /// played, following repeats, endings and navigation markers.
+    ///
+    /// Repeated sections are played once for each of their endings, or twice
+    /// without endings. After a D.C. or D.S., repeats are not taken and only
+    /// the last ending of each repeated section is played.
+    pub fn playback_order(&self) -> Vec<usize> {
+        let mut order = vec![];
+
+        let mut index = 0;
+        let mut repeat_start = 0;
+        let mut pass = 1;
+        let mut jump: Option<Option<Target>> = None;
+
+        while let Some(measure) = self.measures.get(index) {
+            if index != repeat_start && self.starts_repeat(index) {
+                repeat_start = index;
+                pass = 1;
+            }
+
+            let is_played = match &measure.ending {
+                None => true,
+                Some(_) if jump.is_some() => self.is_in_last_ending(index),
+                Some(ending) => ending.numbers.contains(&pass),
+            };
+
+            if !is_played {
+                index += 1;
+                continue;
+            }
+
+            order.push(index);
+
+            match jump {
+                Some(Some(Target::Fine)) if measure.navigation.contains(&Navigation::Fine) => {
+                    break;
+                }
+                Some(Some(Target::Coda)) if measure.navigation.contains(&Navigation::ToCoda) => {
+                    match self.position_of(Navigation::Coda) {
+                        Some(coda) if coda > index => {
+                            index = coda;
+                            continue;
+                        }
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+
+            if jump.is_none() {
+                if measure.barline.is_end_repeat() && self.is_repeated_again(index, pass) {
+                    pass += 1;
+                    index = repeat_start;
+                    continue;
+                }
+
+                let destination =
+                    measure
+                        .navigation
+                        .iter()
+                        .find_map(|navigation| match navigation {
+                            Navigation::DaCapo(target) => Some((Some(0), *target)),
+                            Navigation::DalSegno(target) => {
+                                Some((self.position_of(Navigation::Segno), *target))
+                            }
+                            _ => None,
+                        });
+
+                if let Some((Some(destination), target)) = destination {
+                    jump = Some(target);
+                    index = destination;
+                    repeat_start = destination;
+                    pass = 1;
+                    continue;
+                }
+            }
+
+            index += 1;
+        }
+
+        order
+    }
+
+    /// Returns whether a repeated section starts at the measure, either with a
+    /// start repeat or after an end-start repeat.
+    fn starts_repeat(&self, index: usize) -> bool {
+        self.measures[index].start_barline == Some(Barline::StartRepeat)
+            || index
+                .checked_sub(1)
+                .is_some_and(|previous| self.measures[previous].barline == Barline::EndStartRepeat)
+    }
+
+    /// Returns whether the repeated section which ends at the measure is played
+    /// again after the given pass, which is when the measure or the endings
+    /// which follow it have an ending for the next pass, or once if there are
+    /// no endings.
+    fn is_repeated_again(&self, index: usize, pass: u8) -> bool {
+        if self.measures[index].ending.is_none() {
+            return pass == 1;
+        }
+
+        self.measures[index..]
+            .iter()
+            .map_while(|measure| measure.ending.as_ref())
+            .any(|ending| ending.numbers.contains(&(pass + 1)))
+    }
+
+    /// Returns whether the measure is in the last of the consecutive endings
+    /// it is part of.
+    fn is_in_last_ending(&self, index: usize) -> bool {
+        let ending = &self.measures[index].ending;
+
+        self.measures[index..]
+            .iter()
+            .take_while(|measure| measure.ending.is_some())
+            .all(|measure| &measure.ending == ending)
+    }
+
+    fn position_of(&self, navigation: Navigation) -> Option<usize> {
+        self.measures
+            .iter()
+            .position(|measure| measure.navigation.contains(&navigation))
+    }
+}
+
 impl From<Staff> for render::input::Staff {
-    fn from(value: Staff) -> Self {
+    fn from(mut value: Staff) -> Self {
         let mut context = Context::default();
+        context.staff_geometry = value.geometry;
+        context.time_signature = value.time_signature.clone();
+
+        // Key signature changes are preceded by a double barline, unless a
+        // different barline was chosen.
+        for index in 1..value.measures.len() {
+            let next = &value.measures[index];
+
+            if next.key_signature.is_some() && next.start_barline.is_none() {
+                let barline = &mut value.measures[index - 1].barline;
+
+                if *barline == Barline::Thin {
+                    *barline = Barline::Double;
+                }
+            }
+        }
 
         Self {
+            geometry: value.geometry,
             clef: Some(value.clef.into_input(&mut context)),
             key_signature: value
                 .key_signature
@@ -22,8 +249,12 @@ impl From<Staff> for render::input::Staff {
             measures: value
                 .measures
                 .into_iter()
-                .map(|measure| measure.into_input(&mut context))
+                .map(|measure| measure.into_input(&mut context, value.key_cancellation))
                 .collect(),
+            measure_numbering: render::input::MeasureNumbering::default(),
+            multi_measure_rests: None,
+            pedal_style: render::input::pedal::Style::default(),
+            text_metrics: render::TextMetrics::default(),
         }
     }
 }
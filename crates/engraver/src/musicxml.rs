@@ -0,0 +1,1183 @@
+use std::{collections::HashSet, fmt::Write, mem};
+
+use crate::{
+    model::{
+        annotation::{self, Annotation},
+        chord_symbol,
+        duration::{self, DIVISIONS_PER_QUARTER},
+        grace, hairpin, lyric, measure, ornament, pedal, time_signature, AccidentalState,
+        Alteration, Articulation, Barline, Chord, ChordSymbol, Clef, Context, Duration, Dynamic,
+        Ending, Grace, Hairpin, KeySignature, Lyric, Measure, Navigation, Note, OctaveLine,
+        Ornament, Pedal, Pitch, Rest, Staff, StaffGeometry, Step, TimeSignature,
+    },
+    Error, Result,
+};
+
+const PART_ID: &str = "P1";
+
+/// Returns a [MusicXML 4.0](https://www.w3.org/2021/06/musicxml40/) partwise
+/// document containing the staff as a single part.
+///
+/// The `<divisions>` of the document are the fewest divisions of a quarter
+/// note which represent every duration in the staff as a whole number.
+///
+/// The ids of notes, chords and rests are written as `id` attributes, so they
+/// must be valid and unique XML ids, which start with a letter or an
+/// underscore and only contain letters, digits, hyphens, underscores and
+/// periods. Otherwise an [`Error::InvalidMusicXmlId`] or
+/// [`Error::DuplicateMusicXmlId`] is returned.
+pub fn staff_to_musicxml_document(staff: &Staff) -> Result<String> {
+    validate_ids(staff)?;
+
+    let mut writer = Writer::default();
+
+    writer.declaration();
+    writer.open("score-partwise", &[("version", "4.0")]);
+
+    writer.open("part-list", &[]);
+    writer.open("score-part", &[("id", PART_ID)]);
+    writer.empty("part-name", &[]);
+    writer.close("score-part");
+    writer.close("part-list");
+
+    writer.open("part", &[("id", PART_ID)]);
+
+    let divisions = divisions(staff);
+    let mut context = Context::default();
+    // Whether the last syllable of each verse continues with a hyphen.
+    let mut hyphenated = vec![];
+
+    context.start_key_signature(staff.key_signature);
+
+    if staff.measures.is_empty() {
+        writer.open("measure", &[("number", "1")]);
+        write_attributes(&mut writer, staff, divisions);
+        writer.close("measure");
+    }
+
+    for (index, measure) in staff.measures.iter().enumerate() {
+        let number = (index + 1).to_string();
+        writer.open("measure", &[("number", &number)]);
+
+        let previous = index.checked_sub(1).map(|index| &staff.measures[index]);
+        let next = staff.measures.get(index + 1);
+
+        write_left_barline(&mut writer, measure, previous);
+
+        if index == 0 {
+            write_attributes(&mut writer, staff, divisions);
+        }
+
+        if measure.key_signature.is_some() || measure.time_signature.is_some() {
+            write_signature_changes(&mut writer, staff, measure, &mut context);
+        }
+
+        write_measure(
+            &mut writer,
+            measure,
+            staff.measure_divisions_at(index),
+            divisions,
+            &mut context,
+            &mut hyphenated,
+        );
+        write_right_barline(&mut writer, measure, next);
+
+        writer.close("measure");
+    }
+
+    writer.close("part");
+    writer.close("score-partwise");
+
+    Ok(writer.output)
+}
+
+/// Returns an error unless the ids of the notes, chords and rests of the staff
+/// are valid XML ids, which are unique in the document.
+fn validate_ids(staff: &Staff) -> Result<()> {
+    let mut ids = HashSet::from([PART_ID]);
+
+    for element in staff.measures.iter().flat_map(|measure| &measure.elements) {
+        let element_ids = match element {
+            measure::Element::Note(note) => vec![&note.id],
+            measure::Element::Chord(chord) => vec![&chord.id],
+            measure::Element::Rest(rest) => vec![&rest.id],
+            measure::Element::Grace(grace) => grace.notes.iter().map(|note| &note.id).collect(),
+            _ => vec![],
+        };
+
+        for id in element_ids.into_iter().flatten() {
+            if !is_valid_id(id) {
+                return Err(Error::InvalidMusicXmlId(id.clone()));
+            }
+
+            if !ids.insert(id) {
+                return Err(Error::DuplicateMusicXmlId(id.clone()));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns whether the id is a valid XML id, allowing any letters rather than
+/// only those of the XML name character classes.
+fn is_valid_id(id: &str) -> bool {
+    let mut chars = id.chars();
+
+    chars
+        .next()
+        .is_some_and(|first| first.is_alphabetic() || first == '_')
+        && chars.all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// Returns the number of divisions of a quarter note needed to represent the
+/// shortest duration in the staff.
+fn divisions(staff: &Staff) -> u32 {
+    let common_divisor = staff
+        .measures
+        .iter()
+        .enumerate()
+        .flat_map(|(index, measure)| {
+            measure
+                .elements
+                .iter()
+                .filter_map(move |element| match element {
+                    measure::Element::FullMeasureRest => Some(staff.measure_divisions_at(index)),
+                    _ => element.duration().map(|duration| duration.divisions()),
+                })
+        })
+        .fold(DIVISIONS_PER_QUARTER, gcd);
+
+    DIVISIONS_PER_QUARTER / common_divisor
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn write_attributes(writer: &mut Writer, staff: &Staff, divisions: u32) {
+    writer.open("attributes", &[]);
+    writer.leaf("divisions", &divisions.to_string());
+
+    if let Some(key_signature) = staff.key_signature {
+        write_key_signature(writer, Some(key_signature), None);
+    }
+
+    if let Some(time_signature) = &staff.time_signature {
+        write_time_signature(writer, time_signature);
+    }
+
+    write_clef(writer, staff.clef);
+    write_staff_details(writer, staff.geometry);
+
+    writer.close("attributes");
+}
+
+/// Writes the number of lines and size of a staff, unless it is a standard
+/// staff.
+fn write_staff_details(writer: &mut Writer, geometry: StaffGeometry) {
+    if geometry == StaffGeometry::default() {
+        return;
+    }
+
+    writer.open("staff-details", &[]);
+    writer.leaf("staff-lines", &geometry.lines.to_string());
+
+    if geometry.scale != 1.0 {
+        writer.leaf("staff-size", &(geometry.scale * 100.0).to_string());
+    }
+
+    writer.close("staff-details");
+}
+
+/// Writes the key and time signature changes at the start of a measure, and
+/// starts its key signature in the context.
+fn write_signature_changes(
+    writer: &mut Writer,
+    staff: &Staff,
+    measure: &Measure,
+    context: &mut Context,
+) {
+    writer.open("attributes", &[]);
+
+    if let Some(key_signature) = measure.key_signature {
+        let cancel = context.key_signature().filter(|old| {
+            !staff
+                .key_cancellation
+                .cancelled_steps(*old, key_signature)
+                .is_empty()
+        });
+
+        write_key_signature(writer, key_signature, cancel);
+        context.start_key_signature(key_signature);
+    }
+
+    if let Some(time_signature) = &measure.time_signature {
+        write_time_signature(writer, time_signature);
+    }
+
+    writer.close("attributes");
+}
+
+/// Writes a key signature, which has no sharps or flats if it is `None`, and
+/// which cancels the `cancel` key signature if there is one.
+fn write_key_signature(
+    writer: &mut Writer,
+    key_signature: Option<KeySignature>,
+    cancel: Option<KeySignature>,
+) {
+    writer.open("key", &[]);
+
+    if let Some(cancel) = cancel {
+        writer.leaf("cancel", &cancel.fifths().to_string());
+    }
+
+    let fifths = key_signature.map_or(0, |key_signature| key_signature.fifths());
+    writer.leaf("fifths", &fifths.to_string());
+    writer.close("key");
+}
+
+/// Writes a time signature, with any interchangeable meters after the first.
+fn write_time_signature(writer: &mut Writer, time_signature: &TimeSignature) {
+    let meters = match time_signature {
+        TimeSignature::Common => {
+            writer.open("time", &[("symbol", "common")]);
+            write_beats(writer, "4", 4);
+            writer.close("time");
+            return;
+        }
+        TimeSignature::Cut => {
+            writer.open("time", &[("symbol", "cut")]);
+            write_beats(writer, "2", 2);
+            writer.close("time");
+            return;
+        }
+        TimeSignature::Meters(meters) => meters,
+    };
+
+    let Some((first, others)) = meters.split_first() else {
+        return;
+    };
+
+    match first.unit {
+        None => writer.open("time", &[("symbol", "single-number")]),
+        Some(time_signature::Unit::Note(Duration { dots: None, .. })) => {
+            writer.open("time", &[("symbol", "note")])
+        }
+        Some(time_signature::Unit::Note(_)) => writer.open("time", &[("symbol", "dotted-note")]),
+        Some(time_signature::Unit::Number(_)) => writer.open("time", &[]),
+    }
+
+    write_meter(writer, first);
+
+    if !others.is_empty() {
+        writer.open("interchangeable", &[]);
+
+        for meter in others {
+            write_meter(writer, meter);
+        }
+
+        writer.close("interchangeable");
+    }
+
+    writer.close("time");
+}
+
+/// Writes the beats and beat type of a meter, where an unspecified number of
+/// beats is written as X, and the beats of a note denominator are counted in
+/// the fraction of a whole note which it lasts, such as 3/8 for a dotted
+/// quarter note.
+fn write_meter(writer: &mut Writer, meter: &time_signature::Meter) {
+    let (multiplier, beat_type) = match meter.unit {
+        Some(time_signature::Unit::Number(denominator)) => (1, u32::from(denominator)),
+        Some(time_signature::Unit::Note(duration)) => {
+            let whole = duration::Value::Whole.divisions();
+            let divisor = gcd(duration.divisions(), whole);
+
+            (duration.divisions() / divisor, whole / divisor)
+        }
+        None => (1, 4),
+    };
+
+    let beats = match &meter.beats {
+        time_signature::Beats::Numbers(numbers) => numbers
+            .iter()
+            .map(|number| (u32::from(*number) * multiplier).to_string())
+            .collect::<Vec<_>>()
+            .join("+"),
+        time_signature::Beats::X => "X".to_owned(),
+    };
+
+    write_beats(writer, &beats, beat_type);
+}
+
+fn write_beats(writer: &mut Writer, beats: &str, beat_type: u32) {
+    writer.leaf("beats", beats);
+    writer.leaf("beat-type", &beat_type.to_string());
+}
+
+fn write_clef(writer: &mut Writer, clef: Clef) {
+    let (sign, line) = match clef {
+        Clef::Treble | Clef::Treble8vb | Clef::Treble8va => ("G", 2),
+        Clef::FrenchViolin => ("G", 1),
+        Clef::Soprano => ("C", 1),
+        Clef::MezzoSoprano => ("C", 2),
+        Clef::Alto => ("C", 3),
+        Clef::Tenor => ("C", 4),
+        Clef::Baritone => ("C", 5),
+        Clef::Bass | Clef::Bass8vb => ("F", 4),
+        Clef::Percussion => ("percussion", 3),
+        Clef::Tab => ("TAB", 5),
+    };
+
+    writer.open("clef", &[]);
+    writer.leaf("sign", sign);
+    writer.leaf("line", &line.to_string());
+
+    if clef.octave_shift() != 0 {
+        writer.leaf("clef-octave-change", &clef.octave_shift().to_string());
+    }
+
+    writer.close("clef");
+}
+
+fn write_measure(
+    writer: &mut Writer,
+    measure: &Measure,
+    measure_divisions: u32,
+    divisions: u32,
+    context: &mut Context,
+    hyphenated: &mut Vec<bool>,
+) {
+    context.start_measure();
+
+    for navigation in measure
+        .navigation
+        .iter()
+        .filter(|navigation| navigation.is_at_start())
+    {
+        write_navigation(writer, *navigation);
+    }
+
+    let mut num_sung = 0;
+
+    for element in &measure.elements {
+        match element {
+            measure::Element::Note(note) => {
+                let lyrics = syllables(measure, num_sung, hyphenated);
+                write_note(writer, note, divisions, context, &lyrics);
+                num_sung += 1;
+            }
+            measure::Element::Chord(chord) => {
+                let lyrics = syllables(measure, num_sung, hyphenated);
+                write_chord(writer, chord, divisions, context, &lyrics);
+                num_sung += 1;
+            }
+            measure::Element::Rest(rest) => write_rest(writer, rest, divisions),
+            measure::Element::FullMeasureRest => {
+                write_full_measure_rest(writer, measure_divisions, divisions)
+            }
+            measure::Element::Dynamic(dynamic) => write_dynamic(writer, *dynamic),
+            measure::Element::Hairpin(hairpin) => write_hairpin(writer, *hairpin),
+            measure::Element::Clef(clef) => {
+                writer.open("attributes", &[]);
+                write_clef(writer, *clef);
+                writer.close("attributes");
+            }
+            measure::Element::Grace(grace) => write_grace(writer, grace, context),
+            measure::Element::ChordSymbol(chord_symbol) => write_harmony(writer, chord_symbol),
+            measure::Element::Annotation(annotation) => write_annotation(writer, annotation),
+            measure::Element::OctaveLine(octave_line) => {
+                write_octave_shift(writer, *octave_line, context)
+            }
+            measure::Element::Pedal(pedal) => write_pedal(writer, *pedal),
+        }
+    }
+
+    for navigation in measure
+        .navigation
+        .iter()
+        .filter(|navigation| !navigation.is_at_start())
+    {
+        write_navigation(writer, *navigation);
+    }
+}
+
+fn write_note(
+    writer: &mut Writer,
+    note: &Note,
+    divisions: u32,
+    context: &mut Context,
+    lyrics: &[SungSyllable],
+) {
+    writer.open("note", &id_attributes(note.id.as_deref()));
+    write_pitch(writer, note.pitch);
+    write_duration(writer, note.duration, divisions);
+    write_accidental(writer, note.pitch, context);
+    write_notations(writer, &note.articulations, note.ornament);
+    write_lyrics(writer, lyrics);
+    writer.close("note");
+}
+
+/// A syllable of a verse, with its MusicXML `<syllabic>` type.
+struct SungSyllable<'a> {
+    verse: usize,
+    syllable: &'a lyric::Syllable,
+    syllabic: &'static str,
+}
+
+/// Returns the syllables of each verse for the note or chord with the index
+/// `num_sung` among the notes and chords of the measure.
+///
+/// The `<syllabic>` of a syllable depends on whether it and the previous
+/// syllable of its verse are joined to the next one by a hyphen, which is
+/// tracked for each verse in `hyphenated`.
+fn syllables<'a>(
+    measure: &'a Measure,
+    num_sung: usize,
+    hyphenated: &mut Vec<bool>,
+) -> Vec<SungSyllable<'a>> {
+    let mut syllables = vec![];
+
+    for (verse, lyrics) in measure.lyrics.iter().enumerate() {
+        let Some(Lyric::Syllable(syllable)) = lyrics.get(num_sung) else {
+            continue;
+        };
+
+        if hyphenated.len() <= verse {
+            hyphenated.resize(verse + 1, false);
+        }
+
+        let continues = syllable.connector == Some(lyric::Connector::Hyphen);
+        let syllabic = match (hyphenated[verse], continues) {
+            (false, false) => "single",
+            (false, true) => "begin",
+            (true, true) => "middle",
+            (true, false) => "end",
+        };
+        hyphenated[verse] = continues;
+
+        syllables.push(SungSyllable {
+            verse,
+            syllable,
+            syllabic,
+        });
+    }
+
+    syllables
+}
+
+fn write_lyrics(writer: &mut Writer, lyrics: &[SungSyllable]) {
+    for SungSyllable {
+        verse,
+        syllable,
+        syllabic,
+    } in lyrics
+    {
+        writer.open("lyric", &[("number", &(verse + 1).to_string())]);
+        writer.leaf("syllabic", syllabic);
+        writer.leaf("text", &syllable.text);
+        if syllable.connector == Some(lyric::Connector::Extender) {
+            writer.empty("extend", &[]);
+        }
+        writer.close("lyric");
+    }
+}
+
+/// Writes grace notes, which have a type but no duration, and whose stems are
+/// slashed for an acciaccatura.
+fn write_grace(writer: &mut Writer, grace: &Grace, context: &mut Context) {
+    let slash = match grace.kind {
+        grace::Kind::Acciaccatura => "yes",
+        grace::Kind::Appoggiatura => "no",
+    };
+
+    for note in &grace.notes {
+        writer.open("note", &id_attributes(note.id.as_deref()));
+        writer.empty("grace", &[("slash", slash)]);
+        write_pitch(writer, note.pitch);
+        write_type(writer, note.duration);
+        write_accidental(writer, note.pitch, context);
+        write_notations(writer, &note.articulations, note.ornament);
+        writer.close("note");
+    }
+}
+
+/// Writes a chord as a note for each pitch, where the notes after the first
+/// have a `<chord/>` element. The id, articulations, ornament and lyrics of the
+/// chord are written on the first note.
+fn write_chord(
+    writer: &mut Writer,
+    chord: &Chord,
+    divisions: u32,
+    context: &mut Context,
+    lyrics: &[SungSyllable],
+) {
+    for (index, pitch) in chord.pitches.iter().enumerate() {
+        if index == 0 {
+            writer.open("note", &id_attributes(chord.id.as_deref()));
+        } else {
+            writer.open("note", &[]);
+            writer.empty("chord", &[]);
+        }
+
+        write_pitch(writer, *pitch);
+        write_duration(writer, chord.duration, divisions);
+        write_accidental(writer, *pitch, context);
+
+        if index == 0 {
+            write_notations(writer, &chord.articulations, chord.ornament);
+            write_lyrics(writer, lyrics);
+        }
+
+        writer.close("note");
+    }
+}
+
+fn write_pitch(writer: &mut Writer, pitch: Pitch) {
+    writer.open("pitch", &[]);
+    writer.leaf("step", step_name(pitch.step));
+    if pitch.alteration != Alteration::Natural {
+        writer.leaf("alter", &pitch.alteration.semitones().to_string());
+    }
+    writer.leaf("octave", &pitch.octave.to_string());
+    writer.close("pitch");
+}
+
+fn write_accidental(writer: &mut Writer, pitch: Pitch, context: &mut Context) {
+    if let AccidentalState::NeedsAccidental = context.add_pitch(pitch) {
+        writer.leaf("accidental", accidental_name(pitch.alteration));
+    }
+}
+
+fn write_notations(
+    writer: &mut Writer,
+    articulations: &[Articulation],
+    ornament: Option<Ornament>,
+) {
+    if articulations.is_empty() && ornament.is_none() {
+        return;
+    }
+
+    writer.open("notations", &[]);
+
+    if let Some(ornament) = ornament {
+        write_ornament(writer, ornament);
+    }
+
+    if !articulations.is_empty() {
+        writer.open("articulations", &[]);
+
+        for articulation in articulations {
+            writer.empty(articulation_name(*articulation), &[]);
+        }
+
+        writer.close("articulations");
+    }
+
+    writer.close("notations");
+}
+
+/// Writes an ornament followed by its accidentals, where the wavy line of an
+/// extended trill starts and stops on the same note.
+fn write_ornament(writer: &mut Writer, ornament: Ornament) {
+    writer.open("ornaments", &[]);
+
+    match ornament.kind {
+        ornament::Kind::Trill { extended } => {
+            writer.empty("trill-mark", &[]);
+
+            if extended {
+                writer.empty("wavy-line", &[("type", "start")]);
+                writer.empty("wavy-line", &[("type", "stop")]);
+            }
+        }
+        ornament::Kind::Mordent => writer.empty("mordent", &[]),
+        ornament::Kind::InvertedMordent => writer.empty("inverted-mordent", &[]),
+        ornament::Kind::Turn => writer.empty("turn", &[]),
+        ornament::Kind::InvertedTurn => writer.empty("inverted-turn", &[]),
+    }
+
+    for (alteration, placement) in [
+        (ornament.accidental_above, "above"),
+        (ornament.accidental_below, "below"),
+    ] {
+        if let Some(alteration) = alteration {
+            writer.leaf_with_attributes(
+                "accidental-mark",
+                &[("placement", placement)],
+                accidental_name(alteration),
+            );
+        }
+    }
+
+    writer.close("ornaments");
+}
+
+fn write_rest(writer: &mut Writer, rest: &Rest, divisions: u32) {
+    writer.open("note", &id_attributes(rest.id.as_deref()));
+    writer.empty("rest", &[]);
+    write_duration(writer, rest.duration, divisions);
+    writer.close("note");
+}
+
+/// Writes a full-measure rest, whose length is that of the measure.
+fn write_full_measure_rest(writer: &mut Writer, measure_divisions: u32, divisions: u32) {
+    writer.open("note", &[]);
+    writer.empty("rest", &[("measure", "yes")]);
+    let length = measure_divisions * divisions / DIVISIONS_PER_QUARTER;
+    writer.leaf("duration", &length.to_string());
+    writer.close("note");
+}
+
+fn write_dynamic(writer: &mut Writer, dynamic: Dynamic) {
+    writer.open("direction", &[("placement", "below")]);
+    writer.open("direction-type", &[]);
+    writer.open("dynamics", &[]);
+    writer.empty(dynamic_name(dynamic), &[]);
+    writer.close("dynamics");
+    writer.close("direction-type");
+    writer.close("direction");
+}
+
+/// Writes a chord symbol as a `<harmony>`, whose kind comes from its quality
+/// and first extension. Its other extensions are written as added degrees.
+fn write_harmony(writer: &mut Writer, chord_symbol: &ChordSymbol) {
+    use chord_symbol::{Change, Quality};
+
+    let mut extensions = chord_symbol.extensions.iter();
+    let first = extensions.next();
+
+    let kind = match (chord_symbol.quality, first) {
+        (Quality::Major, Some(extension)) => match (extension.degree, extension.is_major) {
+            (6, _) => Some("major-sixth"),
+            (7, true) => Some("major-seventh"),
+            (7, false) => Some("dominant"),
+            (9, true) => Some("major-ninth"),
+            (9, false) => Some("dominant-ninth"),
+            (11, true) => Some("major-11th"),
+            (11, false) => Some("dominant-11th"),
+            (13, true) => Some("major-13th"),
+            (13, false) => Some("dominant-13th"),
+            _ => None,
+        },
+        (Quality::Minor, Some(extension)) => match (extension.degree, extension.is_major) {
+            (6, _) => Some("minor-sixth"),
+            (7, true) => Some("major-minor"),
+            (7, false) => Some("minor-seventh"),
+            (9, false) => Some("minor-ninth"),
+            (11, false) => Some("minor-11th"),
+            (13, false) => Some("minor-13th"),
+            _ => None,
+        },
+        (Quality::Augmented, Some(extension)) if extension.degree == 7 => Some("augmented-seventh"),
+        (Quality::Diminished, Some(extension)) if extension.degree == 7 => {
+            Some("diminished-seventh")
+        }
+        (Quality::HalfDiminished, Some(extension)) if extension.degree == 7 => {
+            Some("half-diminished")
+        }
+        _ => None,
+    };
+
+    // An extension which is not part of the kind is written as an added degree.
+    let (kind, added) = match kind {
+        Some(kind) => (kind, None),
+        None => {
+            let kind = match chord_symbol.quality {
+                Quality::Major => "major",
+                Quality::Minor => "minor",
+                Quality::Augmented => "augmented",
+                Quality::Diminished => "diminished",
+                Quality::HalfDiminished => "half-diminished",
+                Quality::SuspendedSecond => "suspended-second",
+                Quality::SuspendedFourth => "suspended-fourth",
+                Quality::Power => "power",
+            };
+
+            (kind, first)
+        }
+    };
+
+    writer.open("harmony", &[]);
+
+    writer.open("root", &[]);
+    writer.leaf("root-step", step_name(chord_symbol.root.step));
+    write_alter(writer, "root-alter", chord_symbol.root.alteration);
+    writer.close("root");
+
+    writer.leaf("kind", kind);
+
+    if let Some(bass) = chord_symbol.bass {
+        writer.open("bass", &[]);
+        writer.leaf("bass-step", step_name(bass.step));
+        write_alter(writer, "bass-alter", bass.alteration);
+        writer.close("bass");
+    }
+
+    for extension in added.into_iter().chain(extensions) {
+        write_degree(writer, extension.degree, 0, "add");
+    }
+
+    for alteration in &chord_symbol.alterations {
+        let (alter, degree_type) = match alteration.change {
+            Change::Flat => (-1, "alter"),
+            Change::Sharp => (1, "alter"),
+            Change::Add => (0, "add"),
+            Change::Omit => (0, "subtract"),
+        };
+
+        write_degree(writer, alteration.degree, alter, degree_type);
+    }
+
+    writer.close("harmony");
+}
+
+fn write_alter(writer: &mut Writer, name: &str, alteration: Alteration) {
+    if alteration != Alteration::Natural {
+        writer.leaf(name, &alteration.semitones().to_string());
+    }
+}
+
+fn write_degree(writer: &mut Writer, value: u8, alter: i8, degree_type: &str) {
+    writer.open("degree", &[]);
+    writer.leaf("degree-value", &value.to_string());
+    writer.leaf("degree-alter", &alter.to_string());
+    writer.leaf("degree-type", degree_type);
+    writer.close("degree");
+}
+
+fn write_hairpin(writer: &mut Writer, hairpin: Hairpin) {
+    let wedge_type = match hairpin {
+        Hairpin::Start(hairpin::Kind::Crescendo) => "crescendo",
+        Hairpin::Start(hairpin::Kind::Diminuendo) => "diminuendo",
+        Hairpin::End => "stop",
+    };
+
+    writer.open("direction", &[("placement", "below")]);
+    writer.open("direction-type", &[]);
+    writer.empty("wedge", &[("type", wedge_type)]);
+    writer.close("direction-type");
+    writer.close("direction");
+}
+
+/// Writes the start or end of an octave line as an octave shift, whose pitches
+/// are the sounding pitches, so an 8va line shifts the notes down.
+fn write_octave_shift(writer: &mut Writer, octave_line: OctaveLine, context: &mut Context) {
+    let (shift_type, octaves) = match octave_line {
+        OctaveLine::Start(kind) => {
+            context.octave_shift = kind.octaves();
+            let shift_type = if kind.is_above() { "down" } else { "up" };
+
+            (shift_type, kind.octaves())
+        }
+        OctaveLine::End => ("stop", mem::take(&mut context.octave_shift)),
+    };
+    let size = (7 * octaves.unsigned_abs() + 1).to_string();
+    let placement = if octaves < 0 { "below" } else { "above" };
+
+    writer.open("direction", &[("placement", placement)]);
+    writer.open("direction-type", &[]);
+    writer.empty("octave-shift", &[("type", shift_type), ("size", &size)]);
+    writer.close("direction-type");
+    writer.close("direction");
+}
+
+/// Writes a pedal mark as a direction below the staff, with a `<sound>` element
+/// which presses or lifts the pedal, halfway for a half pedal.
+fn write_pedal(writer: &mut Writer, pedal: Pedal) {
+    let pressed = |depth| match depth {
+        pedal::Depth::Full => "yes",
+        pedal::Depth::Half => "50",
+    };
+
+    writer.open("direction", &[("placement", "below")]);
+    writer.open("direction-type", &[]);
+
+    let sound = match pedal {
+        Pedal::Down(depth) => {
+            writer.empty("pedal", &[("type", "start")]);
+            ("damper-pedal", pressed(depth))
+        }
+        Pedal::Change(depth) => {
+            writer.empty("pedal", &[("type", "change")]);
+            ("damper-pedal", pressed(depth))
+        }
+        Pedal::Up => {
+            writer.empty("pedal", &[("type", "stop")]);
+            ("damper-pedal", "no")
+        }
+        Pedal::UnaCorda => {
+            writer.leaf_with_attributes("words", &[("font-style", "italic")], "una corda");
+            ("soft-pedal", "yes")
+        }
+        Pedal::TreCorde => {
+            writer.leaf_with_attributes("words", &[("font-style", "italic")], "tre corde");
+            ("soft-pedal", "no")
+        }
+    };
+
+    writer.close("direction-type");
+    writer.empty("sound", &[sound]);
+    writer.close("direction");
+}
+
+/// Writes an annotation as a direction above the staff, with a `<sound>`
+/// element which gives the tempo of a metronome mark in quarter notes per
+/// minute.
+fn write_annotation(writer: &mut Writer, annotation: &Annotation) {
+    writer.open("direction", &[("placement", "above")]);
+
+    match annotation {
+        Annotation::Tempo(tempo) => {
+            if let Some(text) = &tempo.text {
+                writer.open("direction-type", &[]);
+                writer.leaf_with_attributes("words", &[("font-weight", "bold")], text);
+                writer.close("direction-type");
+            }
+
+            if let Some(metronome_mark) = &tempo.metronome_mark {
+                writer.open("direction-type", &[]);
+                write_metronome(writer, metronome_mark);
+                writer.close("direction-type");
+
+                let tempo = metronome_mark.quarters_per_minute().to_string();
+                writer.empty("sound", &[("tempo", &tempo)]);
+            }
+        }
+        Annotation::Expression(text) => {
+            writer.open("direction-type", &[]);
+            writer.leaf_with_attributes("words", &[("font-style", "italic")], text);
+            writer.close("direction-type");
+        }
+        Annotation::RehearsalMark(text) => {
+            writer.open("direction-type", &[]);
+            writer.leaf_with_attributes("rehearsal", &[("enclosure", "square")], text);
+            writer.close("direction-type");
+        }
+    }
+
+    writer.close("direction");
+}
+
+fn write_metronome(writer: &mut Writer, metronome_mark: &annotation::MetronomeMark) {
+    writer.open("metronome", &[]);
+    writer.leaf("beat-unit", type_name(metronome_mark.beat.value));
+
+    let num_dots = match metronome_mark.beat.dots {
+        None => 0,
+        Some(duration::Dots::Dot) => 1,
+        Some(duration::Dots::DoubleDot) => 2,
+    };
+
+    for _ in 0..num_dots {
+        writer.empty("beat-unit-dot", &[]);
+    }
+
+    writer.leaf("per-minute", &metronome_mark.per_minute.to_string());
+    writer.close("metronome");
+}
+
+/// Writes a navigation marker as a direction, with a `<sound>` element which
+/// gives its meaning for playback.
+fn write_navigation(writer: &mut Writer, navigation: Navigation) {
+    let sound = match navigation {
+        Navigation::Segno => ("segno", "segno"),
+        Navigation::Coda => ("coda", "coda"),
+        Navigation::ToCoda => ("tocoda", "coda"),
+        Navigation::Fine => ("fine", "yes"),
+        Navigation::DaCapo(_) => ("dacapo", "yes"),
+        Navigation::DalSegno(_) => ("dalsegno", "segno"),
+    };
+
+    writer.open("direction", &[("placement", "above")]);
+    writer.open("direction-type", &[]);
+
+    match navigation {
+        Navigation::Segno => writer.empty("segno", &[]),
+        Navigation::Coda => writer.empty("coda", &[]),
+        _ => {}
+    }
+
+    if let Some(text) = navigation.text() {
+        writer.leaf("words", text);
+    }
+
+    writer.close("direction-type");
+    writer.empty("sound", &[sound]);
+    writer.close("direction");
+}
+
+/// Writes the `<duration>`, `<type>` and `<dot>` elements of a note.
+///
+/// These are written together since `<type>` and `<dot>` must follow
+/// `<duration>` with only optional elements between them.
+fn write_duration(writer: &mut Writer, duration: Duration, divisions: u32) {
+    let length = duration.divisions() * divisions / DIVISIONS_PER_QUARTER;
+    writer.leaf("duration", &length.to_string());
+    write_type(writer, duration);
+}
+
+fn write_type(writer: &mut Writer, duration: Duration) {
+    writer.leaf("type", type_name(duration.value));
+
+    let num_dots = match duration.dots {
+        None => 0,
+        Some(duration::Dots::Dot) => 1,
+        Some(duration::Dots::DoubleDot) => 2,
+    };
+
+    for _ in 0..num_dots {
+        writer.empty("dot", &[]);
+    }
+}
+
+/// Writes the barline at the start of a measure, which has a forward repeat
+/// for a start repeat, including one after an end-start repeat, and starts an
+/// ending which the previous measure does not have.
+fn write_left_barline(writer: &mut Writer, measure: &Measure, previous: Option<&Measure>) {
+    let is_start_repeat = measure.start_barline == Some(Barline::StartRepeat)
+        || previous.is_some_and(|previous| previous.barline == Barline::EndStartRepeat);
+    let starts_ending = measure.ending.is_some()
+        && previous.is_none_or(|previous| previous.ending != measure.ending);
+    let bar_style = match &measure.start_barline {
+        Some(barline) if !is_start_repeat => Some(bar_style(barline)),
+        _ => None,
+    };
+
+    if !is_start_repeat && !starts_ending && bar_style.is_none() {
+        return;
+    }
+
+    writer.open("barline", &[("location", "left")]);
+
+    if is_start_repeat {
+        writer.leaf("bar-style", "heavy-light");
+    } else if let Some(bar_style) = bar_style {
+        writer.leaf("bar-style", bar_style);
+    }
+
+    if let (true, Some(ending)) = (starts_ending, &measure.ending) {
+        write_ending(writer, ending, "start");
+    }
+
+    if is_start_repeat {
+        writer.empty("repeat", &[("direction", "forward")]);
+    }
+
+    writer.close("barline");
+}
+
+/// Writes the barline at the end of a measure, which stops an ending which
+/// the next measure does not have.
+fn write_right_barline(writer: &mut Writer, measure: &Measure, next: Option<&Measure>) {
+    let stops_ending =
+        measure.ending.is_some() && next.is_none_or(|next| next.ending != measure.ending);
+
+    if measure.barline == Barline::Thin && !stops_ending {
+        return;
+    }
+
+    writer.open("barline", &[("location", "right")]);
+    writer.leaf("bar-style", bar_style(&measure.barline));
+
+    if let (true, Some(ending)) = (stops_ending, &measure.ending) {
+        let ending_type = if measure.barline.is_end_repeat() {
+            "stop"
+        } else {
+            "discontinue"
+        };
+        write_ending(writer, ending, ending_type);
+    }
+
+    if measure.barline.is_end_repeat() {
+        writer.empty("repeat", &[("direction", "backward")]);
+    }
+
+    writer.close("barline");
+}
+
+fn write_ending(writer: &mut Writer, ending: &Ending, ending_type: &str) {
+    let numbers: Vec<_> = ending.numbers.iter().map(u8::to_string).collect();
+
+    writer.leaf_with_attributes(
+        "ending",
+        &[("number", &numbers.join(", ")), ("type", ending_type)],
+        &ending.label(),
+    );
+}
+
+fn id_attributes(id: Option<&str>) -> Vec<(&'static str, &str)> {
+    id.map(|id| vec![("id", id)]).unwrap_or_default()
+}
+
+fn bar_style(barline: &Barline) -> &'static str {
+    match barline {
+        Barline::Thin => "regular",
+        Barline::Final => "light-heavy",
+        Barline::Double => "light-light",
+        Barline::Heavy => "heavy",
+        Barline::Dashed => "dashed",
+        Barline::Dotted => "dotted",
+        Barline::StartRepeat => "heavy-light",
+        Barline::EndRepeat | Barline::EndStartRepeat => "light-heavy",
+        Barline::Invisible => "none",
+    }
+}
+
+fn step_name(step: Step) -> &'static str {
+    match step {
+        Step::C => "C",
+        Step::D => "D",
+        Step::E => "E",
+        Step::F => "F",
+        Step::G => "G",
+        Step::A => "A",
+        Step::B => "B",
+    }
+}
+
+fn accidental_name(alteration: Alteration) -> &'static str {
+    match alteration {
+        Alteration::DoubleFlat => "flat-flat",
+        Alteration::Flat => "flat",
+        Alteration::Natural => "natural",
+        Alteration::Sharp => "sharp",
+        Alteration::DoubleSharp => "double-sharp",
+    }
+}
+
+fn articulation_name(articulation: Articulation) -> &'static str {
+    match articulation {
+        Articulation::Staccato => "staccato",
+        Articulation::Staccatissimo => "staccatissimo",
+        Articulation::Tenuto => "tenuto",
+        Articulation::Stress => "stress",
+        Articulation::Accent => "accent",
+        Articulation::Marcato => "strong-accent",
+    }
+}
+
+fn dynamic_name(dynamic: Dynamic) -> &'static str {
+    match dynamic {
+        Dynamic::Pppppp => "pppppp",
+        Dynamic::Ppppp => "ppppp",
+        Dynamic::Pppp => "pppp",
+        Dynamic::Ppp => "ppp",
+        Dynamic::Pp => "pp",
+        Dynamic::P => "p",
+        Dynamic::Mp => "mp",
+        Dynamic::Mf => "mf",
+        Dynamic::F => "f",
+        Dynamic::Ff => "ff",
+        Dynamic::Fff => "fff",
+        Dynamic::Ffff => "ffff",
+        Dynamic::Fffff => "fffff",
+        Dynamic::Ffffff => "ffffff",
+        Dynamic::Fp => "fp",
+        Dynamic::Pf => "pf",
+        Dynamic::Sf => "sf",
+        Dynamic::Sfp => "sfp",
+        Dynamic::Sfpp => "sfpp",
+        Dynamic::Sfz => "sfz",
+        Dynamic::Sfzp => "sfzp",
+        Dynamic::Sffz => "sffz",
+        Dynamic::Fz => "fz",
+        Dynamic::Rf => "rf",
+        Dynamic::Rfz => "rfz",
+        Dynamic::N => "n",
+    }
+}
+
+fn type_name(value: duration::Value) -> &'static str {
+    match value {
+        duration::Value::Whole => "whole",
+        duration::Value::Half => "half",
+        duration::Value::Quarter => "quarter",
+        duration::Value::Eighth => "eighth",
+        duration::Value::Sixteenth => "16th",
+        duration::Value::ThirtySecond => "32nd",
+        duration::Value::SixtyFourth => "64th",
+        duration::Value::OneHundredTwentyEighth => "128th",
+        duration::Value::TwoHundredFiftySixth => "256th",
+    }
+}
+
+/// Writes indented XML to a string.
+#[derive(Debug, Default)]
+struct Writer {
+    output: String,
+    depth: usize,
+}
+
+impl Writer {
+    fn declaration(&mut self) {
+        self.output
+            .push_str(r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>"#);
+        self.output.push('\n');
+        self.output.push_str(
+            r#"<!DOCTYPE score-partwise PUBLIC "-//Recordare//DTD MusicXML 4.0 Partwise//EN" "http://www.musicxml.org/dtds/partwise.dtd">"#,
+        );
+        self.output.push('\n');
+    }
+
+    fn open(&mut self, name: &str, attributes: &[(&str, &str)]) {
+        self.start_tag(name, attributes);
+        self.output.push_str(">\n");
+        self.depth += 1;
+    }
+
+    fn close(&mut self, name: &str) {
+        self.depth -= 1;
+        self.indent();
+        writeln!(self.output, "</{name}>").unwrap();
+    }
+
+    fn empty(&mut self, name: &str, attributes: &[(&str, &str)]) {
+        self.start_tag(name, attributes);
+        self.output.push_str("/>\n");
+    }
+
+    fn leaf(&mut self, name: &str, text: &str) {
+        self.leaf_with_attributes(name, &[], text);
+    }
+
+    fn leaf_with_attributes(&mut self, name: &str, attributes: &[(&str, &str)], text: &str) {
+        self.start_tag(name, attributes);
+        writeln!(self.output, ">{}</{name}>", escape(text)).unwrap();
+    }
+
+    fn start_tag(&mut self, name: &str, attributes: &[(&str, &str)]) {
+        self.indent();
+        write!(self.output, "<{name}").unwrap();
+
+        for (key, value) in attributes {
+            write!(self.output, r#" {key}="{}""#, escape(value)).unwrap();
+        }
+    }
+
+    fn indent(&mut self) {
+        for _ in 0..self.depth {
+            self.output.push_str("  ");
+        }
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.chars()
+        .fold(String::with_capacity(text.len()), |mut escaped, c| {
+            match c {
+                '&' => escaped.push_str("&amp;"),
+                '<' => escaped.push_str("&lt;"),
+                '>' => escaped.push_str("&gt;"),
+                '"' => escaped.push_str("&quot;"),
+                '\'' => escaped.push_str("&apos;"),
+                _ => escaped.push(c),
+            }
+            escaped
+        })
+}
@@ -0,0 +1,99 @@
+use smufl::{Metadata, StaffSpaces};
+
+use crate::{
+    render::{
+        input::{Annotation, StaffGeometry},
+        ir::Element,
+        math, TextMetrics,
+    },
+    Result,
+};
+
+/// The height of the baseline of the lowest row of annotations above the top
+/// line when nothing extends far above the staff.
+const DEFAULT_HEIGHT: StaffSpaces = StaffSpaces(2.0);
+
+/// The minimum distance from the highest element of the staff to the baseline
+/// of the lowest row of annotations.
+const CLEARANCE: StaffSpaces = StaffSpaces(1.5);
+
+/// The distance between the baselines of consecutive rows of annotations.
+const ROW_SPACING: StaffSpaces = StaffSpaces(2.0);
+
+/// The minimum space between annotations in the same row.
+const SPACING: StaffSpaces = StaffSpaces(1.0);
+
+/// The tempo markings, expressions and rehearsal marks above a staff, which
+/// are collected as the staff is rendered and then placed above everything
+/// else.
+///
+/// An annotation which would run into an earlier one is placed in a row above
+/// it, rather than moving the notes apart.
+#[derive(Debug, Default)]
+pub struct Annotations {
+    annotations: Vec<PlacedAnnotation>,
+    /// The right edge of the last annotation in each row.
+    rows: Vec<StaffSpaces>,
+}
+
+#[derive(Debug)]
+struct PlacedAnnotation {
+    annotation: Annotation,
+    x: StaffSpaces,
+    row: usize,
+}
+
+impl Annotations {
+    pub fn is_empty(&self) -> bool {
+        self.annotations.is_empty()
+    }
+
+    /// Adds an annotation which starts at `x`, in the lowest row where it does
+    /// not run into the previous annotation.
+    pub fn add(&mut self, annotation: Annotation, x: StaffSpaces, width: StaffSpaces) {
+        let row = self
+            .rows
+            .iter()
+            .position(|right| *right + SPACING <= x)
+            .unwrap_or(self.rows.len());
+
+        match self.rows.get_mut(row) {
+            Some(right) => *right = x + width,
+            None => self.rows.push(x + width),
+        }
+
+        self.annotations
+            .push(PlacedAnnotation { annotation, x, row });
+    }
+
+    /// Returns the baseline of the lowest row of annotations for a staff whose
+    /// highest element is at `highest_y`.
+    pub fn baseline(highest_y: Option<StaffSpaces>, staff_geometry: StaffGeometry) -> StaffSpaces {
+        let default_y = staff_geometry.top() + DEFAULT_HEIGHT;
+
+        highest_y.map_or(default_y, |highest_y| {
+            math::max(default_y, highest_y + CLEARANCE)
+        })
+    }
+
+    /// Renders the annotations in rows above `baseline`.
+    pub fn render(
+        &self,
+        baseline: StaffSpaces,
+        text_metrics: &TextMetrics,
+        metadata: &Metadata,
+    ) -> Result<Vec<Element<StaffSpaces>>> {
+        let mut elements = vec![];
+
+        for placed in &self.annotations {
+            let y = baseline + ROW_SPACING * placed.row as f64;
+            elements.extend(
+                placed
+                    .annotation
+                    .render_at(placed.x, y, text_metrics, metadata)?,
+            );
+        }
+
+        Ok(elements)
+    }
+}
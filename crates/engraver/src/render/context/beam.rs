@@ -47,6 +47,12 @@ impl Render for Beam {
     ) -> Result<Output> {
         let beam_thickness = metadata.engraving_defaults.beam_thickness();
 
+        // The beam is drawn from the ends of the stems towards the noteheads.
+        let towards_noteheads = match self.stem_direction {
+            stem::Direction::Up => beam_thickness * -1.0,
+            stem::Direction::Down => beam_thickness,
+        };
+
         let mut stems: Vec<_> = self
             .noteheads
             .iter()
@@ -87,11 +93,11 @@ impl Render for Beam {
             Coord { x: end_x, y: end_y },
             Coord {
                 x: end_x,
-                y: end_y - beam_thickness,
+                y: end_y + towards_noteheads,
             },
             Coord {
                 x: start_x,
-                y: start_y - beam_thickness,
+                y: start_y + towards_noteheads,
             },
         ]);
         let element = Element::Polygon(polygon);
@@ -0,0 +1,109 @@
+use smufl::{Metadata, StaffSpaces};
+
+use crate::{
+    render::{
+        input::{chord_symbol::Part, StaffGeometry},
+        ir::{Coord, Element, FontStyle, FontWeight, Symbol, Text, TextAnchor},
+        math, TextMetrics,
+    },
+    Result,
+};
+
+/// The height of the baseline of chord symbols above the top line when nothing
+/// extends far above the staff.
+const DEFAULT_HEIGHT: StaffSpaces = StaffSpaces(2.0);
+
+/// The minimum distance from the highest element of the staff to the baseline
+/// of chord symbols.
+const CLEARANCE: StaffSpaces = StaffSpaces(1.0);
+
+/// The minimum space between consecutive chord symbols.
+const SPACING: StaffSpaces = StaffSpaces(1.0);
+
+/// The chord symbols above a staff, which are collected as the staff is
+/// rendered and then placed on a common baseline above everything else.
+#[derive(Debug, Default)]
+pub struct ChordSymbols {
+    chord_symbols: Vec<ChordSymbol>,
+}
+
+#[derive(Debug)]
+struct ChordSymbol {
+    parts: Vec<Part>,
+    x: StaffSpaces,
+    width: StaffSpaces,
+}
+
+impl ChordSymbols {
+    pub fn is_empty(&self) -> bool {
+        self.chord_symbols.is_empty()
+    }
+
+    /// Returns how far a chord symbol at `x` must move to the right so that it
+    /// does not run into the previous chord symbol.
+    pub fn overlap(&self, x: StaffSpaces) -> StaffSpaces {
+        self.chord_symbols
+            .last()
+            .map_or(StaffSpaces::zero(), |previous| {
+                math::max(
+                    StaffSpaces::zero(),
+                    previous.x + previous.width + SPACING - x,
+                )
+            })
+    }
+
+    /// Adds a chord symbol which starts at `x`, at the left of the notehead it
+    /// applies to.
+    pub fn add(&mut self, parts: Vec<Part>, x: StaffSpaces, width: StaffSpaces) {
+        self.chord_symbols.push(ChordSymbol { parts, x, width });
+    }
+
+    /// Returns the baseline of the chord symbols for a staff whose highest
+    /// element is at `highest_y`.
+    pub fn baseline(highest_y: Option<StaffSpaces>, staff_geometry: StaffGeometry) -> StaffSpaces {
+        let default_y = staff_geometry.top() + DEFAULT_HEIGHT;
+
+        highest_y.map_or(default_y, |highest_y| {
+            math::max(default_y, highest_y + CLEARANCE)
+        })
+    }
+
+    /// Renders the chord symbols on `baseline`, with their text and glyphs
+    /// side by side.
+    pub fn render(
+        &self,
+        baseline: StaffSpaces,
+        text_metrics: &TextMetrics,
+        metadata: &Metadata,
+    ) -> Result<Vec<Element<StaffSpaces>>> {
+        let mut elements = vec![];
+
+        for chord_symbol in &self.chord_symbols {
+            let mut x = chord_symbol.x;
+
+            for part in &chord_symbol.parts {
+                let origin = Coord { x, y: baseline };
+
+                elements.push(match part {
+                    Part::Text(text) => Element::Text(Text {
+                        origin,
+                        value: text.clone(),
+                        anchor: TextAnchor::Start,
+                        style: FontStyle::Normal,
+                        weight: FontWeight::Normal,
+                        size: 1.0,
+                    }),
+                    Part::Glyph(glyph) => Element::Symbol(Symbol {
+                        origin,
+                        value: *glyph,
+                        size: 1.0,
+                    }),
+                });
+
+                x += part.width(text_metrics, metadata)?;
+            }
+        }
+
+        Ok(elements)
+    }
+}
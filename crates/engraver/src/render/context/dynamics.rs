@@ -0,0 +1,193 @@
+use smufl::{Glyph, Metadata, StaffSpaces};
+
+use crate::{
+    render::{
+        engraving_defaults_extensions::EngravingDefaultsExtensions,
+        glyph_data_extensions::GlyphDataExtensions,
+        input::hairpin,
+        ir::{Coord, Element, Line, Linecap, Symbol},
+        math,
+        metadata_extensions::MetadataExtensions,
+    },
+    Error, Result,
+};
+
+/// The baseline of dynamics when nothing extends far below the staff.
+const DEFAULT_BASELINE: StaffSpaces = StaffSpaces(-2.0);
+
+/// The minimum distance from the lowest element of the staff to the baseline
+/// of dynamics.
+const CLEARANCE: StaffSpaces = StaffSpaces(2.0);
+
+/// The height above the baseline of the closed end of a hairpin.
+const HAIRPIN_MIDDLE: StaffSpaces = StaffSpaces(0.5);
+
+/// The distance between the lines at the open end of a hairpin.
+const HAIRPIN_OPENING: StaffSpaces = StaffSpaces(1.0);
+
+/// The space between a hairpin and the dynamics before and after it.
+const HAIRPIN_SPACING: StaffSpaces = StaffSpaces(0.4);
+
+const MIN_HAIRPIN_LENGTH: StaffSpaces = StaffSpaces(1.0);
+
+/// The dynamics and hairpins below a staff, which are collected as the staff is
+/// rendered and then placed on a common baseline below everything else.
+#[derive(Debug, Default)]
+pub struct Dynamics {
+    dynamics: Vec<Dynamic>,
+    hairpins: Vec<Hairpin>,
+}
+
+#[derive(Debug)]
+struct Dynamic {
+    glyph: Glyph,
+    x: StaffSpaces,
+}
+
+#[derive(Debug)]
+struct Hairpin {
+    kind: hairpin::Kind,
+    start: StaffSpaces,
+    end: Option<StaffSpaces>,
+}
+
+impl Dynamics {
+    /// Adds a dynamic for the note at `x`, which ends any hairpin in progress.
+    pub fn add_dynamic(&mut self, glyph: Glyph, x: StaffSpaces) {
+        self.end_hairpin_in_progress(x);
+        self.dynamics.push(Dynamic { glyph, x });
+    }
+
+    /// Starts a hairpin at `x`, which ends any hairpin in progress.
+    pub fn start_hairpin(&mut self, kind: hairpin::Kind, x: StaffSpaces) {
+        self.end_hairpin_in_progress(x);
+        self.hairpins.push(Hairpin {
+            kind,
+            start: x,
+            end: None,
+        });
+    }
+
+    pub fn end_hairpin(&mut self, x: StaffSpaces) -> Result<()> {
+        if self.end_hairpin_in_progress(x) {
+            Ok(())
+        } else {
+            Err(Error::EndedHairpinWhileNoHairpinInProgress)
+        }
+    }
+
+    /// Returns the kind of the hairpin in progress, if any.
+    pub fn hairpin_in_progress(&self) -> Option<hairpin::Kind> {
+        self.hairpins
+            .last()
+            .filter(|hairpin| hairpin.end.is_none())
+            .map(|hairpin| hairpin.kind)
+    }
+
+    fn end_hairpin_in_progress(&mut self, x: StaffSpaces) -> bool {
+        match self.hairpins.last_mut() {
+            Some(hairpin) if hairpin.end.is_none() => {
+                hairpin.end = Some(x);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the baseline of the dynamics for a staff whose lowest element is
+    /// at `lowest_y`.
+    pub fn baseline(lowest_y: Option<StaffSpaces>) -> StaffSpaces {
+        lowest_y.map_or(DEFAULT_BASELINE, |lowest_y| {
+            math::min(DEFAULT_BASELINE, lowest_y - CLEARANCE)
+        })
+    }
+
+    /// Renders the dynamics and hairpins on `baseline`, where hairpins which
+    /// have not ended continue until `end_x`.
+    ///
+    /// Dynamics are centered on the noteheads they apply to, and hairpins stop
+    /// short of the dynamics before and after them.
+    pub fn render(
+        &self,
+        baseline: StaffSpaces,
+        end_x: StaffSpaces,
+        metadata: &Metadata,
+    ) -> Result<Vec<Element<StaffSpaces>>> {
+        let notehead_center = metadata.width_of(Glyph::NoteheadBlack)? / 2.0;
+
+        let dynamics = self
+            .dynamics
+            .iter()
+            .map(|dynamic| {
+                let width = metadata.width_of(dynamic.glyph)?;
+                let origin = dynamic.x + notehead_center - width / 2.0;
+
+                // The italic letters of dynamics may extend past their advance
+                // width, so hairpins keep clear of their bounding boxes.
+                let bounding_box = metadata.bounding_boxes.try_get(dynamic.glyph)?;
+                let left = math::min(origin, origin + bounding_box.sw.x());
+                let right = math::max(origin + width, origin + bounding_box.ne.x());
+
+                Ok((dynamic, origin, left, right))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut elements: Vec<_> = dynamics
+            .iter()
+            .map(|(dynamic, origin, ..)| {
+                Element::Symbol(Symbol {
+                    origin: Coord {
+                        x: *origin,
+                        y: baseline,
+                    },
+                    value: dynamic.glyph,
+                    size: 1.0,
+                })
+            })
+            .collect();
+
+        for hairpin in &self.hairpins {
+            let end = hairpin.end.unwrap_or(end_x);
+
+            let start = dynamics
+                .iter()
+                .filter(|(dynamic, _, _, right)| {
+                    dynamic.x <= hairpin.start && *right + HAIRPIN_SPACING > hairpin.start
+                })
+                .fold(hairpin.start, |start, (_, _, _, right)| {
+                    math::max(start, *right + HAIRPIN_SPACING)
+                });
+
+            let end = dynamics
+                .iter()
+                .filter(|(dynamic, _, left, _)| dynamic.x >= end && *left - HAIRPIN_SPACING < end)
+                .fold(end, |end, (_, _, left, _)| {
+                    math::min(end, *left - HAIRPIN_SPACING)
+                });
+            let end = math::max(end, start + MIN_HAIRPIN_LENGTH);
+
+            let (closed_x, open_x) = match hairpin.kind {
+                hairpin::Kind::Crescendo => (start, end),
+                hairpin::Kind::Diminuendo => (end, start),
+            };
+            let middle = baseline + HAIRPIN_MIDDLE;
+
+            for direction in [1.0, -1.0] {
+                elements.push(Element::Line(Line {
+                    from: Coord {
+                        x: closed_x,
+                        y: middle,
+                    },
+                    to: Coord {
+                        x: open_x,
+                        y: middle + HAIRPIN_OPENING / 2.0 * direction,
+                    },
+                    thickness: metadata.engraving_defaults.hairpin_thickness(),
+                    cap: Linecap::Round,
+                }));
+            }
+        }
+
+        Ok(elements)
+    }
+}
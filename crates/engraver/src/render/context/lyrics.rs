@@ -0,0 +1,222 @@
+use smufl::{Glyph, Metadata, StaffSpaces};
+
+use crate::{
+    render::{
+        engraving_defaults_extensions::EngravingDefaultsExtensions,
+        input::lyric::{Connector, Syllable},
+        ir::{Coord, Element, FontStyle, FontWeight, Line, Linecap, Text, TextAnchor},
+        math,
+        metadata_extensions::MetadataExtensions,
+        TextMetrics,
+    },
+    Result,
+};
+
+/// The baseline of the first verse when nothing extends far below the staff.
+const DEFAULT_BASELINE: StaffSpaces = StaffSpaces(-3.0);
+
+/// The minimum distance from the lowest element of the staff to the baseline
+/// of the first verse.
+const CLEARANCE: StaffSpaces = StaffSpaces(1.5);
+
+/// The distance between the baselines of consecutive verses.
+const VERSE_SPACING: StaffSpaces = StaffSpaces(1.5);
+
+/// The minimum space between syllables of different words.
+const WORD_SPACING: StaffSpaces = StaffSpaces(0.5);
+
+/// The minimum space between syllables of the same word, which leaves room for
+/// the hyphen between them.
+const HYPHEN_SPACING: StaffSpaces = StaffSpaces(1.0);
+
+const HYPHEN_LENGTH: StaffSpaces = StaffSpaces(0.4);
+
+/// The height of hyphens above the baseline.
+const HYPHEN_HEIGHT: StaffSpaces = StaffSpaces(0.25);
+
+/// The space between a syllable and its extender line.
+const EXTENDER_SPACING: StaffSpaces = StaffSpaces(0.2);
+
+/// The verses of lyrics below a staff, which are collected as the staff is
+/// rendered and then placed below everything else.
+#[derive(Debug, Default)]
+pub struct Lyrics {
+    verses: Vec<Vec<PlacedSyllable>>,
+}
+
+#[derive(Debug)]
+struct PlacedSyllable {
+    text: String,
+    connector: Option<Connector>,
+    center: StaffSpaces,
+    width: StaffSpaces,
+    /// The right edge of the notehead of the last note the syllable is sung
+    /// on, which is where its extender line ends.
+    end: StaffSpaces,
+}
+
+impl PlacedSyllable {
+    fn left(&self) -> StaffSpaces {
+        self.center - self.width / 2.0
+    }
+
+    fn right(&self) -> StaffSpaces {
+        self.center + self.width / 2.0
+    }
+}
+
+impl Lyrics {
+    /// Returns how far a note or chord at `x` must move to the right so that
+    /// the syllable, centered on its notehead, does not run into the previous
+    /// syllable of the verse.
+    pub fn overlap(
+        &self,
+        verse: usize,
+        syllable: &Syllable,
+        x: StaffSpaces,
+        notehead: Glyph,
+        text_metrics: &TextMetrics,
+        metadata: &Metadata,
+    ) -> Result<StaffSpaces> {
+        let Some(previous) = self.verses.get(verse).and_then(|verse| verse.last()) else {
+            return Ok(StaffSpaces::zero());
+        };
+
+        let spacing = match previous.connector {
+            Some(Connector::Hyphen) => HYPHEN_SPACING,
+            Some(Connector::Extender) | None => WORD_SPACING,
+        };
+        let width = text_metrics.width_of(&syllable.text, 1.0, FontWeight::Normal);
+        let left = x + metadata.width_of(notehead)? / 2.0 - width / 2.0;
+
+        Ok(math::max(
+            StaffSpaces::zero(),
+            previous.right() + spacing - left,
+        ))
+    }
+
+    /// Returns the right edge of the last syllable of any verse.
+    pub fn right(&self) -> Option<StaffSpaces> {
+        self.verses
+            .iter()
+            .filter_map(|verse| verse.last())
+            .map(PlacedSyllable::right)
+            .reduce(math::max)
+    }
+
+    /// Adds a syllable of a verse for the note or chord at `x`.
+    pub fn add_syllable(
+        &mut self,
+        verse: usize,
+        syllable: &Syllable,
+        x: StaffSpaces,
+        notehead: Glyph,
+        text_metrics: &TextMetrics,
+        metadata: &Metadata,
+    ) -> Result<()> {
+        if self.verses.len() <= verse {
+            self.verses.resize_with(verse + 1, Vec::new);
+        }
+
+        let notehead_width = metadata.width_of(notehead)?;
+
+        self.verses[verse].push(PlacedSyllable {
+            text: syllable.text.clone(),
+            connector: syllable.connector,
+            center: x + notehead_width / 2.0,
+            width: text_metrics.width_of(&syllable.text, 1.0, FontWeight::Normal),
+            end: x + notehead_width,
+        });
+
+        Ok(())
+    }
+
+    /// Continues the melisma of the last syllable of a verse to the note or
+    /// chord at `x`.
+    pub fn add_melisma(
+        &mut self,
+        verse: usize,
+        x: StaffSpaces,
+        notehead: Glyph,
+        metadata: &Metadata,
+    ) -> Result<()> {
+        if let Some(syllable) = self
+            .verses
+            .get_mut(verse)
+            .and_then(|verse| verse.last_mut())
+        {
+            syllable.end = x + metadata.width_of(notehead)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the baseline of the first verse for a staff whose lowest element
+    /// is at `lowest_y`.
+    pub fn baseline(lowest_y: Option<StaffSpaces>) -> StaffSpaces {
+        lowest_y.map_or(DEFAULT_BASELINE, |lowest_y| {
+            math::min(DEFAULT_BASELINE, lowest_y - CLEARANCE)
+        })
+    }
+
+    /// Renders the verses below each other, starting from `baseline`.
+    ///
+    /// Syllables are centered on their noteheads. A hyphen is centered between
+    /// the syllables of a word, and an extender line continues from a syllable
+    /// to the end of its melisma.
+    pub fn render(&self, baseline: StaffSpaces, metadata: &Metadata) -> Vec<Element<StaffSpaces>> {
+        let thickness = metadata.engraving_defaults.lyric_line_thickness();
+        let mut elements = vec![];
+
+        for (index, verse) in self.verses.iter().enumerate() {
+            let y = baseline - VERSE_SPACING * index as f64;
+
+            for (syllable, next) in verse
+                .iter()
+                .zip(verse.iter().skip(1).map(Some).chain([None]))
+            {
+                elements.push(Element::Text(Text {
+                    origin: Coord {
+                        x: syllable.center,
+                        y,
+                    },
+                    value: syllable.text.clone(),
+                    anchor: TextAnchor::Middle,
+                    style: FontStyle::Normal,
+                    weight: FontWeight::Normal,
+                    size: 1.0,
+                }));
+
+                let line = match (syllable.connector, next) {
+                    (Some(Connector::Hyphen), Some(next)) => {
+                        let middle = (syllable.right() + next.left()) / 2.0;
+                        let length = math::min(HYPHEN_LENGTH, next.left() - syllable.right());
+
+                        (length > StaffSpaces::zero()).then_some((
+                            middle - length / 2.0,
+                            middle + length / 2.0,
+                            y + HYPHEN_HEIGHT,
+                        ))
+                    }
+                    (Some(Connector::Extender), _) => {
+                        let start = syllable.right() + EXTENDER_SPACING;
+
+                        (syllable.end > start).then_some((start, syllable.end, y))
+                    }
+                    _ => None,
+                };
+
+                if let Some((from, to, y)) = line {
+                    elements.push(Element::Line(Line {
+                        from: Coord { x: from, y },
+                        to: Coord { x: to, y },
+                        thickness,
+                        cap: Linecap::Butt,
+                    }));
+                }
+            }
+        }
+
+        elements
+    }
+}
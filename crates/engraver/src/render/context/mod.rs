@@ -1,16 +1,73 @@
+pub mod annotations;
 pub mod beam;
+pub mod chord_symbols;
+pub mod dynamics;
+pub mod lyrics;
+pub mod octave_lines;
+pub mod pedals;
 
+use std::rc::Rc;
+
+pub use annotations::Annotations;
 pub use beam::Beam;
+pub use chord_symbols::ChordSymbols;
+pub use dynamics::Dynamics;
+pub use lyrics::Lyrics;
+pub use octave_lines::OctaveLines;
+pub use pedals::Pedals;
+use smufl::StaffSpaces;
 
-use super::stem;
+use super::{
+    input::{hairpin, octave_line, StaffGeometry},
+    ir::Coord,
+    stem, TextMetrics,
+};
 use crate::{Error, Result};
 
 #[derive(Debug, Default)]
 pub struct Context {
+    annotations: Annotations,
     beam: Option<Beam>,
+    chord_symbols: ChordSymbols,
+    dynamics: Dynamics,
+    lyrics: Lyrics,
+    octave_lines: OctaveLines,
+    pedals: Pedals,
+    staff_geometry: StaffGeometry,
+    text_metrics: Rc<TextMetrics>,
+    /// The start of the extension line of an extended trill, which lasts until
+    /// the next note, chord or rest.
+    trill_extension: Option<Coord<StaffSpaces>>,
+}
+
+/// The lines in progress at the end of a system, which continue on the next
+/// system.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Continuing {
+    hairpin: Option<hairpin::Kind>,
+    octave_line: Option<octave_line::Kind>,
+    sustain: bool,
 }
 
 impl Context {
+    pub fn staff_geometry(&self) -> StaffGeometry {
+        self.staff_geometry
+    }
+
+    pub fn set_staff_geometry(&mut self, staff_geometry: StaffGeometry) {
+        self.staff_geometry = staff_geometry;
+    }
+
+    /// Returns the metrics of the text font, which are shared so that they can
+    /// be used while the context is borrowed.
+    pub fn text_metrics(&self) -> Rc<TextMetrics> {
+        Rc::clone(&self.text_metrics)
+    }
+
+    pub fn set_text_metrics(&mut self, text_metrics: Rc<TextMetrics>) {
+        self.text_metrics = text_metrics;
+    }
+
     pub fn begin_beam(&mut self, stem_direction: stem::Direction) -> Result<()> {
         match self.beam {
             Some(_) => Err(Error::StartedBeamWhileBeamInProgress),
@@ -30,4 +87,63 @@ impl Context {
     pub fn beam(&mut self) -> Option<&mut Beam> {
         self.beam.as_mut()
     }
+
+    pub fn annotations(&mut self) -> &mut Annotations {
+        &mut self.annotations
+    }
+
+    pub fn chord_symbols(&mut self) -> &mut ChordSymbols {
+        &mut self.chord_symbols
+    }
+
+    pub fn dynamics(&mut self) -> &mut Dynamics {
+        &mut self.dynamics
+    }
+
+    pub fn lyrics(&mut self) -> &mut Lyrics {
+        &mut self.lyrics
+    }
+
+    pub fn octave_lines(&mut self) -> &mut OctaveLines {
+        &mut self.octave_lines
+    }
+
+    pub fn pedals(&mut self) -> &mut Pedals {
+        &mut self.pedals
+    }
+
+    /// Returns the lines in progress, which continue on the next system.
+    pub fn continuing(&self) -> Continuing {
+        Continuing {
+            hairpin: self.dynamics.hairpin_in_progress(),
+            octave_line: self.octave_lines.in_progress(),
+            sustain: self.pedals.is_sustained(),
+        }
+    }
+
+    /// Continues the lines in progress at the end of the previous system from
+    /// `x`.
+    pub fn resume(&mut self, continuing: Continuing, x: StaffSpaces) {
+        if let Some(kind) = continuing.hairpin {
+            self.dynamics.start_hairpin(kind, x);
+        }
+
+        if let Some(kind) = continuing.octave_line {
+            self.octave_lines.start(kind, x);
+        }
+
+        if continuing.sustain {
+            self.pedals.continue_sustain(x);
+        }
+    }
+
+    pub fn begin_trill_extension(&mut self, start: Coord<StaffSpaces>) {
+        self.trill_extension = Some(start);
+    }
+
+    /// Ends the trill extension line in progress, if any, and returns its
+    /// start.
+    pub fn end_trill_extension(&mut self) -> Option<Coord<StaffSpaces>> {
+        self.trill_extension.take()
+    }
 }
@@ -0,0 +1,181 @@
+use smufl::{Metadata, StaffSpaces};
+
+use crate::{
+    render::{
+        engraving_defaults_extensions::EngravingDefaultsExtensions,
+        input::{octave_line, StaffGeometry},
+        ir::{Coord, Element, Line, Linecap, Symbol},
+        math,
+        metadata_extensions::MetadataExtensions,
+    },
+    Error, Result,
+};
+
+/// The height of the baseline of octave lines above the top line when nothing
+/// extends far above the staff.
+const DEFAULT_HEIGHT_ABOVE: StaffSpaces = StaffSpaces(2.0);
+
+/// The baseline of octave lines below the staff when nothing extends far below
+/// it.
+const DEFAULT_BASELINE_BELOW: StaffSpaces = StaffSpaces(-3.0);
+
+/// The minimum distance from the highest element of the staff to the baseline
+/// of the octave lines above it.
+const CLEARANCE_ABOVE: StaffSpaces = StaffSpaces(1.0);
+
+/// The minimum distance from the lowest element of the staff to the baseline
+/// of the octave lines below it, which leaves room for the height of their
+/// numbers.
+const CLEARANCE_BELOW: StaffSpaces = StaffSpaces(2.5);
+
+/// The height of the dashed line above the baseline of the number.
+const LINE_HEIGHT: StaffSpaces = StaffSpaces(0.75);
+
+const DASH_LENGTH: StaffSpaces = StaffSpaces(0.5);
+
+const GAP_LENGTH: StaffSpaces = StaffSpaces(0.5);
+
+/// The length of the hook at the end of the line, which points towards the
+/// staff.
+const HOOK_LENGTH: StaffSpaces = StaffSpaces(1.0);
+
+/// The octave lines above and below a staff, which are collected as the staff
+/// is rendered and then placed clear of everything else.
+#[derive(Debug, Default)]
+pub struct OctaveLines {
+    lines: Vec<OctaveLine>,
+}
+
+#[derive(Debug)]
+struct OctaveLine {
+    kind: octave_line::Kind,
+    start: StaffSpaces,
+    end: Option<StaffSpaces>,
+}
+
+impl OctaveLines {
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Starts an octave line at `x`, which ends any octave line in progress.
+    pub fn start(&mut self, kind: octave_line::Kind, x: StaffSpaces) {
+        self.end_line_in_progress(x);
+        self.lines.push(OctaveLine {
+            kind,
+            start: x,
+            end: None,
+        });
+    }
+
+    pub fn end(&mut self, x: StaffSpaces) -> Result<()> {
+        if self.end_line_in_progress(x) {
+            Ok(())
+        } else {
+            Err(Error::EndedOctaveLineWhileNoOctaveLineInProgress)
+        }
+    }
+
+    /// Returns the kind of the octave line in progress, if any.
+    pub fn in_progress(&self) -> Option<octave_line::Kind> {
+        self.lines
+            .last()
+            .filter(|line| line.end.is_none())
+            .map(|line| line.kind)
+    }
+
+    fn end_line_in_progress(&mut self, x: StaffSpaces) -> bool {
+        match self.lines.last_mut() {
+            Some(line) if line.end.is_none() => {
+                line.end = Some(x);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the baseline of the octave lines above a staff whose highest
+    /// element is at `highest_y`.
+    pub fn baseline_above(
+        highest_y: Option<StaffSpaces>,
+        staff_geometry: StaffGeometry,
+    ) -> StaffSpaces {
+        let default_y = staff_geometry.top() + DEFAULT_HEIGHT_ABOVE;
+
+        highest_y.map_or(default_y, |highest_y| {
+            math::max(default_y, highest_y + CLEARANCE_ABOVE)
+        })
+    }
+
+    /// Returns the baseline of the octave lines below a staff whose lowest
+    /// element is at `lowest_y`.
+    pub fn baseline_below(lowest_y: Option<StaffSpaces>) -> StaffSpaces {
+        lowest_y.map_or(DEFAULT_BASELINE_BELOW, |lowest_y| {
+            math::min(DEFAULT_BASELINE_BELOW, lowest_y - CLEARANCE_BELOW)
+        })
+    }
+
+    /// Renders the octave lines on the baselines `above` and `below` the
+    /// staff, where a line which has not ended continues until `end_x`.
+    ///
+    /// Each line starts with its number, followed by a dashed line to its end,
+    /// where a hook points towards the staff.
+    pub fn render(
+        &self,
+        above: StaffSpaces,
+        below: StaffSpaces,
+        end_x: StaffSpaces,
+        metadata: &Metadata,
+    ) -> Result<Vec<Element<StaffSpaces>>> {
+        let thickness = metadata.engraving_defaults.octave_line_thickness();
+        let line = |from, to| {
+            Element::Line(Line {
+                from,
+                to,
+                thickness,
+                cap: Linecap::Butt,
+            })
+        };
+
+        let mut elements = vec![];
+
+        for octave_line in &self.lines {
+            let glyph = octave_line.kind.glyph();
+            let (baseline, hook_direction) = if octave_line.kind.is_above() {
+                (above, -1.0)
+            } else {
+                (below, 1.0)
+            };
+
+            elements.push(Element::Symbol(Symbol {
+                origin: Coord {
+                    x: octave_line.start,
+                    y: baseline,
+                },
+                value: glyph,
+                size: 1.0,
+            }));
+
+            let y = baseline + LINE_HEIGHT;
+            let start = octave_line.start + metadata.width_of(glyph)? + GAP_LENGTH;
+            let end = math::max(octave_line.end.unwrap_or(end_x), start);
+
+            let mut x = start;
+            while x < end {
+                let dash_end = math::min(x + DASH_LENGTH, end);
+                elements.push(line(Coord { x, y }, Coord { x: dash_end, y }));
+                x = dash_end + GAP_LENGTH;
+            }
+
+            elements.push(line(
+                Coord { x: end, y },
+                Coord {
+                    x: end,
+                    y: y + HOOK_LENGTH * hook_direction,
+                },
+            ));
+        }
+
+        Ok(elements)
+    }
+}
@@ -0,0 +1,238 @@
+use smufl::{Glyph, Metadata, StaffSpaces};
+
+use crate::{
+    render::{
+        engraving_defaults_extensions::EngravingDefaultsExtensions,
+        input::{pedal, Pedal},
+        ir::{Coord, Element, FontStyle, FontWeight, Line, Linecap, Symbol, Text, TextAnchor},
+        math,
+        metadata_extensions::MetadataExtensions,
+    },
+    Result,
+};
+
+/// The baseline of pedal marks when nothing extends far below the staff.
+const DEFAULT_BASELINE: StaffSpaces = StaffSpaces(-4.0);
+
+/// The minimum distance from the lowest element of the staff to the baseline
+/// of pedal marks, which leaves room for the height of "Ped." and of the hooks
+/// of pedal brackets.
+const CLEARANCE: StaffSpaces = StaffSpaces(2.5);
+
+/// The distance between the baselines of the sustain pedal marks and of the
+/// una corda and tre corde markings below them.
+const ROW_SPACING: StaffSpaces = StaffSpaces(2.0);
+
+/// The space between the asterisk and "Ped." of a pedal change.
+const CHANGE_SPACING: StaffSpaces = StaffSpaces(0.3);
+
+/// The height of the hooks at the ends of a pedal bracket, and of the notches
+/// where the pedal is changed, for a fully pressed pedal.
+const HOOK_HEIGHT: StaffSpaces = StaffSpaces(1.0);
+
+/// The width of the notches of a pedal bracket.
+const NOTCH_WIDTH: StaffSpaces = StaffSpaces(1.0);
+
+/// The pedal marks below a staff, which are collected as the staff is rendered
+/// and then placed on a common baseline below everything else.
+#[derive(Debug, Default)]
+pub struct Pedals {
+    marks: Vec<(Pedal, StaffSpaces)>,
+    /// The start of a bracket which continues from the previous system, and so
+    /// has no hook.
+    continued_from: Option<StaffSpaces>,
+}
+
+impl Pedals {
+    pub fn is_empty(&self) -> bool {
+        self.marks.is_empty() && self.continued_from.is_none()
+    }
+
+    /// Adds a pedal mark for the note at `x`.
+    pub fn add(&mut self, pedal: Pedal, x: StaffSpaces) {
+        self.marks.push((pedal, x));
+    }
+
+    /// Returns whether the sustain pedal is still pressed after the last mark.
+    pub fn is_sustained(&self) -> bool {
+        self.marks
+            .iter()
+            .rev()
+            .find_map(|(pedal, _)| match pedal {
+                Pedal::Down(_) | Pedal::Change(_) => Some(true),
+                Pedal::Up => Some(false),
+                Pedal::UnaCorda | Pedal::TreCorde => None,
+            })
+            .unwrap_or(self.continued_from.is_some())
+    }
+
+    /// Continues the sustain pedal pressed on the previous system from `x`.
+    pub fn continue_sustain(&mut self, x: StaffSpaces) {
+        self.continued_from = Some(x);
+    }
+
+    /// Returns the baseline of the pedal marks for a staff whose lowest element
+    /// is at `lowest_y`.
+    pub fn baseline(lowest_y: Option<StaffSpaces>) -> StaffSpaces {
+        lowest_y.map_or(DEFAULT_BASELINE, |lowest_y| {
+            math::min(DEFAULT_BASELINE, lowest_y - CLEARANCE)
+        })
+    }
+
+    /// Renders the sustain pedal marks in the `style` on `baseline`, where a
+    /// bracket which has not ended continues until `end_x`.
+    ///
+    /// Una corda and tre corde markings are drawn in italics, in a row below
+    /// any sustain pedal marks.
+    pub fn render(
+        &self,
+        baseline: StaffSpaces,
+        end_x: StaffSpaces,
+        style: pedal::Style,
+        metadata: &Metadata,
+    ) -> Result<Vec<Element<StaffSpaces>>> {
+        let (soft, sustain): (Vec<_>, Vec<_>) = self
+            .marks
+            .iter()
+            .partition(|(pedal, _)| matches!(pedal, Pedal::UnaCorda | Pedal::TreCorde));
+
+        let mut elements = match style {
+            pedal::Style::Text => render_text(&sustain, baseline, metadata)?,
+            pedal::Style::Bracket => {
+                render_bracket(&sustain, self.continued_from, baseline, end_x, metadata)
+            }
+        };
+
+        let soft_baseline = if sustain.is_empty() {
+            baseline
+        } else {
+            baseline - ROW_SPACING
+        };
+
+        elements.extend(soft.iter().map(|(pedal, x)| {
+            Element::Text(Text {
+                origin: Coord {
+                    x: *x,
+                    y: soft_baseline,
+                },
+                value: match pedal {
+                    Pedal::UnaCorda => "una corda",
+                    _ => "tre corde",
+                }
+                .to_owned(),
+                anchor: TextAnchor::Start,
+                style: FontStyle::Italic,
+                weight: FontWeight::Normal,
+                size: 1.0,
+            })
+        }));
+
+        Ok(elements)
+    }
+}
+
+/// Returns "Ped." where the pedal is pressed, "½ Ped." where it is pressed
+/// halfway and an asterisk where it is lifted, followed by "Ped." for a change.
+fn render_text(
+    marks: &[&(Pedal, StaffSpaces)],
+    baseline: StaffSpaces,
+    metadata: &Metadata,
+) -> Result<Vec<Element<StaffSpaces>>> {
+    let symbol = |x, glyph: Glyph| {
+        Element::Symbol(Symbol {
+            origin: Coord { x, y: baseline },
+            value: glyph,
+            size: 1.0,
+        })
+    };
+    let down_glyph = |depth| match depth {
+        pedal::Depth::Full => Glyph::KeyboardPedalPed,
+        pedal::Depth::Half => Glyph::KeyboardPedalHalf,
+    };
+
+    let mut elements = vec![];
+
+    for (pedal, x) in marks {
+        match pedal {
+            Pedal::Down(depth) => elements.push(symbol(*x, down_glyph(*depth))),
+            Pedal::Change(depth) => {
+                let up_width = metadata.width_of(Glyph::KeyboardPedalUp)?;
+
+                elements.push(symbol(*x, Glyph::KeyboardPedalUp));
+                elements.push(symbol(*x + up_width + CHANGE_SPACING, down_glyph(*depth)));
+            }
+            Pedal::Up => elements.push(symbol(*x, Glyph::KeyboardPedalUp)),
+            Pedal::UnaCorda | Pedal::TreCorde => {}
+        }
+    }
+
+    Ok(elements)
+}
+
+/// Returns the lines of pedal brackets, which start with a hook where the
+/// pedal is pressed, have a notch where it is changed and end with a hook
+/// where it is lifted, after any bracket continued from `continued_from`.
+fn render_bracket(
+    marks: &[&(Pedal, StaffSpaces)],
+    continued_from: Option<StaffSpaces>,
+    baseline: StaffSpaces,
+    end_x: StaffSpaces,
+    metadata: &Metadata,
+) -> Vec<Element<StaffSpaces>> {
+    let thickness = metadata.engraving_defaults.pedal_line_thickness();
+    let line = |from: (StaffSpaces, StaffSpaces), to: (StaffSpaces, StaffSpaces)| {
+        Element::Line(Line {
+            from: Coord {
+                x: from.0,
+                y: from.1,
+            },
+            to: Coord { x: to.0, y: to.1 },
+            thickness,
+            cap: Linecap::Butt,
+        })
+    };
+    let hook_top = |depth| match depth {
+        pedal::Depth::Full => baseline + HOOK_HEIGHT,
+        pedal::Depth::Half => baseline + HOOK_HEIGHT / 2.0,
+    };
+
+    let mut elements = vec![];
+    // The start of the part of the bracket which has not been drawn yet.
+    let mut start = continued_from;
+
+    for (pedal, x) in marks {
+        let x = *x;
+
+        match (pedal, start) {
+            (Pedal::Change(depth), Some(from)) => {
+                let left = math::max(from, x - NOTCH_WIDTH / 2.0);
+                let right = x + NOTCH_WIDTH / 2.0;
+
+                elements.push(line((from, baseline), (left, baseline)));
+                elements.push(line((left, baseline), (x, hook_top(*depth))));
+                elements.push(line((x, hook_top(*depth)), (right, baseline)));
+                start = Some(right);
+            }
+            (Pedal::Down(depth) | Pedal::Change(depth), _) => {
+                if let Some(from) = start {
+                    elements.push(line((from, baseline), (x, baseline)));
+                }
+
+                elements.push(line((x, hook_top(*depth)), (x, baseline)));
+                start = Some(x);
+            }
+            (Pedal::Up, Some(from)) => {
+                elements.push(line((from, baseline), (x, baseline)));
+                elements.push(line((x, baseline), (x, hook_top(pedal::Depth::Full))));
+                start = None;
+            }
+            (Pedal::Up, None) | (Pedal::UnaCorda | Pedal::TreCorde, _) => {}
+        }
+    }
+
+    if let Some(from) = start {
+        elements.push(line((from, baseline), (math::max(from, end_x), baseline)));
+    }
+
+    elements
+}
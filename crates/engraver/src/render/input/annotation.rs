@@ -0,0 +1,274 @@
+use smufl::{Glyph, Metadata, StaffSpaces};
+
+use super::{
+    duration::{self, Dots, DIVISIONS_PER_QUARTER},
+    Duration,
+};
+use crate::{
+    render::{
+        context::Context,
+        engraving_defaults_extensions::EngravingDefaultsExtensions,
+        ir::{Coord, Element, FontStyle, FontWeight, Line, Linecap, Symbol, Text, TextAnchor},
+        metadata_extensions::MetadataExtensions,
+        Output, Render, TextMetrics,
+    },
+    Result,
+};
+
+/// The size of the notes of metronome marks, relative to other glyphs, which
+/// matches them to the size of text.
+const METRONOME_SIZE: f64 = 0.6;
+
+/// The space between the text of a tempo marking and its metronome mark.
+const TEXT_TO_METRONOME: StaffSpaces = StaffSpaces(0.5);
+
+/// The space between the note of a metronome mark and its dots.
+const NOTE_TO_DOT: StaffSpaces = StaffSpaces(0.15);
+
+/// The space between the note of a metronome mark, or its last dot, and the
+/// number of beats per minute.
+const NOTE_TO_NUMBER: StaffSpaces = StaffSpaces(0.3);
+
+/// The space between a rehearsal mark and its enclosing box.
+const ENCLOSURE_PADDING: StaffSpaces = StaffSpaces(0.3);
+
+/// Text attached to a point in the staff, which applies from the note, chord
+/// or rest which follows it and is drawn above the staff.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Annotation {
+    Tempo(Tempo),
+    /// An expression such as "dolce" or "rit.", drawn in italics.
+    Expression(String),
+    /// A rehearsal mark such as "A", drawn in bold in a box.
+    RehearsalMark(String),
+}
+
+/// A tempo marking such as "Allegro", a metronome mark, or both.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Tempo {
+    /// The text of the marking, drawn in bold.
+    pub text: Option<String>,
+    pub metronome_mark: Option<MetronomeMark>,
+}
+
+/// A metronome mark such as ♩ = 120.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MetronomeMark {
+    /// The duration of a beat.
+    pub beat: Duration,
+    pub per_minute: u16,
+}
+
+impl MetronomeMark {
+    /// Returns the tempo in quarter notes per minute, such as 90 for a dotted
+    /// quarter note at 60.
+    pub fn quarters_per_minute(&self) -> u16 {
+        let divisions = u32::from(self.per_minute) * self.beat.divisions();
+        let quarters = (divisions + DIVISIONS_PER_QUARTER / 2) / DIVISIONS_PER_QUARTER;
+
+        quarters.try_into().unwrap_or(u16::MAX)
+    }
+}
+
+impl Annotation {
+    /// Returns the width of the annotation.
+    pub fn width(&self, text_metrics: &TextMetrics, metadata: &Metadata) -> Result<StaffSpaces> {
+        let (_, right) = self.layout(
+            StaffSpaces::zero(),
+            StaffSpaces::zero(),
+            text_metrics,
+            metadata,
+        )?;
+
+        Ok(right)
+    }
+
+    /// Returns the annotation starting at `x`, with its baseline at `y`.
+    pub(crate) fn render_at(
+        &self,
+        x: StaffSpaces,
+        y: StaffSpaces,
+        text_metrics: &TextMetrics,
+        metadata: &Metadata,
+    ) -> Result<Vec<Element<StaffSpaces>>> {
+        let (elements, _) = self.layout(x, y, text_metrics, metadata)?;
+
+        Ok(elements)
+    }
+
+    /// Returns the elements of the annotation starting at `x`, with its
+    /// baseline at `y`, and the position of its right edge.
+    fn layout(
+        &self,
+        x: StaffSpaces,
+        y: StaffSpaces,
+        text_metrics: &TextMetrics,
+        metadata: &Metadata,
+    ) -> Result<(Vec<Element<StaffSpaces>>, StaffSpaces)> {
+        match self {
+            Self::Tempo(tempo) => tempo.layout(x, y, text_metrics, metadata),
+            Self::Expression(text) => Ok((
+                vec![text_element(
+                    x,
+                    y,
+                    text,
+                    FontStyle::Italic,
+                    FontWeight::Normal,
+                )],
+                x + text_metrics.width_of(text, 1.0, FontWeight::Normal),
+            )),
+            Self::RehearsalMark(text) => {
+                let thickness = metadata.engraving_defaults.text_enclosure_thickness();
+                let left = x;
+                let right = x
+                    + text_metrics.width_of(text, 1.0, FontWeight::Bold)
+                    + ENCLOSURE_PADDING * 2.0;
+                let bottom = y - ENCLOSURE_PADDING;
+                let top = y + text_metrics.cap_height(1.0) + ENCLOSURE_PADDING;
+
+                let line = |from: (StaffSpaces, StaffSpaces), to: (StaffSpaces, StaffSpaces)| {
+                    Element::Line(Line {
+                        from: Coord {
+                            x: from.0,
+                            y: from.1,
+                        },
+                        to: Coord { x: to.0, y: to.1 },
+                        thickness,
+                        cap: Linecap::Round,
+                    })
+                };
+
+                Ok((
+                    vec![
+                        text_element(
+                            x + ENCLOSURE_PADDING,
+                            y,
+                            text,
+                            FontStyle::Normal,
+                            FontWeight::Bold,
+                        ),
+                        line((left, bottom), (right, bottom)),
+                        line((right, bottom), (right, top)),
+                        line((right, top), (left, top)),
+                        line((left, top), (left, bottom)),
+                    ],
+                    right,
+                ))
+            }
+        }
+    }
+}
+
+impl Tempo {
+    /// Returns the elements of the marking starting at `x`, with its text in
+    /// bold followed by the note, dots and number of the metronome mark.
+    fn layout(
+        &self,
+        mut x: StaffSpaces,
+        y: StaffSpaces,
+        text_metrics: &TextMetrics,
+        metadata: &Metadata,
+    ) -> Result<(Vec<Element<StaffSpaces>>, StaffSpaces)> {
+        let mut elements = vec![];
+
+        if let Some(text) = &self.text {
+            elements.push(text_element(
+                x,
+                y,
+                text,
+                FontStyle::Normal,
+                FontWeight::Bold,
+            ));
+            x += text_metrics.width_of(text, 1.0, FontWeight::Bold);
+
+            if self.metronome_mark.is_some() {
+                x += TEXT_TO_METRONOME;
+            }
+        }
+
+        if let Some(metronome_mark) = &self.metronome_mark {
+            let note = metronome_glyph(metronome_mark.beat.value);
+            elements.push(symbol_element(x, y, note));
+            x += metadata.width_of(note)? * METRONOME_SIZE;
+
+            let num_dots = match metronome_mark.beat.dots {
+                None => 0,
+                Some(Dots::Dot) => 1,
+                Some(Dots::DoubleDot) => 2,
+            };
+
+            for _ in 0..num_dots {
+                x += NOTE_TO_DOT;
+                elements.push(symbol_element(x, y, Glyph::MetAugmentationDot));
+                x += metadata.width_of(Glyph::MetAugmentationDot)? * METRONOME_SIZE;
+            }
+
+            x += NOTE_TO_NUMBER;
+
+            let number = format!("= {}", metronome_mark.per_minute);
+            elements.push(text_element(
+                x,
+                y,
+                &number,
+                FontStyle::Normal,
+                FontWeight::Normal,
+            ));
+            x += text_metrics.width_of(&number, 1.0, FontWeight::Normal);
+        }
+
+        Ok((elements, x))
+    }
+}
+
+impl Render for Annotation {
+    /// Adds the annotation to the annotations above the staff, which are
+    /// rendered once the whole staff has been rendered.
+    fn render(&self, x: StaffSpaces, context: &mut Context, metadata: &Metadata) -> Result<Output> {
+        let width = self.width(&context.text_metrics(), metadata)?;
+        context.annotations().add(self.clone(), x, width);
+
+        Ok(Output {
+            elements: vec![],
+            width: StaffSpaces::zero(),
+        })
+    }
+}
+
+fn text_element(
+    x: StaffSpaces,
+    y: StaffSpaces,
+    text: &str,
+    style: FontStyle,
+    weight: FontWeight,
+) -> Element<StaffSpaces> {
+    Element::Text(Text {
+        origin: Coord { x, y },
+        value: text.to_owned(),
+        anchor: TextAnchor::Start,
+        style,
+        weight,
+        size: 1.0,
+    })
+}
+
+fn symbol_element(x: StaffSpaces, y: StaffSpaces, glyph: Glyph) -> Element<StaffSpaces> {
+    Element::Symbol(Symbol {
+        origin: Coord { x, y },
+        value: glyph,
+        size: METRONOME_SIZE,
+    })
+}
+
+fn metronome_glyph(value: duration::Value) -> Glyph {
+    match value {
+        duration::Value::Whole => Glyph::MetNoteWhole,
+        duration::Value::Half => Glyph::MetNoteHalfUp,
+        duration::Value::Quarter => Glyph::MetNoteQuarterUp,
+        duration::Value::Eighth => Glyph::MetNote8thUp,
+        duration::Value::Sixteenth => Glyph::MetNote16thUp,
+        duration::Value::ThirtySecond => Glyph::MetNote32ndUp,
+        duration::Value::SixtyFourth => Glyph::MetNote64thUp,
+        duration::Value::OneHundredTwentyEighth => Glyph::MetNote128thUp,
+        duration::Value::TwoHundredFiftySixth => Glyph::MetNote256thUp,
+    }
+}
@@ -0,0 +1,136 @@
+use smufl::{Glyph, Metadata, StaffSpaces};
+use strum_macros::EnumIter;
+
+use crate::{
+    render::{
+        glyph_data_extensions::GlyphDataExtensions,
+        input::StaffGeometry,
+        ir::{Coord, Element, Symbol},
+        metadata_extensions::MetadataExtensions,
+        stem,
+    },
+    Result,
+};
+
+/// The distance from the center of a notehead to the center of the nearest
+/// articulation.
+const NOTEHEAD_TO_ARTICULATION: StaffSpaces = StaffSpaces(1.0);
+
+/// The space between stacked articulations.
+const ARTICULATION_SPACING: StaffSpaces = StaffSpaces(0.25);
+
+/// An articulation attached to a note or chord.
+///
+/// The variants are in the order in which articulations are stacked, starting
+/// closest to the notehead.
+#[derive(Clone, Copy, Debug, EnumIter, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Articulation {
+    Staccato,
+    Staccatissimo,
+    Tenuto,
+    Stress,
+    Accent,
+    Marcato,
+}
+
+/// Which side of a notehead articulations are drawn on.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Placement {
+    Above,
+    Below,
+}
+
+impl Articulation {
+    fn glyph(&self, placement: Placement) -> Glyph {
+        match (self, placement) {
+            (Self::Staccato, Placement::Above) => Glyph::ArticStaccatoAbove,
+            (Self::Staccato, Placement::Below) => Glyph::ArticStaccatoBelow,
+            (Self::Staccatissimo, Placement::Above) => Glyph::ArticStaccatissimoAbove,
+            (Self::Staccatissimo, Placement::Below) => Glyph::ArticStaccatissimoBelow,
+            (Self::Tenuto, Placement::Above) => Glyph::ArticTenutoAbove,
+            (Self::Tenuto, Placement::Below) => Glyph::ArticTenutoBelow,
+            (Self::Stress, Placement::Above) => Glyph::ArticStressAbove,
+            (Self::Stress, Placement::Below) => Glyph::ArticStressBelow,
+            (Self::Accent, Placement::Above) => Glyph::ArticAccentAbove,
+            (Self::Accent, Placement::Below) => Glyph::ArticAccentBelow,
+            (Self::Marcato, Placement::Above) => Glyph::ArticMarcatoAbove,
+            (Self::Marcato, Placement::Below) => Glyph::ArticMarcatoBelow,
+        }
+    }
+}
+
+/// Returns the articulations for a notehead at `x` and `y`, stacked outwards
+/// from the notehead on the side opposite the stem and centered on the
+/// notehead.
+///
+/// Articulations which would be centered on a staff line are moved to the
+/// middle of the next space.
+pub fn create_articulations(
+    x: StaffSpaces,
+    y: StaffSpaces,
+    notehead_glyph: Glyph,
+    stem_direction: stem::Direction,
+    articulations: &[Articulation],
+    staff_geometry: StaffGeometry,
+    metadata: &Metadata,
+) -> Result<Vec<Element<StaffSpaces>>> {
+    let (placement, direction) = match stem_direction {
+        stem::Direction::Up => (Placement::Below, -1.0),
+        stem::Direction::Down => (Placement::Above, 1.0),
+    };
+
+    let mut articulations = articulations.to_vec();
+    articulations.sort();
+    articulations.dedup();
+
+    let center_x = x + metadata.width_of(notehead_glyph)? / 2.0;
+
+    let mut elements = Vec::with_capacity(articulations.len());
+    let mut edge = y + NOTEHEAD_TO_ARTICULATION * direction;
+
+    for (index, articulation) in articulations.into_iter().enumerate() {
+        let glyph = articulation.glyph(placement);
+        let bounding_box = metadata.bounding_boxes.try_get(glyph)?;
+        let height = bounding_box.ne.y() - bounding_box.sw.y();
+
+        let center_y = if index == 0 {
+            outside_staff_lines(edge, direction, staff_geometry)
+        } else {
+            outside_staff_lines(
+                edge + (height / 2.0 + ARTICULATION_SPACING) * direction,
+                direction,
+                staff_geometry,
+            )
+        };
+
+        elements.push(Element::Symbol(Symbol {
+            origin: Coord {
+                x: center_x - (bounding_box.sw.x() + bounding_box.ne.x()) / 2.0,
+                y: center_y - (bounding_box.sw.y() + bounding_box.ne.y()) / 2.0,
+            },
+            value: glyph,
+            size: 1.0,
+        }));
+
+        edge = center_y + height / 2.0 * direction;
+    }
+
+    Ok(elements)
+}
+
+/// Moves `y` away from the notehead to the middle of the next space if it is on
+/// a staff line.
+fn outside_staff_lines(
+    y: StaffSpaces,
+    direction: f64,
+    staff_geometry: StaffGeometry,
+) -> StaffSpaces {
+    let is_in_staff = staff_geometry.contains(y);
+    let distance_to_line = y.0 - y.0.round();
+
+    if is_in_staff && distance_to_line.abs() < 0.25 {
+        StaffSpaces(y.0.round() + 0.5 * direction)
+    } else {
+        y
+    }
+}
@@ -1,11 +1,14 @@
-use smufl::{Metadata, StaffSpaces};
+use smufl::{Glyph, Metadata, StaffSpaces};
 use strum_macros::EnumIter;
 
 use crate::{
     render::{
         context::Context,
         engraving_defaults_extensions::EngravingDefaultsExtensions,
-        ir::{Coord, Element, Line, Linecap},
+        glyph_data_extensions::GlyphDataExtensions,
+        input::StaffGeometry,
+        ir::{Coord, Element, Line, Linecap, Symbol},
+        metadata_extensions::MetadataExtensions,
         Output, Render,
     },
     Result,
@@ -15,6 +18,14 @@ use crate::{
 pub enum Barline {
     Thin,
     Final,
+    Double,
+    Heavy,
+    Dashed,
+    Dotted,
+    StartRepeat,
+    EndRepeat,
+    EndStartRepeat,
+    Invisible,
 }
 
 impl Default for Barline {
@@ -23,76 +34,211 @@ impl Default for Barline {
     }
 }
 
-impl Render for Barline {
+/// A part of a barline, from left to right.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Part {
+    Thin,
+    Thick,
+    Dashed,
+    Dotted,
+    RepeatDots,
+}
+
+impl Barline {
+    fn parts(&self) -> &'static [Part] {
+        match self {
+            Self::Thin => &[Part::Thin],
+            Self::Final => &[Part::Thin, Part::Thick],
+            Self::Double => &[Part::Thin, Part::Thin],
+            Self::Heavy => &[Part::Thick],
+            Self::Dashed => &[Part::Dashed],
+            Self::Dotted => &[Part::Dotted],
+            Self::StartRepeat => &[Part::Thick, Part::Thin, Part::RepeatDots],
+            Self::EndRepeat => &[Part::RepeatDots, Part::Thin, Part::Thick],
+            Self::EndStartRepeat => &[
+                Part::RepeatDots,
+                Part::Thin,
+                Part::Thick,
+                Part::Thin,
+                Part::RepeatDots,
+            ],
+            Self::Invisible => &[],
+        }
+    }
+
+    /// Returns whether the barline ends a repeated section.
+    pub fn is_end_repeat(&self) -> bool {
+        matches!(self, Self::EndRepeat | Self::EndStartRepeat)
+    }
+}
+
+impl Part {
     fn render(
         &self,
         x: StaffSpaces,
-        _context: &mut Context,
+        staff_geometry: StaffGeometry,
         metadata: &Metadata,
     ) -> Result<Output> {
+        let engraving_defaults = &metadata.engraving_defaults;
+        let (bottom, top) = staff_geometry.barline_extent();
+
         Ok(match self {
             Self::Thin => {
-                let thickness = metadata.engraving_defaults.thin_barline_thickness();
+                vertical_line(x, bottom, top, engraving_defaults.thin_barline_thickness())
+            }
+            Self::Thick => {
+                vertical_line(x, bottom, top, engraving_defaults.thick_barline_thickness())
+            }
+            Self::Dashed => {
+                let thickness = engraving_defaults.dashed_barline_thickness();
+                let dash_length = engraving_defaults.dashed_barline_dash_length();
+                let period = dash_length + engraving_defaults.dashed_barline_gap_length();
                 let x = x + thickness / 2.0;
 
-                let line = Element::Line(Line {
-                    from: Coord {
-                        x,
-                        y: StaffSpaces::zero(),
-                    },
-                    to: Coord {
-                        x,
-                        y: StaffSpaces(4.0),
-                    },
-                    thickness,
-                    cap: Linecap::Butt,
-                });
-
                 Output {
-                    elements: vec![line],
+                    elements: dash_positions(bottom, top, period)
+                        .map(|top| {
+                            Element::Line(Line {
+                                from: Coord { x, y: top },
+                                to: Coord {
+                                    x,
+                                    y: top - dash_length,
+                                },
+                                thickness,
+                                cap: Linecap::Butt,
+                            })
+                        })
+                        .collect(),
                     width: thickness,
                 }
             }
-            Self::Final => {
-                let thin_thickness = metadata.engraving_defaults.thin_barline_thickness();
-                let thin_x = x + thin_thickness / 2.0;
-
-                let thin_line = Element::Line(Line {
-                    from: Coord {
-                        x: thin_x,
-                        y: StaffSpaces::zero(),
-                    },
-                    to: Coord {
-                        x: thin_x,
-                        y: StaffSpaces(4.0),
-                    },
-                    thickness: thin_thickness,
-                    cap: Linecap::Butt,
-                });
-
-                let separation = metadata.engraving_defaults.thin_thick_barline_separation();
-
-                let thick_thickness = metadata.engraving_defaults.thick_barline_thickness();
-                let thick_x = x + thin_thickness + separation + thick_thickness / 2.0;
-
-                let thick_line = Element::Line(Line {
-                    from: Coord {
-                        x: thick_x,
-                        y: StaffSpaces::zero(),
-                    },
-                    to: Coord {
-                        x: thick_x,
-                        y: StaffSpaces(4.0),
-                    },
-                    thickness: thick_thickness,
-                    cap: Linecap::Butt,
-                });
+            Self::Dotted => {
+                // Dotted barlines have no engraving defaults of their own, so
+                // they use round dots the size of the dashes of dashed barlines.
+                let diameter = engraving_defaults.dashed_barline_thickness();
+                let period = engraving_defaults.dashed_barline_dash_length()
+                    + engraving_defaults.dashed_barline_gap_length();
+                let x = x + diameter / 2.0;
+
+                Output {
+                    elements: dash_positions(bottom, top, period)
+                        .chain([bottom])
+                        .map(|y| {
+                            Element::Line(Line {
+                                from: Coord { x, y },
+                                to: Coord { x, y },
+                                thickness: diameter,
+                                cap: Linecap::Round,
+                            })
+                        })
+                        .collect(),
+                    width: diameter,
+                }
+            }
+            Self::RepeatDots => {
+                let glyph = Glyph::RepeatDot;
+                let bounding_box = metadata.bounding_boxes.try_get(glyph)?;
+                let center_offset = (bounding_box.sw.y() + bounding_box.ne.y()) / 2.0;
 
                 Output {
-                    elements: vec![thin_line, thick_line],
-                    width: thin_thickness + separation + thick_thickness,
+                    elements: repeat_dot_positions(staff_geometry)
+                        .iter()
+                        .map(|y| {
+                            Element::Symbol(Symbol {
+                                origin: Coord {
+                                    x,
+                                    y: *y - center_offset,
+                                },
+                                value: glyph,
+                                size: 1.0,
+                            })
+                        })
+                        .collect(),
+                    width: metadata.width_of(glyph)?,
                 }
             }
         })
     }
+
+    /// Returns the space between two adjacent parts of a barline.
+    fn separation(&self, next: &Part, metadata: &Metadata) -> StaffSpaces {
+        let engraving_defaults = &metadata.engraving_defaults;
+
+        match (self, next) {
+            (Self::RepeatDots, _) | (_, Self::RepeatDots) => {
+                engraving_defaults.repeat_barline_dot_separation()
+            }
+            (Self::Thin, Self::Thick) | (Self::Thick, Self::Thin) => {
+                engraving_defaults.thin_thick_barline_separation()
+            }
+            _ => engraving_defaults.barline_separation(),
+        }
+    }
+}
+
+fn vertical_line(
+    x: StaffSpaces,
+    bottom: StaffSpaces,
+    top: StaffSpaces,
+    thickness: StaffSpaces,
+) -> Output {
+    let x = x + thickness / 2.0;
+
+    Output {
+        elements: vec![Element::Line(Line {
+            from: Coord { x, y: bottom },
+            to: Coord { x, y: top },
+            thickness,
+            cap: Linecap::Butt,
+        })],
+        width: thickness,
+    }
+}
+
+/// Returns the tops of the dashes of a dashed barline, starting from its top.
+fn dash_positions(
+    bottom: StaffSpaces,
+    top: StaffSpaces,
+    period: StaffSpaces,
+) -> impl Iterator<Item = StaffSpaces> {
+    let num_dashes = ((top - bottom).0 / period.0).floor() as u32;
+
+    (0..num_dashes).map(move |index| top - period * f64::from(index))
+}
+
+/// Returns the y-coordinates of the centers of the dots of repeat barlines,
+/// which are in the spaces either side of the middle line, or either side of
+/// the middle space on staves with an even number of lines.
+fn repeat_dot_positions(staff_geometry: StaffGeometry) -> [StaffSpaces; 2] {
+    let middle = staff_geometry.middle();
+    let offset = if staff_geometry.lines.is_multiple_of(2) {
+        StaffSpaces(1.0)
+    } else {
+        StaffSpaces(0.5)
+    };
+
+    [middle - offset, middle + offset]
+}
+
+impl Render for Barline {
+    fn render(&self, x: StaffSpaces, context: &mut Context, metadata: &Metadata) -> Result<Output> {
+        let staff_geometry = context.staff_geometry();
+        let mut elements = vec![];
+        let mut width = StaffSpaces::zero();
+        let mut previous: Option<Part> = None;
+
+        for part in self.parts() {
+            if let Some(previous) = previous {
+                width += previous.separation(part, metadata);
+            }
+
+            let mut output = part.render(x + width, staff_geometry, metadata)?;
+            elements.append(&mut output.elements);
+            width += output.width;
+
+            previous = Some(*part);
+        }
+
+        Ok(Output { elements, width })
+    }
 }
@@ -1,9 +1,10 @@
 use smufl::StaffSpaces;
 
 use super::{
+    articulation::create_articulations,
     duration,
-    note::{create_accidental, create_flag, create_leger_lines, create_notehead},
-    Accidental, Beam, Duration,
+    note::{create_accidental, create_flag, create_leger_lines, create_notehead, highest_point},
+    Accidental, Articulation, Beam, Duration, Ornament,
 };
 use crate::{
     render::{
@@ -20,6 +21,8 @@ use crate::{
 pub struct Chord {
     notes: Vec<Note>,
     pub duration: Duration,
+    pub articulations: Vec<Articulation>,
+    pub ornament: Option<Ornament>,
     pub beam: Option<Beam>,
     pub id: Option<String>,
 }
@@ -41,7 +44,8 @@ enum Side {
 }
 
 impl Chord {
-    /// Returns a `Chord` with the given `notes` and `duration`.
+    /// Returns a `Chord` with the given `notes`, `duration` and
+    /// `articulations`.
     ///
     /// # Panics
     ///
@@ -49,6 +53,7 @@ impl Chord {
     pub fn new<Notes>(
         notes: Notes,
         duration: Duration,
+        articulations: Vec<Articulation>,
         beam: Option<Beam>,
         id: Option<String>,
     ) -> Self
@@ -68,6 +73,8 @@ impl Chord {
         Self {
             notes,
             duration,
+            articulations,
+            ornament: None,
             beam,
             id,
         }
@@ -87,14 +94,12 @@ impl Chord {
     /// > direction is best for visual clarity.
     ///
     /// [Gardner, p. 69](https://archive.org/details/musicnotationman00read/page/69)
-    fn stem_direction(&self) -> Option<stem::Direction> {
-        const MIDDLE_STAFF_LINE: StaffSpaces = StaffSpaces(2.0);
-
+    fn stem_direction(&self, middle: StaffSpaces) -> Option<stem::Direction> {
         let lowest = self.lowest_note().y;
         let highest = self.highest_note().y;
 
-        let lowest_distance_to_middle = MIDDLE_STAFF_LINE - lowest;
-        let highest_distance_to_middle = highest - MIDDLE_STAFF_LINE;
+        let lowest_distance_to_middle = middle - lowest;
+        let highest_distance_to_middle = highest - middle;
 
         if lowest_distance_to_middle == highest_distance_to_middle {
             None
@@ -164,12 +169,12 @@ impl Chord {
     }
 
     /// Returns the lowest note in the chord.
-    fn lowest_note(&self) -> Note {
+    pub(crate) fn lowest_note(&self) -> Note {
         *self.notes.first().unwrap()
     }
 
     /// Returns the highest note in the chord.
-    fn highest_note(&self) -> Note {
+    pub(crate) fn highest_note(&self) -> Note {
         *self.notes.last().unwrap()
     }
 }
@@ -187,7 +192,7 @@ impl Render for Chord {
         let stem_direction = context
             .beam()
             .map(|beam| beam.stem_direction)
-            .or_else(|| self.stem_direction())
+            .or_else(|| self.stem_direction(context.staff_geometry().middle()))
             .unwrap_or(stem::Direction::Up);
 
         let notes = self.notes(stem_direction);
@@ -205,10 +210,24 @@ impl Render for Chord {
             })
             .collect::<Vec<_>>();
 
-        let mut leger_lines = create_leger_lines(x, notes.first().unwrap().0.y, glyph, metadata)?;
+        let staff_geometry = context.staff_geometry();
+
+        let mut leger_lines = create_leger_lines(
+            x,
+            notes.first().unwrap().0.y,
+            glyph,
+            staff_geometry,
+            metadata,
+        )?;
         elements.append(&mut leger_lines);
 
-        let mut leger_lines = create_leger_lines(x, notes.last().unwrap().0.y, glyph, metadata)?;
+        let mut leger_lines = create_leger_lines(
+            x,
+            notes.last().unwrap().0.y,
+            glyph,
+            staff_geometry,
+            metadata,
+        )?;
         elements.append(&mut leger_lines);
 
         let mut accidentals = notes
@@ -258,10 +277,40 @@ impl Render for Chord {
             }
         };
 
+        let mut articulations = create_articulations(
+            x,
+            start_y,
+            glyph,
+            stem_direction,
+            &self.articulations,
+            staff_geometry,
+            metadata,
+        )?;
+        elements.append(&mut articulations);
+
+        if let Some(ornament) = &self.ornament {
+            let highest_y = highest_point(
+                &elements,
+                self.highest_note().y,
+                stem_direction,
+                context,
+                metadata,
+            );
+            let mut ornament = ornament.render(
+                x + width / 2.0,
+                highest_y,
+                staff_geometry,
+                context,
+                metadata,
+            )?;
+            elements.append(&mut ornament);
+        }
+
         if self.id.is_some() {
             Ok(Output {
                 elements: vec![Element::Group(Group {
                     id: self.id.clone(),
+                    data: vec![],
                     elements,
                 })],
                 width,
@@ -0,0 +1,64 @@
+use smufl::{Glyph, Metadata, StaffSpaces};
+
+use crate::{
+    render::{
+        context::Context, ir::FontWeight, metadata_extensions::MetadataExtensions, Output, Render,
+        TextMetrics,
+    },
+    Result,
+};
+
+/// A chord symbol drawn above the staff, which applies from the note, chord or
+/// rest which follows it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ChordSymbol {
+    /// The text and glyphs of the chord symbol, from left to right.
+    pub parts: Vec<Part>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Part {
+    Text(String),
+    /// A chord symbol glyph, such as an accidental or a quality symbol.
+    Glyph(Glyph),
+}
+
+impl Part {
+    pub fn width(&self, text_metrics: &TextMetrics, metadata: &Metadata) -> Result<StaffSpaces> {
+        match self {
+            Part::Text(text) => Ok(text_metrics.width_of(text, 1.0, FontWeight::Normal)),
+            Part::Glyph(glyph) => metadata.width_of(*glyph),
+        }
+    }
+}
+
+impl ChordSymbol {
+    pub fn width(&self, text_metrics: &TextMetrics, metadata: &Metadata) -> Result<StaffSpaces> {
+        self.parts
+            .iter()
+            .try_fold(StaffSpaces::zero(), |width, part| {
+                Ok(width + part.width(text_metrics, metadata)?)
+            })
+    }
+}
+
+impl Render for ChordSymbol {
+    /// Adds the chord symbol to the line of chord symbols above the staff,
+    /// which is rendered once the whole staff has been rendered.
+    ///
+    /// The width of the output is how far the following note must move to the
+    /// right so that the chord symbol does not run into the previous one.
+    fn render(&self, x: StaffSpaces, context: &mut Context, metadata: &Metadata) -> Result<Output> {
+        let overlap = context.chord_symbols().overlap(x);
+        let width = self.width(&context.text_metrics(), metadata)?;
+
+        context
+            .chord_symbols()
+            .add(self.parts.clone(), x + overlap, width);
+
+        Ok(Output {
+            elements: vec![],
+            width: overlap,
+        })
+    }
+}
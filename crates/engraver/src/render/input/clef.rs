@@ -16,6 +16,21 @@ pub struct Clef {
     pub y: StaffSpaces,
 }
 
+impl Clef {
+    /// Returns the clef at the normal size, such as a clef change drawn again
+    /// at the start of the next system.
+    pub(crate) fn full_size(self) -> Self {
+        let glyph = match self.glyph {
+            Glyph::GClefChange => Glyph::GClef,
+            Glyph::CClefChange => Glyph::CClef,
+            Glyph::FClefChange => Glyph::FClef,
+            glyph => glyph,
+        };
+
+        Self { glyph, ..self }
+    }
+}
+
 impl Render for Clef {
     fn render(
         &self,
@@ -26,7 +41,8 @@ impl Render for Clef {
         Ok(Output {
             elements: vec![Element::Symbol(Symbol {
                 origin: Coord { x, y: self.y },
-                value: self.glyph.codepoint(),
+                value: self.glyph,
+                size: 1.0,
             })],
             width: metadata.width_of(self.glyph)?,
         })
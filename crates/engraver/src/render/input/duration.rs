@@ -3,12 +3,30 @@ use strum_macros::EnumIter;
 
 use crate::render::stem;
 
+/// The number of divisions of a quarter note needed to represent every
+/// [`Duration`] as a whole number of divisions.
+pub const DIVISIONS_PER_QUARTER: u32 = 256;
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct Duration {
     pub value: Value,
     pub dots: Option<Dots>,
 }
 
+impl Duration {
+    /// Returns the length of the duration, in [`DIVISIONS_PER_QUARTER`]
+    /// divisions of a quarter note.
+    pub fn divisions(&self) -> u32 {
+        let divisions = self.value.divisions();
+
+        match self.dots {
+            None => divisions,
+            Some(Dots::Dot) => divisions + divisions / 2,
+            Some(Dots::DoubleDot) => divisions + divisions / 2 + divisions / 4,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, EnumIter, Eq, Ord, PartialEq, PartialOrd)]
 pub enum Value {
     Whole,
@@ -23,6 +41,22 @@ pub enum Value {
 }
 
 impl Value {
+    /// Returns the length of the value, in [`DIVISIONS_PER_QUARTER`]
+    /// divisions of a quarter note.
+    pub fn divisions(&self) -> u32 {
+        match self {
+            Self::Whole => DIVISIONS_PER_QUARTER * 4,
+            Self::Half => DIVISIONS_PER_QUARTER * 2,
+            Self::Quarter => DIVISIONS_PER_QUARTER,
+            Self::Eighth => DIVISIONS_PER_QUARTER / 2,
+            Self::Sixteenth => DIVISIONS_PER_QUARTER / 4,
+            Self::ThirtySecond => DIVISIONS_PER_QUARTER / 8,
+            Self::SixtyFourth => DIVISIONS_PER_QUARTER / 16,
+            Self::OneHundredTwentyEighth => DIVISIONS_PER_QUARTER / 32,
+            Self::TwoHundredFiftySixth => DIVISIONS_PER_QUARTER / 64,
+        }
+    }
+
     pub fn notehead_glyph(&self) -> Glyph {
         match self {
             Self::Whole => Glyph::NoteheadWhole,
@@ -0,0 +1,89 @@
+use smufl::{Glyph, Metadata, StaffSpaces};
+use strum_macros::EnumIter;
+
+use crate::{
+    render::{context::Context, Output, Render},
+    Result,
+};
+
+/// A dynamic marking, which applies from the note or chord which follows it.
+#[derive(Clone, Copy, Debug, EnumIter, Eq, PartialEq)]
+pub enum Dynamic {
+    Pppppp,
+    Ppppp,
+    Pppp,
+    Ppp,
+    Pp,
+    P,
+    Mp,
+    Mf,
+    F,
+    Ff,
+    Fff,
+    Ffff,
+    Fffff,
+    Ffffff,
+    Fp,
+    Pf,
+    Sf,
+    Sfp,
+    Sfpp,
+    Sfz,
+    Sfzp,
+    Sffz,
+    Fz,
+    Rf,
+    Rfz,
+    N,
+}
+
+impl Dynamic {
+    pub fn glyph(&self) -> Glyph {
+        match self {
+            Self::Pppppp => Glyph::DynamicPppppp,
+            Self::Ppppp => Glyph::DynamicPpppp,
+            Self::Pppp => Glyph::DynamicPppp,
+            Self::Ppp => Glyph::DynamicPpp,
+            Self::Pp => Glyph::DynamicPp,
+            Self::P => Glyph::DynamicPiano,
+            Self::Mp => Glyph::DynamicMp,
+            Self::Mf => Glyph::DynamicMf,
+            Self::F => Glyph::DynamicForte,
+            Self::Ff => Glyph::DynamicFf,
+            Self::Fff => Glyph::DynamicFff,
+            Self::Ffff => Glyph::DynamicFfff,
+            Self::Fffff => Glyph::DynamicFffff,
+            Self::Ffffff => Glyph::DynamicFfffff,
+            Self::Fp => Glyph::DynamicFortePiano,
+            Self::Pf => Glyph::DynamicPf,
+            Self::Sf => Glyph::DynamicSforzando1,
+            Self::Sfp => Glyph::DynamicSforzandoPiano,
+            Self::Sfpp => Glyph::DynamicSforzandoPianissimo,
+            Self::Sfz => Glyph::DynamicSforzato,
+            Self::Sfzp => Glyph::DynamicSforzatoPiano,
+            Self::Sffz => Glyph::DynamicSforzatoFf,
+            Self::Fz => Glyph::DynamicForzando,
+            Self::Rf => Glyph::DynamicRinforzando1,
+            Self::Rfz => Glyph::DynamicRinforzando2,
+            Self::N => Glyph::DynamicNiente,
+        }
+    }
+}
+
+impl Render for Dynamic {
+    /// Adds the dynamic to the line of dynamics below the staff, which is
+    /// rendered once the whole staff has been rendered.
+    fn render(
+        &self,
+        x: StaffSpaces,
+        context: &mut Context,
+        _metadata: &Metadata,
+    ) -> Result<Output> {
+        context.dynamics().add_dynamic(self.glyph(), x);
+
+        Ok(Output {
+            elements: vec![],
+            width: StaffSpaces::zero(),
+        })
+    }
+}
@@ -0,0 +1,99 @@
+use smufl::{Metadata, StaffSpaces};
+
+use crate::render::{
+    engraving_defaults_extensions::EngravingDefaultsExtensions,
+    ir::{Coord, Element, FontStyle, FontWeight, Line, Linecap, Size, Text, TextAnchor},
+};
+
+/// The length of the hooks at the ends of the bracket.
+const HOOK_LENGTH: StaffSpaces = StaffSpaces(1.5);
+
+/// The offset of the numbering from the top left corner of the bracket.
+const TEXT_OFFSET: Size<StaffSpaces> = Size {
+    width: StaffSpaces(0.5),
+    height: StaffSpaces(-1.25),
+};
+
+/// A first, second or later ending (volta) of a repeated section, which is
+/// drawn as a numbered bracket above the consecutive measures that have it.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Ending {
+    /// The times through the repeated section on which the ending is played,
+    /// such as `[1]` for a first ending or `[1, 2]` for an ending played the
+    /// first two times.
+    pub numbers: Vec<u8>,
+}
+
+impl Ending {
+    /// Returns the numbering of the ending, such as "1." or "1, 2.".
+    pub fn label(&self) -> String {
+        let numbers: Vec<_> = self.numbers.iter().map(u8::to_string).collect();
+
+        format!("{}.", numbers.join(", "))
+    }
+
+    /// Returns the bracket for the ending from `start` to `end` at height `y`,
+    /// which has a hook at its end if it is `closed`.
+    ///
+    /// A bracket `continued` from the previous system has neither a hook at its
+    /// start nor the numbering.
+    pub(crate) fn render_bracket(
+        &self,
+        start: StaffSpaces,
+        end: StaffSpaces,
+        y: StaffSpaces,
+        continued: bool,
+        closed: bool,
+        metadata: &Metadata,
+    ) -> Vec<Element<StaffSpaces>> {
+        let thickness = metadata.engraving_defaults.repeat_ending_line_thickness();
+        let line = |from, to| {
+            Element::Line(Line {
+                from,
+                to,
+                thickness,
+                cap: Linecap::Butt,
+            })
+        };
+
+        let top_left = Coord { x: start, y };
+        let top_right = Coord { x: end, y };
+
+        let mut elements = vec![];
+
+        if !continued {
+            elements.push(line(
+                Coord {
+                    x: start,
+                    y: y - HOOK_LENGTH,
+                },
+                top_left,
+            ));
+        }
+
+        elements.push(line(top_left, top_right));
+
+        if closed {
+            elements.push(line(
+                top_right,
+                Coord {
+                    x: end,
+                    y: y - HOOK_LENGTH,
+                },
+            ));
+        }
+
+        if !continued {
+            elements.push(Element::Text(Text {
+                origin: top_left + TEXT_OFFSET,
+                value: self.label(),
+                anchor: TextAnchor::Start,
+                style: FontStyle::Normal,
+                weight: FontWeight::Normal,
+                size: 1.0,
+            }));
+        }
+
+        elements
+    }
+}
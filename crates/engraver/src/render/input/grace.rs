@@ -0,0 +1,227 @@
+use smufl::{Glyph, Metadata, StaffSpaces};
+
+use super::{
+    duration,
+    note::{create_accidental, create_flag, create_leger_lines, create_notehead},
+    Note,
+};
+use crate::{
+    render::{
+        context::Context,
+        engraving_defaults_extensions::EngravingDefaultsExtensions,
+        ir::{Convert, Coord, Element, Polygon, Scale, Size, Symbol},
+        metadata_extensions::MetadataExtensions,
+        stem::{self, Stem},
+        Output, Render,
+    },
+    Result,
+};
+
+/// The size of grace notes relative to normal notes.
+pub const SIZE: f64 = 0.6;
+
+/// The space between the noteheads of a group of grace notes.
+const SPACE_BETWEEN_NOTES: StaffSpaces = StaffSpaces(0.3);
+
+/// The space between the last grace note and the main note.
+const SPACE_BEFORE_MAIN_NOTE: StaffSpaces = StaffSpaces(0.5);
+
+/// The distance from the end of the stem to the slash of an acciaccatura,
+/// before the grace note is scaled.
+const SLASH_TO_STEM_END: StaffSpaces = StaffSpaces(1.5);
+
+/// A group of grace notes before a main note.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Grace {
+    pub kind: Kind,
+    pub notes: Vec<Note>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Kind {
+    /// A short grace note played before the beat, whose stem is slashed.
+    Acciaccatura,
+    /// A grace note which takes its written value from the main note, whose
+    /// stem is not slashed.
+    Appoggiatura,
+}
+
+impl Grace {
+    /// Returns the width of the group, including the space before the main
+    /// note.
+    pub fn width(&self, metadata: &Metadata) -> Result<StaffSpaces> {
+        Ok(self.layout(metadata)?.1)
+    }
+
+    /// Returns the offsets of the noteheads from the start of the group, and
+    /// the width of the group.
+    fn layout(&self, metadata: &Metadata) -> Result<(Vec<StaffSpaces>, StaffSpaces)> {
+        let mut offsets = Vec::with_capacity(self.notes.len());
+        let mut width = StaffSpaces::zero();
+
+        for (index, note) in self.notes.iter().enumerate() {
+            if index > 0 {
+                width += SPACE_BETWEEN_NOTES;
+            }
+
+            if let Some(accidental) = note.accidental {
+                width += (super::note::DEFAULT_ACCIDENTAL_SPACING
+                    + metadata.width_of(accidental.glyph())?)
+                    * SIZE;
+            }
+
+            offsets.push(width);
+            width += metadata.width_of(note.duration.value.notehead_glyph())? * SIZE;
+        }
+
+        if let [note] = self.notes.as_slice() {
+            if let Some(flag_glyph) = note.duration.value.flag_glyph(stem::Direction::Up) {
+                width += metadata.width_of(flag_glyph)? * SIZE;
+            }
+        }
+
+        Ok((offsets, width + SPACE_BEFORE_MAIN_NOTE))
+    }
+
+    fn is_beamed(&self) -> bool {
+        self.notes.len() > 1
+    }
+}
+
+/// Returns the number of beams of a note value, or zero for notes which are
+/// not beamed.
+fn num_beams(value: duration::Value) -> usize {
+    match value {
+        duration::Value::Whole | duration::Value::Half | duration::Value::Quarter => 0,
+        duration::Value::Eighth => 1,
+        duration::Value::Sixteenth => 2,
+        duration::Value::ThirtySecond => 3,
+        duration::Value::SixtyFourth => 4,
+        duration::Value::OneHundredTwentyEighth => 5,
+        duration::Value::TwoHundredFiftySixth => 6,
+    }
+}
+
+impl Render for Grace {
+    /// Renders the grace notes to the left of `x`, which is where the main note
+    /// is drawn, so that they take no space of their own.
+    ///
+    /// Grace notes always have stems up. A group of several grace notes is
+    /// joined by horizontal beams above its highest note, and an acciaccatura
+    /// has a slash through the stem of its first note.
+    fn render(&self, x: StaffSpaces, context: &mut Context, metadata: &Metadata) -> Result<Output> {
+        let (offsets, width) = self.layout(metadata)?;
+        let start = x - width;
+        let staff_geometry = context.staff_geometry();
+
+        let beam_y = self
+            .notes
+            .iter()
+            .map(|note| note.y)
+            .fold(StaffSpaces(f64::MIN), |highest, y| highest.max(y))
+            + stem::DEFAULT_LENGTH * SIZE;
+
+        let mut elements = vec![];
+        let mut stem_edges = vec![];
+
+        for (index, (note, offset)) in self.notes.iter().zip(offsets).enumerate() {
+            let x = start + offset;
+            let glyph = note.duration.value.notehead_glyph();
+            let origin = Coord { x, y: note.y };
+
+            // Leger lines stay on the staff positions of their lines.
+            let scale_x = Scale {
+                origin,
+                size: SIZE,
+                scales_y: false,
+            };
+            elements.extend(
+                create_leger_lines(x, note.y, glyph, staff_geometry, metadata)?
+                    .into_iter()
+                    .map(|element| element.convert(&scale_x)),
+            );
+
+            let mut parts = vec![create_notehead(x, note.y, glyph)];
+
+            if let Some(accidental) = note.accidental {
+                parts.push(create_accidental(x, note.y, accidental.glyph(), metadata)?);
+            }
+
+            if note.duration.value != duration::Value::Whole {
+                let length = if self.is_beamed() {
+                    (beam_y - note.y) / SIZE
+                } else {
+                    stem::DEFAULT_LENGTH
+                };
+                let stem = Stem::new(glyph, x, note.y, stem::Direction::Up, Some(length));
+
+                if !self.is_beamed() {
+                    if let Some(flag_glyph) = note.duration.value.flag_glyph(stem::Direction::Up) {
+                        parts.push(create_flag(
+                            x,
+                            stem.end(),
+                            glyph,
+                            flag_glyph,
+                            stem::Direction::Up,
+                            metadata,
+                        )?);
+                    }
+                }
+
+                if index == 0 && self.kind == Kind::Acciaccatura {
+                    let slash_glyph = Glyph::GraceNoteSlashStemUp;
+
+                    parts.push(Element::Symbol(Symbol {
+                        origin: Coord {
+                            x: stem.left(metadata)? - metadata.width_of(slash_glyph)? / 2.0,
+                            y: stem.end() - SLASH_TO_STEM_END,
+                        },
+                        value: slash_glyph,
+                        size: 1.0,
+                    }));
+                }
+
+                stem_edges.push((
+                    scale_x.convert_x(stem.left(metadata)?),
+                    scale_x.convert_x(stem.right(metadata)?),
+                ));
+                parts.push(stem.render(metadata)?);
+            }
+
+            let scale = Scale {
+                origin,
+                size: SIZE,
+                scales_y: true,
+            };
+            elements.extend(parts.into_iter().map(|element| element.convert(&scale)));
+        }
+
+        if self.is_beamed() {
+            if let (Some((left, _)), Some((_, right))) = (stem_edges.first(), stem_edges.last()) {
+                let value = self.notes[0].duration.value;
+                let thickness = metadata.engraving_defaults.beam_thickness() * SIZE;
+                let spacing = metadata.engraving_defaults.beam_spacing() * SIZE;
+
+                elements.extend((0..num_beams(value)).map(|index| {
+                    let top = beam_y - (thickness + spacing) * index as f64;
+
+                    Element::Polygon(Polygon::rect(
+                        Coord {
+                            x: *left,
+                            y: top - thickness,
+                        },
+                        Size {
+                            width: *right - *left,
+                            height: thickness,
+                        },
+                    ))
+                }));
+            }
+        }
+
+        Ok(Output {
+            elements,
+            width: StaffSpaces::zero(),
+        })
+    }
+}
@@ -0,0 +1,43 @@
+use smufl::{Metadata, StaffSpaces};
+
+use crate::{
+    render::{context::Context, Output, Render},
+    Result,
+};
+
+/// The start or end of a hairpin.
+///
+/// A hairpin lasts from its start until its end, the next dynamic or the start
+/// of another hairpin, and may continue across barlines.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Hairpin {
+    Start(Kind),
+    End,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Kind {
+    Crescendo,
+    Diminuendo,
+}
+
+impl Render for Hairpin {
+    /// Adds the start or end of the hairpin to the line of dynamics below the
+    /// staff, which is rendered once the whole staff has been rendered.
+    fn render(
+        &self,
+        x: StaffSpaces,
+        context: &mut Context,
+        _metadata: &Metadata,
+    ) -> Result<Output> {
+        match self {
+            Self::Start(kind) => context.dynamics().start_hairpin(*kind, x),
+            Self::End => context.dynamics().end_hairpin(x)?,
+        }
+
+        Ok(Output {
+            elements: vec![],
+            width: StaffSpaces::zero(),
+        })
+    }
+}
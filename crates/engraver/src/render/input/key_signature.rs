@@ -12,10 +12,17 @@ use crate::{
 
 const SPACE_AFTER_SYMBOL: StaffSpaces = StaffSpaces(0.1);
 
+/// The space between the naturals which cancel the previous key signature and
+/// the sharps or flats of the new key signature.
+const SPACE_AFTER_NATURALS: StaffSpaces = StaffSpaces(0.5);
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct KeySignature {
     pub kind: Kind,
     pub pitches: Vec<StaffSpaces>,
+    /// The positions of the naturals which cancel the previous key signature,
+    /// which are drawn before the sharps or flats.
+    pub naturals: Vec<StaffSpaces>,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -39,6 +46,35 @@ impl Kind {
     }
 }
 
+/// Returns the symbols of a row of accidentals at the given positions.
+fn render_symbols(
+    glyph: Glyph,
+    positions: &[StaffSpaces],
+    x: StaffSpaces,
+    metadata: &Metadata,
+) -> Result<Output> {
+    let symbol_width = metadata.width_of(glyph)? + SPACE_AFTER_SYMBOL;
+
+    let elements = positions
+        .iter()
+        .enumerate()
+        .map(|(index, pitch)| {
+            Element::Symbol(Symbol {
+                origin: Coord {
+                    x: x + symbol_width * (index as f64),
+                    y: *pitch,
+                },
+                value: glyph,
+                size: 1.0,
+            })
+        })
+        .collect();
+
+    let width = symbol_width * (positions.len() as f64);
+
+    Ok(Output { elements, width })
+}
+
 impl Render for KeySignature {
     fn render(
         &self,
@@ -46,27 +82,17 @@ impl Render for KeySignature {
         _context: &mut Context,
         metadata: &Metadata,
     ) -> Result<Output> {
-        let glyph = self.kind.glyph();
-        let codepoint = glyph.codepoint();
-        let symbol_width = metadata.width_of(glyph)? + SPACE_AFTER_SYMBOL;
+        let mut output = render_symbols(Glyph::AccidentalNatural, &self.naturals, x, metadata)?;
 
-        let elements = self
-            .pitches
-            .iter()
-            .enumerate()
-            .map(|(index, pitch)| {
-                Element::Symbol(Symbol {
-                    origin: Coord {
-                        x: x + symbol_width * (index as f64),
-                        y: *pitch,
-                    },
-                    value: codepoint,
-                })
-            })
-            .collect();
+        if !self.naturals.is_empty() && !self.pitches.is_empty() {
+            output.width += SPACE_AFTER_NATURALS;
+        }
 
-        let width = symbol_width * (self.pitches.len() as f64);
+        let mut accidentals =
+            render_symbols(self.kind.glyph(), &self.pitches, x + output.width, metadata)?;
+        output.elements.append(&mut accidentals.elements);
+        output.width += accidentals.width;
 
-        Ok(Output { elements, width })
+        Ok(output)
     }
 }
@@ -0,0 +1,25 @@
+/// The lyric of one verse for a note or chord.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Lyric {
+    Syllable(Syllable),
+    /// No syllable, as the note or chord continues a melisma of an earlier
+    /// syllable.
+    Melisma,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Syllable {
+    pub text: String,
+    /// What joins the syllable to the next one of the verse.
+    pub connector: Option<Connector>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Connector {
+    /// The word continues with the next syllable, which is shown by a hyphen
+    /// between them.
+    Hyphen,
+    /// The syllable ends a word and is held over the following melisma, which
+    /// is shown by a line after it.
+    Extender,
+}
@@ -1,19 +1,50 @@
-use smufl::StaffSpaces;
+use smufl::{Glyph, StaffSpaces};
 
-use super::{duration, Barline, Beam, Chord, Duration, Note, Rest};
+use super::{
+    duration, Annotation, Barline, Beam, Chord, ChordSymbol, Clef, Duration, Dynamic, Ending,
+    FullMeasureRest, Grace, Hairpin, KeySignature, Lyric, MultiMeasureRest, Navigation, Note,
+    OctaveLine, Pedal, Rest, TimeSignature,
+};
 use crate::{
-    render::{context::Context, stem, Render, Renderer},
+    render::{
+        context::Context, input::ornament, ir::Coord, math,
+        metadata_extensions::MetadataExtensions, stem, Render, Renderer,
+    },
     Result,
 };
 
 const BEGINNING_OF_MEASURE_SPACE: StaffSpaces = StaffSpaces(2.0);
 const END_OF_MEASURE_SPACE: StaffSpaces = StaffSpaces(2.0);
 const BASE_SPACE: StaffSpaces = StaffSpaces(8.0);
+const SPACE_AFTER_CLEF_CHANGE: StaffSpaces = StaffSpaces(1.0);
+const SPACE_BEFORE_SIGNATURE_CHANGE: StaffSpaces = StaffSpaces(1.0);
+
+/// The minimum space between grace notes and the element before them.
+const SPACE_BEFORE_GRACE_NOTES: StaffSpaces = StaffSpaces(0.5);
+
+/// The minimum space between the last syllable of lyrics in a measure and the
+/// barline at its end.
+const SPACE_BETWEEN_LYRICS_AND_BARLINE: StaffSpaces = StaffSpaces(0.5);
 
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct Measure {
+    /// The barline at the start of the measure, such as a start repeat, which
+    /// replaces a thin barline at the end of the previous measure.
+    pub start_barline: Option<Barline>,
+    /// A key signature change at the start of the measure.
+    pub key_signature: Option<KeySignature>,
+    /// A time signature change at the start of the measure.
+    pub time_signature: Option<TimeSignature>,
     pub elements: Vec<Element>,
     pub barline: Barline,
+    pub ending: Option<Ending>,
+    pub navigation: Vec<Navigation>,
+    /// The lyrics of each verse, whose lyrics belong in turn to the notes and
+    /// chords of the measure.
+    pub lyrics: Vec<Vec<Lyric>>,
+    /// Whether the measure ends its system, so that the next measure starts a
+    /// new system below it.
+    pub system_break: bool,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -21,19 +52,52 @@ pub enum Element {
     Note(Note),
     Chord(Chord),
     Rest(Rest),
+    Dynamic(Dynamic),
+    Hairpin(Hairpin),
+    Clef(Clef),
+    /// Grace notes before the next note or chord, which take no time.
+    Grace(Grace),
+    ChordSymbol(ChordSymbol),
+    Annotation(Annotation),
+    OctaveLine(OctaveLine),
+    Pedal(Pedal),
+    /// A rest which lasts for the whole measure, and is centered between its
+    /// barlines.
+    FullMeasureRest(FullMeasureRest),
+    /// A rest which lasts for the whole of several measures, which is the only
+    /// element of its measure.
+    MultiMeasureRest(MultiMeasureRest),
 }
 
 impl Element {
-    fn duration(&self) -> Duration {
+    /// Returns the duration of the element, or `None` for elements which are
+    /// attached to the next note, chord or rest and take no time.
+    fn duration(&self) -> Option<Duration> {
         match self {
-            Element::Note(note) => note.duration,
-            Element::Chord(chord) => chord.duration,
-            Element::Rest(rest) => rest.duration,
+            Element::Note(note) => Some(note.duration),
+            Element::Chord(chord) => Some(chord.duration),
+            Element::Rest(rest) => Some(rest.duration),
+            Element::Dynamic(_)
+            | Element::Hairpin(_)
+            | Element::Clef(_)
+            | Element::Grace(_)
+            | Element::ChordSymbol(_)
+            | Element::Annotation(_)
+            | Element::OctaveLine(_)
+            | Element::Pedal(_)
+            | Element::FullMeasureRest(_)
+            | Element::MultiMeasureRest(_) => None,
         }
     }
 
     fn spacing(&self) -> StaffSpaces {
-        let duration = self.duration();
+        if let Element::Clef(_) = self {
+            return SPACE_AFTER_CLEF_CHANGE;
+        }
+
+        let Some(duration) = self.duration() else {
+            return StaffSpaces::zero();
+        };
 
         let value_multiplier = match duration.value {
             super::duration::Value::Whole => 1.0,
@@ -56,11 +120,61 @@ impl Element {
         BASE_SPACE * value_multiplier * dots_multiplier
     }
 
+    /// Returns the heights of the lowest and highest noteheads of notes and
+    /// chords.
+    fn extent(&self) -> Option<(StaffSpaces, StaffSpaces)> {
+        match self {
+            Element::Note(note) => Some((note.y, note.y)),
+            Element::Chord(chord) => Some((chord.lowest_note().y, chord.highest_note().y)),
+            Element::Rest(_)
+            | Element::Dynamic(_)
+            | Element::Hairpin(_)
+            | Element::Clef(_)
+            | Element::Grace(_)
+            | Element::ChordSymbol(_)
+            | Element::Annotation(_)
+            | Element::OctaveLine(_)
+            | Element::Pedal(_)
+            | Element::FullMeasureRest(_)
+            | Element::MultiMeasureRest(_) => None,
+        }
+    }
+
+    /// Returns the notehead glyph of notes and chords, which are the elements
+    /// lyrics are sung on.
+    fn notehead(&self) -> Option<Glyph> {
+        match self {
+            Element::Note(note) => Some(note.duration.value.notehead_glyph()),
+            Element::Chord(chord) => Some(chord.duration.value.notehead_glyph()),
+            Element::Rest(_)
+            | Element::Dynamic(_)
+            | Element::Hairpin(_)
+            | Element::Clef(_)
+            | Element::Grace(_)
+            | Element::ChordSymbol(_)
+            | Element::Annotation(_)
+            | Element::OctaveLine(_)
+            | Element::Pedal(_)
+            | Element::FullMeasureRest(_)
+            | Element::MultiMeasureRest(_) => None,
+        }
+    }
+
     fn beam(&self) -> Option<Beam> {
         match self {
             Element::Note(note) => note.beam,
             Element::Chord(chord) => chord.beam,
-            Element::Rest(_) => None,
+            Element::Rest(_)
+            | Element::Dynamic(_)
+            | Element::Hairpin(_)
+            | Element::Clef(_)
+            | Element::Grace(_)
+            | Element::ChordSymbol(_)
+            | Element::Annotation(_)
+            | Element::OctaveLine(_)
+            | Element::Pedal(_)
+            | Element::FullMeasureRest(_)
+            | Element::MultiMeasureRest(_) => None,
         }
     }
 }
@@ -76,21 +190,165 @@ impl Render for Element {
             Element::Note(note) => note.render(x, context, metadata),
             Element::Chord(chord) => chord.render(x, context, metadata),
             Element::Rest(rest) => rest.render(x, context, metadata),
+            Element::Dynamic(dynamic) => dynamic.render(x, context, metadata),
+            Element::Hairpin(hairpin) => hairpin.render(x, context, metadata),
+            Element::Clef(clef) => clef.render(x, context, metadata),
+            Element::Grace(grace) => grace.render(x, context, metadata),
+            Element::ChordSymbol(chord_symbol) => chord_symbol.render(x, context, metadata),
+            Element::Annotation(annotation) => annotation.render(x, context, metadata),
+            Element::OctaveLine(octave_line) => octave_line.render(x, context, metadata),
+            Element::Pedal(pedal) => pedal.render(x, context, metadata),
+            Element::FullMeasureRest(rest) => rest.render(x, context, metadata),
+            Element::MultiMeasureRest(multi_measure_rest) => {
+                multi_measure_rest.render(x, context, metadata)
+            }
         }
     }
 }
 
 impl Measure {
-    pub fn render(&self, renderer: &mut Renderer) -> Result<()> {
+    /// Returns the length of the notes, chords and rests of the measure, in
+    /// [`DIVISIONS_PER_QUARTER`](duration::DIVISIONS_PER_QUARTER) divisions of
+    /// a quarter note, not counting any full-measure rest.
+    pub fn divisions(&self) -> u32 {
+        self.elements
+            .iter()
+            .filter_map(Element::duration)
+            .map(|duration| duration.divisions())
+            .sum()
+    }
+
+    /// Returns whether the measure has a full-measure rest.
+    pub fn has_full_measure_rest(&self) -> bool {
+        self.elements
+            .iter()
+            .any(|element| matches!(element, Element::FullMeasureRest(_)))
+    }
+
+    /// Returns whether the measure only has rests, and nothing else which would
+    /// keep it from being part of a multi-measure rest, such as lyrics, an
+    /// ending or a navigation marker.
+    pub fn is_empty(&self) -> bool {
+        self.elements
+            .iter()
+            .all(|element| matches!(element, Element::Rest(_) | Element::FullMeasureRest(_)))
+            && self.lyrics.iter().all(Vec::is_empty)
+            && self.ending.is_none()
+            && self.navigation.is_empty()
+    }
+
+    /// Renders the measure between the `previous` and `next` measures.
+    ///
+    /// Clef changes at the start of a measure are drawn before the barline of
+    /// the previous measure, so a clef change at the start of a system is drawn
+    /// at the end of the previous system as a courtesy clef. The barline at the
+    /// end of the measure is left out if it is replaced by the start barline of
+    /// the next measure, unless the measure ends its system.
+    ///
+    /// Key and time signature changes are drawn after the barline at the start
+    /// of the measure, or in the header of the system which the measure starts,
+    /// in which case they are also drawn after the last barline of the previous
+    /// system as courtesy signatures.
+    ///
+    /// Grace notes are drawn in the space before their main note, which is only
+    /// moved to the right when that space is too narrow for them.
+    ///
+    /// A full-measure rest is centered in the space between the start of the
+    /// measure and its barline, before any courtesy clefs for the next measure,
+    /// once the rest of the measure is laid out.
+    ///
+    /// Notes and chords are moved to the right, along with the elements
+    /// attached to them, when their syllables would otherwise run into the
+    /// previous syllables of the same verses, and the barline is moved to the
+    /// right of the last syllable.
+    pub fn render(
+        &self,
+        renderer: &mut Renderer,
+        previous: Option<&Measure>,
+        next: Option<&Measure>,
+    ) -> Result<()> {
+        if let Some(start_barline) = &self.start_barline {
+            renderer.render(start_barline)?;
+        }
+
+        let starts_system = previous.is_some_and(|previous| previous.system_break);
+
+        if let Some(key_signature) = self.key_signature.as_ref().filter(|_| !starts_system) {
+            renderer.advance(SPACE_BEFORE_SIGNATURE_CHANGE);
+            renderer.render(key_signature)?;
+        }
+
+        if let Some(time_signature) = self.time_signature.as_ref().filter(|_| !starts_system) {
+            renderer.advance(SPACE_BEFORE_SIGNATURE_CHANGE);
+            renderer.render(time_signature)?;
+        }
+
+        let start = renderer.position();
+        let mut free_from = start;
         renderer.advance(BEGINNING_OF_MEASURE_SPACE);
 
-        for element in &self.elements {
+        let num_skipped = match previous {
+            Some(_) => self.leading_clefs().count(),
+            None => 0,
+        };
+
+        let elements: Vec<_> = self.elements.iter().skip(num_skipped).collect();
+        let mut num_sung = 0;
+        let mut full_measure_rest = None;
+
+        for (index, element) in elements.iter().copied().enumerate() {
+            let is_attached_to_previous = index.checked_sub(1).is_some_and(|previous| {
+                elements[previous].duration().is_none()
+                    && !matches!(elements[previous], Element::Clef(_))
+            });
+
+            if !is_attached_to_previous {
+                if let Some(sung) = elements[index..]
+                    .iter()
+                    .find(|element| element.duration().is_some())
+                {
+                    let overlap = self.lyrics_overlap(renderer, num_sung, sung)?;
+                    renderer.advance(overlap);
+                }
+            }
+
+            if let Element::Grace(grace) = element {
+                let start = renderer.position() - grace.width(renderer.metadata())?;
+                let overlap = free_from + SPACE_BEFORE_GRACE_NOTES - start;
+
+                if overlap > StaffSpaces::zero() {
+                    renderer.advance(overlap);
+                }
+            }
+
             if element.beam() == Some(Beam::Begin) {
-                renderer.context().begin_beam(stem::Direction::Up)?;
+                let middle = renderer.context().staff_geometry().middle();
+                let stem_direction = beam_stem_direction(&elements[index..], middle);
+                renderer.context().begin_beam(stem_direction)?;
             }
 
+            if element.duration().is_some() {
+                end_trill_extension(renderer)?;
+            }
+
+            let x = renderer.position();
+            let num_elements = renderer.num_elements();
             renderer.render(element)?;
 
+            if let Element::FullMeasureRest(rest) = element {
+                let glyph_width = renderer.metadata().width_of(rest.glyph())?;
+                full_measure_rest = Some((num_elements..renderer.num_elements(), x, glyph_width));
+            }
+
+            if let Some(notehead) = element.notehead() {
+                self.add_lyrics(renderer, num_sung, x, notehead)?;
+                num_sung += 1;
+            }
+
+            if element.duration().is_some() || matches!(element, Element::Clef(_)) {
+                free_from = renderer.position();
+            }
+
             if element.beam() == Some(Beam::End) {
                 let beam = renderer.context().end_beam()?;
                 renderer.render(&beam)?;
@@ -99,10 +357,169 @@ impl Measure {
             renderer.advance(element.spacing());
         }
 
+        if let Some(right) = renderer.context().lyrics().right() {
+            let overlap = right + SPACE_BETWEEN_LYRICS_AND_BARLINE
+                - (renderer.position() + END_OF_MEASURE_SPACE);
+
+            if overlap > StaffSpaces::zero() {
+                renderer.advance(overlap);
+            }
+        }
+
         renderer.advance(END_OF_MEASURE_SPACE);
+        end_trill_extension(renderer)?;
+
+        if let Some((range, x, glyph_width)) = full_measure_rest {
+            let middle = (start + renderer.position()) / 2.0;
+            let offset = Coord {
+                x: middle - glyph_width / 2.0 - x,
+                y: StaffSpaces::zero(),
+            };
+            renderer.translate_elements(range, offset);
+        }
 
-        renderer.render(&self.barline)?;
+        if let Some(next) = next {
+            for clef in next.leading_clefs() {
+                renderer.render(clef)?;
+                renderer.advance(clef.spacing());
+            }
+        }
+
+        let is_replaced = self.barline == Barline::Thin
+            && !self.system_break
+            && next.is_some_and(|next| next.start_barline.is_some());
+
+        if !is_replaced {
+            renderer.render(&self.barline)?;
+        }
+
+        if let Some(next) = next.filter(|_| self.system_break) {
+            if let Some(key_signature) = &next.key_signature {
+                renderer.advance(SPACE_BEFORE_SIGNATURE_CHANGE);
+                renderer.render(key_signature)?;
+            }
+
+            if let Some(time_signature) = &next.time_signature {
+                renderer.advance(SPACE_BEFORE_SIGNATURE_CHANGE);
+                renderer.render(time_signature)?;
+            }
+        }
 
         Ok(())
     }
+
+    /// Returns the lyrics of each verse for the note or chord with the index
+    /// `num_sung` among the notes and chords of the measure.
+    fn lyrics_of(&self, num_sung: usize) -> impl Iterator<Item = (usize, &Lyric)> {
+        self.lyrics
+            .iter()
+            .enumerate()
+            .filter_map(move |(verse, lyrics)| Some((verse, lyrics.get(num_sung)?)))
+    }
+
+    /// Returns how far the element must move to the right so that its
+    /// syllables do not run into the previous syllables of their verses.
+    fn lyrics_overlap(
+        &self,
+        renderer: &mut Renderer,
+        num_sung: usize,
+        element: &Element,
+    ) -> Result<StaffSpaces> {
+        let Some(notehead) = element.notehead() else {
+            return Ok(StaffSpaces::zero());
+        };
+
+        let metadata = renderer.metadata();
+        let text_metrics = renderer.context().text_metrics();
+        let x = renderer.position();
+
+        self.lyrics_of(num_sung)
+            .filter_map(|(verse, lyric)| match lyric {
+                Lyric::Syllable(syllable) => Some((verse, syllable)),
+                Lyric::Melisma => None,
+            })
+            .try_fold(StaffSpaces::zero(), |overlap, (verse, syllable)| {
+                let verse_overlap = renderer.context().lyrics().overlap(
+                    verse,
+                    syllable,
+                    x,
+                    notehead,
+                    &text_metrics,
+                    metadata,
+                )?;
+
+                Ok(math::max(overlap, verse_overlap))
+            })
+    }
+
+    /// Adds the lyrics of the note or chord at `x` to the verses below the
+    /// staff.
+    fn add_lyrics(
+        &self,
+        renderer: &mut Renderer,
+        num_sung: usize,
+        x: StaffSpaces,
+        notehead: Glyph,
+    ) -> Result<()> {
+        let metadata = renderer.metadata();
+        let text_metrics = renderer.context().text_metrics();
+
+        for (verse, lyric) in self.lyrics_of(num_sung) {
+            let lyrics = renderer.context().lyrics();
+
+            match lyric {
+                Lyric::Syllable(syllable) => {
+                    lyrics.add_syllable(verse, syllable, x, notehead, &text_metrics, metadata)?
+                }
+                Lyric::Melisma => lyrics.add_melisma(verse, x, notehead, metadata)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the clef changes before the first note, chord or rest.
+    pub(crate) fn leading_clefs(&self) -> impl Iterator<Item = &Element> {
+        self.elements
+            .iter()
+            .take_while(|element| matches!(element, Element::Clef(_)))
+    }
+}
+
+/// Returns the stem direction of the beam which begins at the first of the
+/// elements and ends at the first element which ends a beam.
+///
+/// The stems point away from the notehead which is furthest from the middle
+/// line, and down when the furthest noteheads above and below it are as far
+/// from it, as for a single note on the middle line.
+fn beam_stem_direction(elements: &[&Element], middle: StaffSpaces) -> stem::Direction {
+    let end = elements
+        .iter()
+        .position(|element| element.beam() == Some(Beam::End))
+        .map_or(elements.len(), |index| index + 1);
+
+    let (lowest, highest) = elements[..end]
+        .iter()
+        .filter_map(|element| element.extent())
+        .fold((middle, middle), |(lowest, highest), (low, high)| {
+            (math::min(lowest, low), math::max(highest, high))
+        });
+
+    if highest - middle >= middle - lowest {
+        stem::Direction::Down
+    } else {
+        stem::Direction::Up
+    }
+}
+
+/// Renders the extension line of an extended trill in progress, if any, up to
+/// the current position.
+fn end_trill_extension(renderer: &mut Renderer) -> Result<()> {
+    if let Some(start) = renderer.context().end_trill_extension() {
+        let elements =
+            ornament::render_trill_extension(start, renderer.position(), renderer.metadata())?;
+        renderer.add_elements(elements);
+    }
+
+    Ok(())
 }
@@ -0,0 +1,92 @@
+use std::ops::RangeInclusive;
+
+use smufl::StaffSpaces;
+
+use crate::render::{
+    input::StaffGeometry,
+    ir::{Coord, Element, FontStyle, FontWeight, Text, TextAnchor},
+};
+
+/// The size of measure numbers relative to other text.
+const SIZE: f64 = 0.8;
+
+/// The height of the baseline of measure numbers above the top line, at the
+/// barline at the start of their measure.
+const BARLINE_HEIGHT: StaffSpaces = StaffSpaces(1.0);
+
+/// The height of the baseline of the measure number at the start of a system
+/// above the top line, which clears the clef.
+const SYSTEM_HEIGHT: StaffSpaces = StaffSpaces(2.0);
+
+/// How the measures of a staff are numbered.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct MeasureNumbering {
+    pub display: Display,
+    /// The number of the first measure, such as 17 for a staff which continues
+    /// a score, or `None` to number a pickup measure 0 and the first full
+    /// measure 1.
+    pub first: Option<u32>,
+    /// Whether the elements of each measure are grouped together with its
+    /// number, such as in a `<g data-measure="5">` in SVG, to find the measure
+    /// which is being played.
+    pub grouped: bool,
+}
+
+/// Which measure numbers are drawn above the staff.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Display {
+    #[default]
+    Never,
+    /// The number of the first measure of each system, unless it is the first
+    /// measure of the piece.
+    System,
+    /// The numbers which are multiples of `n`, such as every fifth measure.
+    Every(u32),
+}
+
+impl Display {
+    /// Returns whether the numbers of the measure at `index` in its system are
+    /// drawn, which are a range for a multi-measure rest.
+    pub fn shows(&self, index: usize, numbers: &RangeInclusive<u32>) -> bool {
+        match self {
+            Self::Never => false,
+            Self::System => index == 0 && *numbers.start() > 1,
+            Self::Every(n) => numbers
+                .clone()
+                .any(|number| number > 0 && number.is_multiple_of(*n)),
+        }
+    }
+}
+
+/// Returns the numbers of the measure at `index` in its system, such as 5–8
+/// for a multi-measure rest, above the barline at `x` at the start of the
+/// measure, or above the clef at the start of the system.
+pub(crate) fn render(
+    numbers: &RangeInclusive<u32>,
+    index: usize,
+    x: StaffSpaces,
+    staff_geometry: StaffGeometry,
+) -> Element<StaffSpaces> {
+    let (x, y, anchor) = if index == 0 {
+        (
+            StaffSpaces::zero(),
+            staff_geometry.top() + SYSTEM_HEIGHT,
+            TextAnchor::Start,
+        )
+    } else {
+        (x, staff_geometry.top() + BARLINE_HEIGHT, TextAnchor::Middle)
+    };
+
+    Element::Text(Text {
+        origin: Coord { x, y },
+        value: if numbers.start() == numbers.end() {
+            numbers.start().to_string()
+        } else {
+            format!("{}–{}", numbers.start(), numbers.end())
+        },
+        anchor,
+        style: FontStyle::Normal,
+        weight: FontWeight::Normal,
+        size: SIZE,
+    })
+}
@@ -1,27 +1,57 @@
+pub mod annotation;
 pub mod chord;
+pub mod chord_symbol;
 pub mod duration;
+pub mod grace;
+pub mod hairpin;
 pub mod key_signature;
+pub mod lyric;
 pub mod measure;
+pub mod measure_numbering;
+pub mod multi_measure_rest;
+pub mod navigation;
+pub mod octave_line;
+pub mod ornament;
+pub mod pedal;
+pub mod score;
+pub mod time_signature;
 
 mod accidental;
+mod articulation;
 mod barline;
 mod beam;
 mod clef;
+mod dynamic;
+mod ending;
 mod rest;
 mod staff;
-mod time_signature;
 
 pub(crate) mod note;
 
 pub use accidental::Accidental;
+pub use annotation::Annotation;
+pub use articulation::Articulation;
 pub use barline::Barline;
 pub use beam::Beam;
 pub use chord::Chord;
+pub use chord_symbol::ChordSymbol;
 pub use clef::Clef;
 pub use duration::Duration;
+pub use dynamic::Dynamic;
+pub use ending::Ending;
+pub use grace::Grace;
+pub use hairpin::Hairpin;
 pub use key_signature::KeySignature;
+pub use lyric::Lyric;
 pub use measure::Measure;
+pub use measure_numbering::MeasureNumbering;
+pub use multi_measure_rest::MultiMeasureRest;
+pub use navigation::Navigation;
 pub use note::Note;
-pub use rest::Rest;
-pub use staff::Staff;
+pub use octave_line::OctaveLine;
+pub use ornament::Ornament;
+pub use pedal::Pedal;
+pub use rest::{FullMeasureRest, Rest};
+pub use score::{Score, ScoreMetadata};
+pub use staff::{Staff, StaffGeometry};
 pub use time_signature::TimeSignature;
@@ -0,0 +1,187 @@
+use smufl::{Glyph, Metadata, StaffSpaces};
+
+use super::time_signature;
+use crate::{
+    render::{
+        context::Context,
+        engraving_defaults_extensions::EngravingDefaultsExtensions,
+        input::StaffGeometry,
+        ir::{Coord, Element, Line, Linecap, Symbol},
+        metadata_extensions::MetadataExtensions,
+        Output, Render,
+    },
+    Result,
+};
+
+/// The length of H-bars, between the outer edges of their ends.
+const H_BAR_LENGTH: StaffSpaces = StaffSpaces(8.0);
+
+/// The space between the rests of church rests.
+const CHURCH_REST_SPACING: StaffSpaces = StaffSpaces(0.5);
+
+/// The most measures which are drawn as church rests, beyond which they would
+/// be hard to count at a glance and an H-bar is drawn instead.
+const MAX_CHURCH_REST_MEASURES: u32 = 8;
+
+/// The height of the middle of the number of measures above the top line.
+const NUMBER_HEIGHT: StaffSpaces = StaffSpaces(1.0);
+
+/// A rest which lasts for several measures, which consecutive empty measures
+/// collapse into when
+/// [`Staff::multi_measure_rests`](super::Staff::multi_measure_rests) are drawn.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MultiMeasureRest {
+    pub num_measures: u32,
+    pub style: Style,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Style {
+    /// A thick horizontal bar on the middle line between two vertical ends.
+    #[default]
+    HBar,
+    /// Longa, breve and whole rests which add up to the number of measures,
+    /// such as a longa and a whole rest for 5 measures, or an H-bar beyond 8
+    /// measures.
+    Church,
+}
+
+impl MultiMeasureRest {
+    /// Returns the rests of church rests, from the longest to the shortest.
+    fn church_rests(&self) -> Vec<Glyph> {
+        let mut glyphs = vec![Glyph::RestLonga; (self.num_measures / 4) as usize];
+
+        if self.num_measures % 4 >= 2 {
+            glyphs.push(Glyph::RestDoubleWhole);
+        }
+
+        if self.num_measures % 2 == 1 {
+            glyphs.push(Glyph::RestWhole);
+        }
+
+        glyphs
+    }
+
+    fn render_h_bar(
+        &self,
+        x: StaffSpaces,
+        staff_geometry: StaffGeometry,
+        metadata: &Metadata,
+    ) -> Result<Output> {
+        let y = staff_geometry.middle();
+        let right_end_width = metadata.width_of(Glyph::RestHBarRight)?;
+        let right_end = x + H_BAR_LENGTH - right_end_width;
+
+        let end = |x: StaffSpaces, glyph: Glyph| {
+            Element::Symbol(Symbol {
+                origin: Coord { x, y },
+                value: glyph,
+                size: 1.0,
+            })
+        };
+
+        let elements = vec![
+            end(x, Glyph::RestHBarLeft),
+            Element::Line(Line {
+                from: Coord {
+                    x: x + metadata.width_of(Glyph::RestHBarLeft)?,
+                    y,
+                },
+                to: Coord { x: right_end, y },
+                thickness: metadata.engraving_defaults.h_bar_thickness(),
+                cap: Linecap::Butt,
+            }),
+            end(right_end, Glyph::RestHBarRight),
+        ];
+
+        Ok(Output {
+            elements,
+            width: H_BAR_LENGTH,
+        })
+    }
+
+    /// Renders church rests, with the whole rest hanging from the second line
+    /// from the top and the other rests standing on the middle line.
+    fn render_church_rests(
+        &self,
+        x: StaffSpaces,
+        staff_geometry: StaffGeometry,
+        metadata: &Metadata,
+    ) -> Result<Output> {
+        let mut elements = vec![];
+        let mut width = StaffSpaces::zero();
+
+        for (index, glyph) in self.church_rests().into_iter().enumerate() {
+            if index > 0 {
+                width += CHURCH_REST_SPACING;
+            }
+
+            let y = match glyph {
+                Glyph::RestWhole => staff_geometry.whole_rest_line(),
+                _ => staff_geometry.middle(),
+            };
+
+            elements.push(Element::Symbol(Symbol {
+                origin: Coord { x: x + width, y },
+                value: glyph,
+                size: 1.0,
+            }));
+            width += metadata.width_of(glyph)?;
+        }
+
+        Ok(Output { elements, width })
+    }
+
+    /// Returns the number of measures in time signature digits, centered
+    /// above the rest between `x` and `x + width`.
+    fn render_number(
+        &self,
+        x: StaffSpaces,
+        width: StaffSpaces,
+        staff_geometry: StaffGeometry,
+        metadata: &Metadata,
+    ) -> Result<Vec<Element<StaffSpaces>>> {
+        let glyphs = time_signature::glyphs_for_number(self.num_measures);
+        let widths = glyphs
+            .iter()
+            .map(|glyph| metadata.width_of(*glyph))
+            .collect::<Result<Vec<_>>>()?;
+        let number_width = widths
+            .iter()
+            .fold(StaffSpaces::zero(), |total, width| total + *width);
+
+        let y = staff_geometry.top() + NUMBER_HEIGHT;
+        let mut x = x + (width - number_width) / 2.0;
+        let mut elements = vec![];
+
+        for (glyph, width) in glyphs.into_iter().zip(widths) {
+            elements.push(Element::Symbol(Symbol {
+                origin: Coord { x, y },
+                value: glyph,
+                size: 1.0,
+            }));
+            x += width;
+        }
+
+        Ok(elements)
+    }
+}
+
+impl Render for MultiMeasureRest {
+    fn render(&self, x: StaffSpaces, context: &mut Context, metadata: &Metadata) -> Result<Output> {
+        let staff_geometry = context.staff_geometry();
+
+        let mut output = match self.style {
+            Style::Church if self.num_measures <= MAX_CHURCH_REST_MEASURES => {
+                self.render_church_rests(x, staff_geometry, metadata)?
+            }
+            Style::HBar | Style::Church => self.render_h_bar(x, staff_geometry, metadata)?,
+        };
+
+        output
+            .elements
+            .extend(self.render_number(x, output.width, staff_geometry, metadata)?);
+
+        Ok(output)
+    }
+}
@@ -0,0 +1,134 @@
+use smufl::{Glyph, Metadata, StaffSpaces};
+
+use crate::{
+    render::{
+        ir::{Coord, Element, FontStyle, FontWeight, Symbol, Text, TextAnchor},
+        metadata_extensions::MetadataExtensions,
+    },
+    Result,
+};
+
+/// The space between a navigation marker and the barline it is aligned to.
+const BARLINE_TO_MARKER: StaffSpaces = StaffSpaces(0.5);
+
+/// The space between the text and glyph of a "To Coda" marker.
+const TEXT_TO_GLYPH: StaffSpaces = StaffSpaces(0.5);
+
+/// A navigation marker attached to a measure, which directs the performer to
+/// another point in the music.
+///
+/// Segno and coda signs mark the start of a measure, while the other markers
+/// apply at the end of a measure.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Navigation {
+    /// The point to which D.S. returns.
+    Segno,
+    /// The start of the coda, to which "To Coda" jumps.
+    Coda,
+    /// Where to jump to the coda, after a D.C. or D.S. al Coda.
+    ToCoda,
+    /// Where to end, after a D.C. or D.S. al Fine.
+    Fine,
+    /// Return to the start of the music, then play until the target.
+    DaCapo(Option<Target>),
+    /// Return to the segno, then play until the target.
+    DalSegno(Option<Target>),
+}
+
+/// Where to play until after a D.C. or D.S., which is the end of the music
+/// without a target.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Target {
+    Fine,
+    Coda,
+}
+
+impl Navigation {
+    /// Returns whether the marker is placed at the start of its measure,
+    /// rather than at its end.
+    pub fn is_at_start(&self) -> bool {
+        matches!(self, Self::Segno | Self::Coda)
+    }
+
+    /// Returns the text of the marker, if it is written as text.
+    pub fn text(&self) -> Option<&'static str> {
+        match self {
+            Self::Segno | Self::Coda => None,
+            Self::ToCoda => Some("To Coda"),
+            Self::Fine => Some("Fine"),
+            Self::DaCapo(None) => Some("D.C."),
+            Self::DaCapo(Some(Target::Fine)) => Some("D.C. al Fine"),
+            Self::DaCapo(Some(Target::Coda)) => Some("D.C. al Coda"),
+            Self::DalSegno(None) => Some("D.S."),
+            Self::DalSegno(Some(Target::Fine)) => Some("D.S. al Fine"),
+            Self::DalSegno(Some(Target::Coda)) => Some("D.S. al Coda"),
+        }
+    }
+
+    fn glyph(&self) -> Option<Glyph> {
+        match self {
+            Self::Segno => Some(Glyph::Segno),
+            Self::Coda | Self::ToCoda => Some(Glyph::Coda),
+            _ => None,
+        }
+    }
+
+    /// Returns the marker for a measure from `start` to `end`, with its
+    /// baseline at `y`.
+    ///
+    /// Signs at the start of the measure are placed after its start, and
+    /// instructions at the end of the measure end just before its barline.
+    pub(crate) fn render(
+        &self,
+        start: StaffSpaces,
+        end: StaffSpaces,
+        y: StaffSpaces,
+        metadata: &Metadata,
+    ) -> Result<Vec<Element<StaffSpaces>>> {
+        let mut elements = vec![];
+
+        if self.is_at_start() {
+            if let Some(glyph) = self.glyph() {
+                elements.push(Element::Symbol(Symbol {
+                    origin: Coord {
+                        x: start + BARLINE_TO_MARKER,
+                        y,
+                    },
+                    value: glyph,
+                    size: 1.0,
+                }));
+            }
+
+            return Ok(elements);
+        }
+
+        let mut text_end = end - BARLINE_TO_MARKER;
+
+        if let Some(glyph) = self.glyph() {
+            let width = metadata.width_of(glyph)?;
+            text_end -= width + TEXT_TO_GLYPH;
+
+            elements.push(Element::Symbol(Symbol {
+                origin: Coord {
+                    x: end - BARLINE_TO_MARKER - width,
+                    y,
+                },
+                value: glyph,
+                size: 1.0,
+            }));
+        }
+
+        if let Some(text) = self.text() {
+            elements.push(Element::Text(Text {
+                origin: Coord { x: text_end, y },
+                value: text.to_owned(),
+                anchor: TextAnchor::End,
+                style: FontStyle::Normal,
+                weight: FontWeight::Normal,
+                size: 1.0,
+            }));
+        }
+
+        Ok(elements)
+    }
+}
@@ -1,12 +1,16 @@
 use smufl::{Glyph, Metadata, StaffSpaces};
 
-use super::{duration, Accidental, Beam, Duration};
+use super::{
+    articulation::create_articulations, duration, Accidental, Articulation, Beam, Duration,
+    Ornament, StaffGeometry,
+};
 use crate::{
     render::{
         context::{beam, Context},
         engraving_defaults_extensions::EngravingDefaultsExtensions,
         glyph_data_extensions::GlyphDataExtensions,
         ir::{Coord, Element, Group, Line, Linecap, Symbol},
+        math,
         metadata_extensions::MetadataExtensions,
         stem::{self, Stem},
         Output, Render,
@@ -21,6 +25,8 @@ pub struct Note {
     pub y: StaffSpaces,
     pub accidental: Option<Accidental>,
     pub duration: Duration,
+    pub articulations: Vec<Articulation>,
+    pub ornament: Option<Ornament>,
     pub beam: Option<Beam>,
     pub id: Option<String>,
 }
@@ -33,7 +39,9 @@ impl Render for Note {
 
         let mut elements = vec![notehead];
 
-        let mut leger_lines = create_leger_lines(x, self.y, glyph, metadata)?;
+        let staff_geometry = context.staff_geometry();
+
+        let mut leger_lines = create_leger_lines(x, self.y, glyph, staff_geometry, metadata)?;
         elements.append(&mut leger_lines);
 
         if let Some(accidental) = self.accidental {
@@ -42,21 +50,25 @@ impl Render for Note {
             elements.push(accidental);
         }
 
-        match context.beam() {
-            Some(beam) => beam.add_notehead(beam::Notehead {
-                glyph,
-                x,
-                y: self.y,
-                min_stem_length: None,
-            }),
+        let stem_direction = match context.beam() {
+            Some(beam) => {
+                beam.add_notehead(beam::Notehead {
+                    glyph,
+                    x,
+                    y: self.y,
+                    min_stem_length: None,
+                });
+
+                beam.stem_direction
+            }
             None => {
-                if self.duration.value != duration::Value::Whole {
-                    let stem_direction = if self.y >= StaffSpaces(2.0) {
-                        stem::Direction::Down
-                    } else {
-                        stem::Direction::Up
-                    };
+                let stem_direction = if self.y >= staff_geometry.middle() {
+                    stem::Direction::Down
+                } else {
+                    stem::Direction::Up
+                };
 
+                if self.duration.value != duration::Value::Whole {
                     let stem = Stem::new(glyph, x, self.y, stem_direction, None);
 
                     if let Some(flag_glyph) = self.duration.value.flag_glyph(stem_direction) {
@@ -73,15 +85,41 @@ impl Render for Note {
 
                     elements.push(stem.render(metadata)?);
                 }
+
+                stem_direction
             }
         };
 
+        let mut articulations = create_articulations(
+            x,
+            self.y,
+            glyph,
+            stem_direction,
+            &self.articulations,
+            staff_geometry,
+            metadata,
+        )?;
+        elements.append(&mut articulations);
+
         let width = metadata.width_of(glyph)?;
 
+        if let Some(ornament) = &self.ornament {
+            let highest_y = highest_point(&elements, self.y, stem_direction, context, metadata);
+            let mut ornament = ornament.render(
+                x + width / 2.0,
+                highest_y,
+                staff_geometry,
+                context,
+                metadata,
+            )?;
+            elements.append(&mut ornament);
+        }
+
         if self.id.is_some() {
             Ok(Output {
                 elements: vec![Element::Group(Group {
                     id: self.id.clone(),
+                    data: vec![],
                     elements,
                 })],
                 width,
@@ -92,10 +130,32 @@ impl Render for Note {
     }
 }
 
+/// Returns the highest point of the elements of a note or chord whose highest
+/// notehead is at `y`, including the stem of a beamed note, whose length is
+/// only known once the beam has been rendered.
+pub fn highest_point(
+    elements: &[Element<StaffSpaces>],
+    y: StaffSpaces,
+    stem_direction: stem::Direction,
+    context: &mut Context,
+    metadata: &Metadata,
+) -> StaffSpaces {
+    let highest_y = elements
+        .iter()
+        .map(|element| element.max_y(metadata))
+        .fold(y, math::max);
+
+    match (context.beam(), stem_direction) {
+        (Some(_), stem::Direction::Up) => math::max(highest_y, y + stem::DEFAULT_LENGTH),
+        _ => highest_y,
+    }
+}
+
 pub fn create_notehead(x: StaffSpaces, y: StaffSpaces, glyph: Glyph) -> Element<StaffSpaces> {
     Element::Symbol(Symbol {
         origin: Coord { x, y },
-        value: glyph.codepoint(),
+        value: glyph,
+        size: 1.0,
     })
 }
 
@@ -103,16 +163,19 @@ pub fn create_leger_lines(
     x: StaffSpaces,
     y: StaffSpaces,
     glyph: Glyph,
+    staff_geometry: StaffGeometry,
     metadata: &Metadata,
 ) -> Result<Vec<Element<StaffSpaces>>> {
-    if y >= StaffSpaces(0.0) && y <= StaffSpaces(4.0) {
+    if staff_geometry.contains(y) {
         return Ok(vec![]);
     }
 
-    let (num_lines, y, increment) = if y >= StaffSpaces(4.0) {
+    let top = staff_geometry.top();
+
+    let (num_lines, y, increment) = if y >= top {
         (
-            (y - StaffSpaces(4.0)).0 as usize,
-            StaffSpaces(5.0),
+            (y - top).0 as usize,
+            top + StaffSpaces(1.0),
             StaffSpaces(1.0),
         )
     } else {
@@ -146,7 +209,8 @@ pub fn create_accidental(
 
     Ok(Element::Symbol(Symbol {
         origin: Coord { x, y },
-        value: accidental_glyph.codepoint(),
+        value: accidental_glyph,
+        size: 1.0,
     }))
 }
 
@@ -174,6 +238,7 @@ pub fn create_flag(
 
     Ok(Element::Symbol(Symbol {
         origin: Coord { x, y },
-        value: flag_glyph.codepoint(),
+        value: flag_glyph,
+        size: 1.0,
     }))
 }
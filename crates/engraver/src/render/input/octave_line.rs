@@ -0,0 +1,78 @@
+use smufl::{Glyph, Metadata, StaffSpaces};
+
+use crate::{
+    render::{context::Context, Output, Render},
+    Result,
+};
+
+/// The start or end of an octave line, such as 8va.
+///
+/// The notes under an octave line are drawn an octave or two away from where
+/// they sound, to keep them close to the staff. An octave line lasts from its
+/// start until its end or the start of another octave line, and may continue
+/// across barlines.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OctaveLine {
+    Start(Kind),
+    End,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Kind {
+    /// 8va, above notes which sound an octave higher than they are drawn.
+    OctaveAbove,
+    /// 8vb, below notes which sound an octave lower than they are drawn.
+    OctaveBelow,
+    /// 15ma, above notes which sound two octaves higher than they are drawn.
+    TwoOctavesAbove,
+    /// 15mb, below notes which sound two octaves lower than they are drawn.
+    TwoOctavesBelow,
+}
+
+impl Kind {
+    /// Returns the number of octaves by which the notes sound higher (or
+    /// lower, if negative) than they are drawn.
+    pub fn octaves(&self) -> i8 {
+        match self {
+            Self::OctaveAbove => 1,
+            Self::OctaveBelow => -1,
+            Self::TwoOctavesAbove => 2,
+            Self::TwoOctavesBelow => -2,
+        }
+    }
+
+    /// Returns whether the line is drawn above the staff, rather than below.
+    pub fn is_above(&self) -> bool {
+        self.octaves() > 0
+    }
+
+    pub(crate) fn glyph(&self) -> Glyph {
+        match self {
+            Self::OctaveAbove => Glyph::OttavaAlta,
+            Self::OctaveBelow => Glyph::OttavaBassaVb,
+            Self::TwoOctavesAbove => Glyph::QuindicesimaAlta,
+            Self::TwoOctavesBelow => Glyph::QuindicesimaBassaMb,
+        }
+    }
+}
+
+impl Render for OctaveLine {
+    /// Adds the start or end of the octave line to the octave lines of the
+    /// staff, which are rendered once the whole staff has been rendered.
+    fn render(
+        &self,
+        x: StaffSpaces,
+        context: &mut Context,
+        _metadata: &Metadata,
+    ) -> Result<Output> {
+        match self {
+            Self::Start(kind) => context.octave_lines().start(*kind, x),
+            Self::End => context.octave_lines().end(x)?,
+        }
+
+        Ok(Output {
+            elements: vec![],
+            width: StaffSpaces::zero(),
+        })
+    }
+}
@@ -0,0 +1,192 @@
+use smufl::{Glyph, Metadata, StaffSpaces};
+
+use super::{Accidental, StaffGeometry};
+use crate::{
+    render::{
+        context::Context,
+        glyph_data_extensions::GlyphDataExtensions,
+        ir::{Coord, Element, Symbol},
+        math,
+        metadata_extensions::MetadataExtensions,
+    },
+    Result,
+};
+
+/// The minimum distance from the top line to the bottom of an ornament and
+/// its accidentals.
+const STAFF_CLEARANCE: StaffSpaces = StaffSpaces(1.0);
+
+/// The minimum distance from the highest point of the note or chord, including
+/// its stem and articulations, to the bottom of its ornament.
+const NOTE_CLEARANCE: StaffSpaces = StaffSpaces(0.5);
+
+/// The size of the accidentals of ornaments, relative to other glyphs.
+const ACCIDENTAL_SIZE: f64 = 0.6;
+
+/// The space between an ornament and its accidentals.
+const ACCIDENTAL_SPACING: StaffSpaces = StaffSpaces(0.2);
+
+/// The space between a trill and its extension line.
+const TRILL_TO_EXTENSION: StaffSpaces = StaffSpaces(0.2);
+
+/// The space between the end of a trill extension line and the next note,
+/// chord, rest or barline.
+const EXTENSION_END_SPACE: StaffSpaces = StaffSpaces(0.5);
+
+/// An ornament attached to a note or chord, which is drawn above the staff.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Ornament {
+    pub kind: Kind,
+    /// An accidental drawn above the ornament, which alters its upper
+    /// auxiliary note.
+    pub accidental_above: Option<Accidental>,
+    /// An accidental drawn below the ornament, which alters its lower
+    /// auxiliary note.
+    pub accidental_below: Option<Accidental>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Kind {
+    /// A trill, which alternates the note with the note above it, and whose
+    /// wavy extension line lasts until the next note, chord or rest if it is
+    /// `extended`.
+    Trill { extended: bool },
+    /// A mordent, which plays the note, the note below it and the note again.
+    Mordent,
+    /// An inverted mordent, which plays the note, the note above it and the
+    /// note again.
+    InvertedMordent,
+    /// A turn, which plays the note above, the note, the note below and the
+    /// note again.
+    Turn,
+    /// An inverted turn, which plays the note below, the note, the note above
+    /// and the note again.
+    InvertedTurn,
+}
+
+impl Kind {
+    fn glyph(&self) -> Glyph {
+        match self {
+            Self::Trill { .. } => Glyph::OrnamentTrill,
+            Self::Mordent => Glyph::OrnamentMordent,
+            Self::InvertedMordent => Glyph::OrnamentShortTrill,
+            Self::Turn => Glyph::OrnamentTurn,
+            Self::InvertedTurn => Glyph::OrnamentTurnInverted,
+        }
+    }
+}
+
+impl Ornament {
+    /// Returns the ornament centered on `center_x`, with its accidentals above
+    /// and below it, clear of the staff and of the note or chord whose highest
+    /// point is at `highest_y`.
+    ///
+    /// The extension line of an extended trill is started in the context, and
+    /// drawn once the next note, chord or rest is reached.
+    pub(crate) fn render(
+        &self,
+        center_x: StaffSpaces,
+        highest_y: StaffSpaces,
+        staff_geometry: StaffGeometry,
+        context: &mut Context,
+        metadata: &Metadata,
+    ) -> Result<Vec<Element<StaffSpaces>>> {
+        let mut elements = vec![];
+        let mut bottom = math::max(
+            staff_geometry.top() + STAFF_CLEARANCE,
+            highest_y + NOTE_CLEARANCE,
+        );
+
+        if let Some(accidental) = self.accidental_below {
+            let (element, top) = centered_symbol(
+                accidental.glyph(),
+                ACCIDENTAL_SIZE,
+                center_x,
+                bottom,
+                metadata,
+            )?;
+            elements.push(element);
+            bottom = top + ACCIDENTAL_SPACING;
+        }
+
+        let glyph = self.kind.glyph();
+        let (element, top) = centered_symbol(glyph, 1.0, center_x, bottom, metadata)?;
+
+        elements.push(element);
+
+        if self.kind == (Kind::Trill { extended: true }) {
+            let bounding_box = metadata.bounding_boxes.try_get(glyph)?;
+            let wiggle_box = metadata.bounding_boxes.try_get(Glyph::WiggleTrill)?;
+            let right = center_x + (bounding_box.ne.x() - bounding_box.sw.x()) / 2.0;
+            let middle = (bottom + top) / 2.0;
+
+            // The wiggles are centered on the middle of the trill.
+            context.begin_trill_extension(Coord {
+                x: right + TRILL_TO_EXTENSION,
+                y: middle - (wiggle_box.sw.y() + wiggle_box.ne.y()) / 2.0,
+            });
+        }
+
+        if let Some(accidental) = self.accidental_above {
+            let (element, _) = centered_symbol(
+                accidental.glyph(),
+                ACCIDENTAL_SIZE,
+                center_x,
+                top + ACCIDENTAL_SPACING,
+                metadata,
+            )?;
+            elements.push(element);
+        }
+
+        Ok(elements)
+    }
+}
+
+/// Returns the wavy extension line of a trill, repeating the trill wiggle from
+/// `start` for as many times as fit before `end`.
+pub(crate) fn render_trill_extension(
+    start: Coord<StaffSpaces>,
+    end: StaffSpaces,
+    metadata: &Metadata,
+) -> Result<Vec<Element<StaffSpaces>>> {
+    let wiggle_width = metadata.width_of(Glyph::WiggleTrill)?;
+    let length = end - EXTENSION_END_SPACE - start.x;
+    let num_wiggles = (length.0 / wiggle_width.0).floor().max(0.0) as usize;
+
+    Ok((0..num_wiggles)
+        .map(|index| {
+            Element::Symbol(Symbol {
+                origin: Coord {
+                    x: start.x + wiggle_width * index as f64,
+                    y: start.y,
+                },
+                value: Glyph::WiggleTrill,
+                size: 1.0,
+            })
+        })
+        .collect())
+}
+
+/// Returns a glyph of the given size centered on `center_x` with the bottom of
+/// its bounding box at `bottom`, and the top of its bounding box.
+fn centered_symbol(
+    glyph: Glyph,
+    size: f64,
+    center_x: StaffSpaces,
+    bottom: StaffSpaces,
+    metadata: &Metadata,
+) -> Result<(Element<StaffSpaces>, StaffSpaces)> {
+    let bounding_box = metadata.bounding_boxes.try_get(glyph)?;
+    let height = (bounding_box.ne.y() - bounding_box.sw.y()) * size;
+
+    let element = Element::Symbol(Symbol {
+        origin: Coord {
+            x: center_x - (bounding_box.sw.x() + bounding_box.ne.x()) / 2.0 * size,
+            y: bottom - bounding_box.sw.y() * size,
+        },
+        value: glyph,
+        size,
+    });
+
+    Ok((element, bottom + height))
+}
@@ -0,0 +1,61 @@
+use smufl::{Metadata, StaffSpaces};
+
+use crate::{
+    render::{context::Context, Output, Render},
+    Result,
+};
+
+/// A piano pedal marking, which applies from the following note, chord or
+/// rest and is drawn below the staff.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Pedal {
+    /// Pressing the sustain pedal.
+    Down(Depth),
+    /// Lifting the sustain pedal and pressing it again straight away, which is
+    /// a notch in a pedal bracket.
+    Change(Depth),
+    /// Lifting the sustain pedal.
+    Up,
+    /// Pressing the soft pedal, marked "una corda".
+    UnaCorda,
+    /// Lifting the soft pedal, marked "tre corde".
+    TreCorde,
+}
+
+/// How far the sustain pedal is pressed.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Depth {
+    #[default]
+    Full,
+    Half,
+}
+
+/// How the sustain pedal marks of a staff are drawn.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Style {
+    /// "Ped." where the pedal is pressed and an asterisk where it is lifted.
+    #[default]
+    Text,
+    /// A bracket below the notes for which the pedal is pressed, with a notch
+    /// where it is changed. The hooks and notches of half pedals are half the
+    /// height of the others.
+    Bracket,
+}
+
+impl Render for Pedal {
+    /// Adds the marking to the pedal marks below the staff, which are rendered
+    /// once the whole staff has been rendered.
+    fn render(
+        &self,
+        x: StaffSpaces,
+        context: &mut Context,
+        _metadata: &Metadata,
+    ) -> Result<Output> {
+        context.pedals().add(*self, x);
+
+        Ok(Output {
+            elements: vec![],
+            width: StaffSpaces::zero(),
+        })
+    }
+}
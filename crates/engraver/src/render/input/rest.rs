@@ -1,4 +1,4 @@
-use smufl::{Metadata, StaffSpaces};
+use smufl::{Glyph, Metadata, StaffSpaces};
 
 use super::Duration;
 use crate::{
@@ -11,6 +11,10 @@ use crate::{
     Result,
 };
 
+/// The width of full-measure rests, which keeps measures of them from being
+/// narrower than measures of notes.
+const FULL_MEASURE_REST_WIDTH: StaffSpaces = StaffSpaces(8.0);
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct Rest {
     pub duration: Duration,
@@ -18,20 +22,16 @@ pub struct Rest {
 }
 
 impl Render for Rest {
-    fn render(
-        &self,
-        x: StaffSpaces,
-        _context: &mut Context,
-        metadata: &Metadata,
-    ) -> Result<Output> {
+    fn render(&self, x: StaffSpaces, context: &mut Context, metadata: &Metadata) -> Result<Output> {
         let glyph = self.duration.value.rest_glyph();
 
         let element = Element::Symbol(Symbol {
             origin: Coord {
                 x,
-                y: StaffSpaces(2.0),
+                y: context.staff_geometry().middle(),
             },
-            value: glyph.codepoint(),
+            value: glyph,
+            size: 1.0,
         });
         let width = metadata.width_of(glyph)?;
 
@@ -39,6 +39,7 @@ impl Render for Rest {
             Ok(Output {
                 elements: vec![Element::Group(Group {
                     id: self.id.clone(),
+                    data: vec![],
                     elements: vec![element],
                 })],
                 width,
@@ -51,3 +52,49 @@ impl Render for Rest {
         }
     }
 }
+
+/// A rest which lasts for the whole of its measure, whatever its time
+/// signature.
+///
+/// The rest is drawn at the start of the space it takes up, and is moved to the
+/// middle of its measure by [`Measure::render`](super::Measure::render) once
+/// the rest of the measure is laid out.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FullMeasureRest {
+    /// A whole rest, which hangs from the second line from the top.
+    Whole,
+    /// A breve rest, which stands on the middle line, for measures of a breve
+    /// or longer, such as 4/2.
+    Breve,
+}
+
+impl FullMeasureRest {
+    pub fn glyph(&self) -> Glyph {
+        match self {
+            Self::Whole => Glyph::RestWhole,
+            Self::Breve => Glyph::RestDoubleWhole,
+        }
+    }
+}
+
+impl Render for FullMeasureRest {
+    fn render(&self, x: StaffSpaces, context: &mut Context, metadata: &Metadata) -> Result<Output> {
+        let staff_geometry = context.staff_geometry();
+        let y = match self {
+            Self::Whole => staff_geometry.whole_rest_line(),
+            Self::Breve => staff_geometry.middle(),
+        };
+
+        let glyph = self.glyph();
+        let width = FULL_MEASURE_REST_WIDTH.max(metadata.width_of(glyph)?);
+
+        Ok(Output {
+            elements: vec![Element::Symbol(Symbol {
+                origin: Coord { x, y },
+                value: glyph,
+                size: 1.0,
+            })],
+            width,
+        })
+    }
+}
@@ -0,0 +1,304 @@
+use smufl::{Metadata, StaffSpaces};
+
+use super::Staff;
+use crate::render::{
+    ir::{Coord, Element, FontStyle, FontWeight, Text, TextAnchor},
+    math, Page, Result,
+};
+
+/// The distance from the highest element of the staff to the baseline of the
+/// lowest row of the header.
+const HEADER_CLEARANCE: StaffSpaces = StaffSpaces(3.0);
+
+/// The distance between the baselines of consecutive rows of the header,
+/// relative to the size of the text of the upper row.
+const LINE_SPACING: f64 = 1.5;
+
+/// The distance from the lowest element of the staff to the top of the
+/// footer.
+const FOOTER_CLEARANCE: StaffSpaces = StaffSpaces(3.0);
+
+/// The space between the content of a page and its top and bottom, which
+/// leaves room for text of the standard size next to them.
+const MARGIN: StaffSpaces = StaffSpaces(1.0);
+
+/// A staff with a header above it, containing the title and the names of the
+/// people who wrote it, and a footer below it, containing the copyright.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Score {
+    pub metadata: ScoreMetadata,
+    pub style: ScoreStyle,
+    pub staff: Staff,
+}
+
+/// The metadata of a score, each of which is left out of the header or footer
+/// when it is `None`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ScoreMetadata {
+    pub title: Option<String>,
+    pub subtitle: Option<String>,
+    pub composer: Option<String>,
+    pub lyricist: Option<String>,
+    pub arranger: Option<String>,
+    pub copyright: Option<String>,
+}
+
+/// The format of each text of the header and footer of a score.
+///
+/// The font family of the text is chosen by the output format, such as with
+/// [`svg::Options::text_font_family`](crate::svg::Options::text_font_family).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScoreStyle {
+    pub title: TextFormat,
+    pub subtitle: TextFormat,
+    pub composer: TextFormat,
+    pub lyricist: TextFormat,
+    pub arranger: TextFormat,
+    pub copyright: TextFormat,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextFormat {
+    /// The size of the text relative to other text, such as lyrics.
+    pub size: f64,
+    pub style: FontStyle,
+    pub weight: FontWeight,
+    pub alignment: Alignment,
+}
+
+/// Where a text is placed across the width of the page.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Alignment {
+    Left,
+    #[default]
+    Center,
+    Right,
+}
+
+impl Default for ScoreStyle {
+    fn default() -> Self {
+        Self {
+            title: TextFormat {
+                size: 2.5,
+                weight: FontWeight::Bold,
+                ..TextFormat::default()
+            },
+            subtitle: TextFormat {
+                size: 1.6,
+                ..TextFormat::default()
+            },
+            composer: TextFormat {
+                size: 1.2,
+                alignment: Alignment::Right,
+                ..TextFormat::default()
+            },
+            lyricist: TextFormat {
+                size: 1.2,
+                alignment: Alignment::Left,
+                ..TextFormat::default()
+            },
+            arranger: TextFormat {
+                style: FontStyle::Italic,
+                alignment: Alignment::Right,
+                ..TextFormat::default()
+            },
+            copyright: TextFormat {
+                size: 0.8,
+                ..TextFormat::default()
+            },
+        }
+    }
+}
+
+impl Default for TextFormat {
+    fn default() -> Self {
+        Self {
+            size: 1.0,
+            style: FontStyle::Normal,
+            weight: FontWeight::Normal,
+            alignment: Alignment::Center,
+        }
+    }
+}
+
+impl Score {
+    /// Returns the page of the score, with the header above the staff and the
+    /// footer below it, across the width of the staff.
+    pub fn render(&self, metadata: &Metadata) -> Result<Page> {
+        let mut elements = self.staff.render(metadata)?;
+
+        let width = elements
+            .iter()
+            .map(Element::max_x)
+            .reduce(math::max)
+            .unwrap_or(StaffSpaces::zero());
+        let highest_y = elements
+            .iter()
+            .map(|element| element.max_y(metadata))
+            .reduce(math::max)
+            .unwrap_or(StaffSpaces::zero());
+        let lowest_y = elements
+            .iter()
+            .map(|element| element.min_y(metadata))
+            .reduce(math::min)
+            .unwrap_or(StaffSpaces::zero());
+
+        let mut top = highest_y;
+        let mut baseline = highest_y + HEADER_CLEARANCE;
+
+        for (index, row) in self.header_rows().iter().rev().enumerate() {
+            let size = row
+                .iter()
+                .map(|(_, format)| format.size)
+                .reduce(f64::max)
+                .unwrap_or(1.0);
+
+            if index > 0 {
+                baseline += StaffSpaces(LINE_SPACING * size);
+            }
+
+            elements.extend(
+                row.iter()
+                    .map(|(text, format)| text_element(text, *format, baseline, width)),
+            );
+            top = baseline + self.staff.text_metrics.cap_height(size);
+        }
+
+        let mut bottom = lowest_y;
+
+        if let Some(copyright) = &self.metadata.copyright {
+            let format = self.style.copyright;
+            let baseline =
+                lowest_y - FOOTER_CLEARANCE - self.staff.text_metrics.cap_height(format.size);
+
+            elements.push(text_element(copyright, format, baseline, width));
+            bottom = baseline - self.staff.text_metrics.descender(format.size);
+        }
+
+        Ok(Page {
+            elements,
+            top: top + MARGIN,
+            bottom: bottom - MARGIN,
+        })
+    }
+
+    /// Returns the rows of the header from top to bottom.
+    ///
+    /// The title comes first, followed by the subtitle, composer, lyricist and
+    /// arranger. Each text shares a row with the texts before it, such as the
+    /// lyricist on the left and the composer on the right, unless one of them
+    /// is centered or has the same alignment.
+    fn header_rows(&self) -> Vec<Vec<(&str, TextFormat)>> {
+        let ScoreMetadata {
+            title,
+            subtitle,
+            composer,
+            lyricist,
+            arranger,
+            copyright: _,
+        } = &self.metadata;
+        let style = &self.style;
+
+        let texts = [
+            (title, style.title),
+            (subtitle, style.subtitle),
+            (composer, style.composer),
+            (lyricist, style.lyricist),
+            (arranger, style.arranger),
+        ];
+
+        let mut rows: Vec<Vec<(&str, TextFormat)>> = vec![];
+
+        for (text, format) in texts {
+            let Some(text) = text else {
+                continue;
+            };
+
+            let shares_row = rows.last().is_some_and(|row| {
+                format.alignment != Alignment::Center
+                    && row.iter().all(|(_, other)| {
+                        other.alignment != Alignment::Center && other.alignment != format.alignment
+                    })
+            });
+
+            match rows.last_mut() {
+                Some(row) if shares_row => row.push((text, format)),
+                _ => rows.push(vec![(text, format)]),
+            }
+        }
+
+        rows
+    }
+}
+
+fn text_element(
+    text: &str,
+    format: TextFormat,
+    y: StaffSpaces,
+    width: StaffSpaces,
+) -> Element<StaffSpaces> {
+    let (x, anchor) = match format.alignment {
+        Alignment::Left => (StaffSpaces::zero(), TextAnchor::Start),
+        Alignment::Center => (width / 2.0, TextAnchor::Middle),
+        Alignment::Right => (width, TextAnchor::End),
+    };
+
+    Element::Text(Text {
+        origin: Coord { x, y },
+        value: text.to_owned(),
+        anchor,
+        style: format.style,
+        weight: format.weight,
+        size: format.size,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Score, ScoreMetadata};
+
+    fn texts_of_header_rows(metadata: ScoreMetadata) -> Vec<Vec<String>> {
+        let score = Score {
+            metadata,
+            ..Score::default()
+        };
+
+        score
+            .header_rows()
+            .into_iter()
+            .map(|row| row.into_iter().map(|(text, _)| text.to_owned()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn header_rows() {
+        let metadata = ScoreMetadata {
+            title: Some("Title".to_owned()),
+            subtitle: Some("Subtitle".to_owned()),
+            composer: Some("Composer".to_owned()),
+            lyricist: Some("Lyricist".to_owned()),
+            arranger: Some("Arranger".to_owned()),
+            copyright: Some("Copyright".to_owned()),
+        };
+
+        assert_eq!(
+            texts_of_header_rows(metadata),
+            vec![
+                vec!["Title"],
+                vec!["Subtitle"],
+                vec!["Composer", "Lyricist"],
+                vec!["Arranger"],
+            ]
+        );
+        assert_eq!(
+            texts_of_header_rows(ScoreMetadata {
+                title: Some("Title".to_owned()),
+                lyricist: Some("Lyricist".to_owned()),
+                arranger: Some("Arranger".to_owned()),
+                ..ScoreMetadata::default()
+            }),
+            vec![vec!["Title"], vec!["Lyricist", "Arranger"]]
+        );
+        assert!(texts_of_header_rows(ScoreMetadata::default()).is_empty());
+    }
+}
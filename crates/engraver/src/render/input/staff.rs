@@ -1,10 +1,16 @@
+use std::{ops::Range, rc::Rc};
+
 use smufl::{Metadata, StaffSpaces};
 
-use super::{Clef, KeySignature, Measure, TimeSignature};
+use super::{
+    measure, measure_numbering, multi_measure_rest, pedal, Barline, Clef, Ending, KeySignature,
+    Measure, MeasureNumbering, MultiMeasureRest, Navigation, TimeSignature,
+};
 use crate::render::{
+    context::{Annotations, ChordSymbols, Continuing, Dynamics, Lyrics, OctaveLines, Pedals},
     engraving_defaults_extensions::EngravingDefaultsExtensions,
-    ir::{Coord, Element, Line, Linecap},
-    Renderer, Result,
+    ir::{Coord, Element, Group, Line, Linecap, Scale, Translate},
+    math, Renderer, Result, TextMetrics,
 };
 
 const BEGINNING_OF_STAFF_SPACE: StaffSpaces = StaffSpaces(1.0);
@@ -12,50 +18,507 @@ const SPACE_AFTER_CLEF: StaffSpaces = StaffSpaces(1.0);
 const SPACE_AFTER_KEY_SIGNATURE: StaffSpaces = StaffSpaces(1.0);
 const SPACE_AFTER_TIME_SIGNATURE: StaffSpaces = StaffSpaces(1.0);
 
-const NUM_STAFF_LINES: u32 = 5;
+/// The height of ending brackets above the top line when nothing extends far
+/// above the staff.
+const DEFAULT_ENDING_HEIGHT: StaffSpaces = StaffSpaces(2.0);
+
+/// The minimum distance from the highest element of the staff to ending
+/// brackets.
+const ENDING_CLEARANCE: StaffSpaces = StaffSpaces(1.0);
+
+/// The height of the baseline of navigation markers above the top line when
+/// nothing extends far above the staff.
+const DEFAULT_NAVIGATION_HEIGHT: StaffSpaces = StaffSpaces(1.5);
+
+/// The minimum distance from the highest element of the staff, including any
+/// ending brackets, to the baseline of navigation markers.
+const NAVIGATION_CLEARANCE: StaffSpaces = StaffSpaces(1.0);
+
+/// The minimum distance from the lowest element of a system to the highest
+/// element of the system below it.
+const SYSTEM_SPACING: StaffSpaces = StaffSpaces(2.0);
+
+/// The number of staff lines of a standard staff.
+const DEFAULT_NUM_LINES: u8 = 5;
 
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct Staff {
+    pub geometry: StaffGeometry,
     pub clef: Option<Clef>,
     pub key_signature: Option<KeySignature>,
     pub time_signature: Option<TimeSignature>,
     pub measures: Vec<Measure>,
+    pub measure_numbering: MeasureNumbering,
+    /// The style of the multi-measure rests which consecutive empty measures
+    /// collapse into, such as in an individual part, or `None` to draw every
+    /// measure, such as in a full score.
+    pub multi_measure_rests: Option<multi_measure_rest::Style>,
+    /// How sustain pedal marks are drawn.
+    pub pedal_style: pedal::Style,
+    /// The metrics of the text font, which lyrics, chord symbols and
+    /// annotations are measured with.
+    pub text_metrics: TextMetrics,
+}
+
+/// The lines and size of a staff.
+///
+/// Staff positions are measured from the bottom line, so the top line of a
+/// staff with `lines` lines is at `lines - 1`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StaffGeometry {
+    /// The number of staff lines, such as 1 for a percussion rhythm staff or 6
+    /// for a guitar tablature staff.
+    pub lines: u8,
+    /// The size of the staff relative to a standard staff, such as 0.75 for a
+    /// cue or ossia staff.
+    ///
+    /// A staff is laid out in its own staff spaces and then scaled around the
+    /// start of its bottom line, so its rendered elements are in the staff
+    /// spaces of a standard staff.
+    pub scale: f64,
+}
+
+impl Default for StaffGeometry {
+    fn default() -> Self {
+        Self {
+            lines: DEFAULT_NUM_LINES,
+            scale: 1.0,
+        }
+    }
+}
+
+impl StaffGeometry {
+    /// Returns the position of the top line.
+    pub fn top(&self) -> StaffSpaces {
+        StaffSpaces(f64::from(self.lines.max(1) - 1))
+    }
+
+    /// Returns the position of the middle of the staff, which is a line on
+    /// staves with an odd number of lines and a space otherwise.
+    pub fn middle(&self) -> StaffSpaces {
+        self.top() / 2.0
+    }
+
+    /// Returns the position of the line which whole rests hang from, which is
+    /// the second line from the top, or the only line of a single-line staff.
+    pub fn whole_rest_line(&self) -> StaffSpaces {
+        if self.lines <= 1 {
+            self.top()
+        } else {
+            self.top() - StaffSpaces(1.0)
+        }
+    }
+
+    /// Returns whether the position is between the bottom and top lines.
+    pub fn contains(&self, y: StaffSpaces) -> bool {
+        y >= StaffSpaces::zero() && y <= self.top()
+    }
+
+    /// Returns the bottom and top of barlines, which extend a space above and
+    /// below a single-line staff.
+    pub fn barline_extent(&self) -> (StaffSpaces, StaffSpaces) {
+        if self.lines <= 1 {
+            (StaffSpaces(-1.0), StaffSpaces(1.0))
+        } else {
+            (StaffSpaces::zero(), self.top())
+        }
+    }
 }
 
 impl Staff {
+    /// Returns the number of each measure, counting from the first number of
+    /// the [`measure_numbering`](Self::measure_numbering).
+    ///
+    /// Unless the first number is given, a first measure which is shorter than
+    /// the time signature is a pickup measure, numbered 0.
+    pub fn measure_numbers(&self) -> Vec<u32> {
+        let first = self.measure_numbering.first.unwrap_or_else(|| {
+            let is_pickup = self.measures.first().is_some_and(|measure| {
+                measure
+                    .time_signature
+                    .as_ref()
+                    .or(self.time_signature.as_ref())
+                    .and_then(TimeSignature::divisions)
+                    .is_some_and(|divisions| {
+                        !measure.has_full_measure_rest() && measure.divisions() < divisions
+                    })
+            });
+
+            if is_pickup {
+                0
+            } else {
+                1
+            }
+        });
+
+        (0..self.measures.len())
+            .map(|index| first + index as u32)
+            .collect()
+    }
+
+    /// Returns the ranges of the indices of the measures which are drawn as
+    /// one, which are the runs of consecutive empty measures when they collapse
+    /// into multi-measure rests, and single measures otherwise.
+    ///
+    /// A run is broken by anything which needs to be seen where it happens,
+    /// such as a barline other than a thin one, a system break, or a key or
+    /// time signature change, which starts a new run.
+    fn bars(&self) -> Vec<Range<usize>> {
+        let mut bars: Vec<Range<usize>> = vec![];
+
+        for (index, measure) in self.measures.iter().enumerate() {
+            let continues_run = self.multi_measure_rests.is_some()
+                && measure.is_empty()
+                && measure.start_barline.is_none()
+                && measure.key_signature.is_none()
+                && measure.time_signature.is_none()
+                && index.checked_sub(1).is_some_and(|previous| {
+                    let previous = &self.measures[previous];
+                    previous.is_empty()
+                        && previous.barline == Barline::Thin
+                        && !previous.system_break
+                });
+
+            match bars.last_mut() {
+                Some(bar) if continues_run => bar.end = index + 1,
+                _ => bars.push(index..index + 1),
+            }
+        }
+
+        bars
+    }
+
+    /// Returns the measure which a run of empty measures collapses into, with
+    /// the barlines and signature changes at its ends and a multi-measure rest.
+    fn multi_measure_rest(&self, bar: &Range<usize>, style: multi_measure_rest::Style) -> Measure {
+        let first = &self.measures[bar.start];
+        let last = &self.measures[bar.end - 1];
+
+        Measure {
+            start_barline: first.start_barline.clone(),
+            key_signature: first.key_signature.clone(),
+            time_signature: first.time_signature.clone(),
+            elements: vec![measure::Element::MultiMeasureRest(MultiMeasureRest {
+                num_measures: bar.len() as u32,
+                style,
+            })],
+            barline: last.barline.clone(),
+            system_break: last.system_break,
+            ..Measure::default()
+        }
+    }
+
+    /// Returns the clef, key signature and time signature at the start of the
+    /// system which starts with the measure at `index`.
+    ///
+    /// The clef and key signature in effect are drawn again, including any
+    /// clef change at the start of the measure, but without the naturals which
+    /// cancelled a previous key signature. The time signature is only drawn if
+    /// it changes at the measure.
+    fn system_header(
+        &self,
+        index: usize,
+    ) -> (Option<Clef>, Option<KeySignature>, Option<TimeSignature>) {
+        let clef = self.measures[..index]
+            .iter()
+            .flat_map(|measure| &measure.elements)
+            .chain(self.measures[index].leading_clefs())
+            .filter_map(|element| match element {
+                measure::Element::Clef(clef) => Some(*clef),
+                _ => None,
+            })
+            .last()
+            .or(self.clef)
+            .map(Clef::full_size);
+
+        let key_signature = self.measures[..=index]
+            .iter()
+            .rev()
+            .find_map(|measure| measure.key_signature.as_ref())
+            .or(self.key_signature.as_ref())
+            .filter(|key_signature| !key_signature.pitches.is_empty())
+            .map(|key_signature| KeySignature {
+                naturals: vec![],
+                ..key_signature.clone()
+            });
+
+        (
+            clef,
+            key_signature,
+            self.measures[index].time_signature.clone(),
+        )
+    }
+
+    /// Renders the staff as systems below each other, which end at the
+    /// measures with a system break.
     pub fn render(&self, metadata: &Metadata) -> Result<Vec<Element<StaffSpaces>>> {
+        let bars = self.bars();
+        let mut systems: Vec<_> = bars
+            .split_inclusive(|bar| self.measures[bar.end - 1].system_break)
+            .collect();
+
+        if systems.is_empty() {
+            systems.push(&[]);
+        }
+
+        let mut elements = vec![];
+        let mut continuing = Continuing::default();
+        // The lowest point of the systems rendered so far.
+        let mut lowest_y = None;
+
+        for system in systems {
+            let system_elements;
+            (system_elements, continuing) = self.render_system(system, continuing, metadata)?;
+
+            let offset = lowest_y.map_or(StaffSpaces::zero(), |lowest_y| {
+                let highest_y = system_elements
+                    .iter()
+                    .map(|element| element.max_y(metadata))
+                    .fold(self.geometry.top(), math::max);
+
+                lowest_y - SYSTEM_SPACING - highest_y
+            });
+
+            let system_lowest_y = system_elements
+                .iter()
+                .map(|element| element.min_y(metadata))
+                .fold(StaffSpaces::zero(), math::min);
+            lowest_y = Some(system_lowest_y + offset);
+
+            let translate = Translate {
+                offset: Coord {
+                    x: StaffSpaces::zero(),
+                    y: offset,
+                },
+            };
+            elements.extend(
+                system_elements
+                    .into_iter()
+                    .map(|element| element.convert(&translate)),
+            );
+        }
+
+        let scale = Scale {
+            origin: Coord {
+                x: StaffSpaces::zero(),
+                y: StaffSpaces::zero(),
+            },
+            size: self.geometry.scale,
+            scales_y: true,
+        };
+
+        Ok(elements
+            .into_iter()
+            .map(|element| element.convert(&scale))
+            .collect())
+    }
+
+    /// Renders the system of the `bars`, where the lines `continuing` from the
+    /// previous system start after the header, and returns the lines which
+    /// continue on the next system.
+    fn render_system(
+        &self,
+        bars: &[Range<usize>],
+        continuing: Continuing,
+        metadata: &Metadata,
+    ) -> Result<(Vec<Element<StaffSpaces>>, Continuing)> {
         let mut renderer = Renderer::new(metadata);
+        renderer.context().set_staff_geometry(self.geometry);
+        renderer
+            .context()
+            .set_text_metrics(Rc::new(self.text_metrics.clone()));
 
         renderer.advance(BEGINNING_OF_STAFF_SPACE);
 
-        if let Some(clef) = &self.clef {
+        let (clef, key_signature, time_signature) = match bars.first() {
+            Some(bar) if bar.start > 0 => self.system_header(bar.start),
+            _ => (
+                self.clef,
+                self.key_signature.clone(),
+                self.time_signature.clone(),
+            ),
+        };
+
+        if let Some(clef) = &clef {
             renderer.render(clef)?;
             renderer.advance(SPACE_AFTER_CLEF);
         }
 
-        if let Some(key_signature) = &self.key_signature {
+        if let Some(key_signature) = &key_signature {
             renderer.render(key_signature)?;
             renderer.advance(SPACE_AFTER_KEY_SIGNATURE);
         }
 
-        if let Some(time_signature) = &self.time_signature {
+        if let Some(time_signature) = &time_signature {
             renderer.render(time_signature)?;
             renderer.advance(SPACE_AFTER_TIME_SIGNATURE);
         }
 
-        for measure in &self.measures {
-            measure.render(&mut renderer)?;
+        let x = renderer.position();
+        renderer.context().resume(continuing, x);
+
+        let mut endings: Vec<(&Ending, StaffSpaces, StaffSpaces, bool, bool)> = vec![];
+        let mut navigation: Vec<(Navigation, StaffSpaces, StaffSpaces)> = vec![];
+
+        let measure_numbers = self.measure_numbers();
+
+        for (index, bar) in bars.iter().enumerate() {
+            let previous = bar.start.checked_sub(1).map(|index| &self.measures[index]);
+            let next = self.measures.get(bar.end);
+            let last = &self.measures[bar.end - 1];
+            let numbers = measure_numbers[bar.start]..=measure_numbers[bar.end - 1];
+
+            let multi_measure_rest = match self.multi_measure_rests {
+                Some(style) if bar.len() > 1 => Some(self.multi_measure_rest(bar, style)),
+                _ => None,
+            };
+            let measure = multi_measure_rest
+                .as_ref()
+                .unwrap_or(&self.measures[bar.start]);
+
+            let num_elements = renderer.num_elements();
+            let start = renderer.position();
+            measure.render(&mut renderer, previous, next)?;
+            let end = renderer.position();
+
+            if self.measure_numbering.display.shows(index, &numbers) {
+                renderer.add_elements(vec![measure_numbering::render(
+                    &numbers,
+                    index,
+                    start,
+                    self.geometry,
+                )]);
+            }
+
+            if self.measure_numbering.grouped {
+                let mut data = vec![("measure", numbers.start().to_string())];
+
+                if numbers.end() != numbers.start() {
+                    data.push(("last-measure", numbers.end().to_string()));
+                }
+
+                renderer.group_elements_from(num_elements, |elements| Group {
+                    elements,
+                    id: None,
+                    data,
+                });
+            }
+
+            navigation.extend(
+                last.navigation
+                    .iter()
+                    .map(|navigation| (*navigation, start, end)),
+            );
+
+            if let Some(ending) = &last.ending {
+                let closed = last.barline.is_end_repeat();
+
+                match endings.last_mut() {
+                    Some((previous, _, previous_end, _, previous_closed))
+                        if *previous == ending && *previous_end == start =>
+                    {
+                        *previous_end = end;
+                        *previous_closed = closed;
+                    }
+                    _ => {
+                        let continued = index == 0
+                            && previous
+                                .is_some_and(|previous| previous.ending.as_ref() == Some(ending));
+
+                        endings.push((ending, start, end, continued, closed));
+                    }
+                }
+            }
+        }
+
+        if !renderer.context().octave_lines().is_empty() {
+            let above = OctaveLines::baseline_above(renderer.highest_y(), self.geometry);
+            let below = OctaveLines::baseline_below(renderer.lowest_y());
+            let end = renderer.position();
+            let octave_lines = renderer
+                .context()
+                .octave_lines()
+                .render(above, below, end, metadata)?;
+            renderer.add_elements(octave_lines);
+        }
+
+        if !renderer.context().chord_symbols().is_empty() {
+            let baseline = ChordSymbols::baseline(renderer.highest_y(), self.geometry);
+            let chord_symbols = renderer.context().chord_symbols().render(
+                baseline,
+                &self.text_metrics,
+                metadata,
+            )?;
+            renderer.add_elements(chord_symbols);
+        }
+
+        if !endings.is_empty() {
+            let default_y = self.geometry.top() + DEFAULT_ENDING_HEIGHT;
+            let y = renderer.highest_y().map_or(default_y, |highest_y| {
+                math::max(default_y, highest_y + ENDING_CLEARANCE)
+            });
+
+            for (ending, start, end, continued, closed) in endings {
+                renderer.add_elements(
+                    ending.render_bracket(start, end, y, continued, closed, metadata),
+                );
+            }
+        }
+
+        if !navigation.is_empty() {
+            let default_y = self.geometry.top() + DEFAULT_NAVIGATION_HEIGHT;
+            let y = renderer.highest_y().map_or(default_y, |highest_y| {
+                math::max(default_y, highest_y + NAVIGATION_CLEARANCE)
+            });
+
+            for (navigation, start, end) in navigation {
+                renderer.add_elements(navigation.render(start, end, y, metadata)?);
+            }
+        }
+
+        if !renderer.context().annotations().is_empty() {
+            let baseline = Annotations::baseline(renderer.highest_y(), self.geometry);
+            let annotations =
+                renderer
+                    .context()
+                    .annotations()
+                    .render(baseline, &self.text_metrics, metadata)?;
+            renderer.add_elements(annotations);
         }
 
-        renderer.add_elements(Self::staff_lines(renderer.position(), metadata));
+        let baseline = Dynamics::baseline(renderer.lowest_y());
+        let end = renderer.position();
+        let dynamics = renderer
+            .context()
+            .dynamics()
+            .render(baseline, end, metadata)?;
+        renderer.add_elements(dynamics);
+
+        let baseline = Lyrics::baseline(renderer.lowest_y());
+        let lyrics = renderer.context().lyrics().render(baseline, metadata);
+        renderer.add_elements(lyrics);
+
+        if !renderer.context().pedals().is_empty() {
+            let baseline = Pedals::baseline(renderer.lowest_y());
+            let pedals =
+                renderer
+                    .context()
+                    .pedals()
+                    .render(baseline, end, self.pedal_style, metadata)?;
+            renderer.add_elements(pedals);
+        }
+
+        renderer.add_elements(self.staff_lines(renderer.position(), metadata));
+
+        let continuing = renderer.context().continuing();
 
-        Ok(renderer.to_elements())
+        Ok((renderer.to_elements(), continuing))
     }
 
-    fn staff_lines(length: StaffSpaces, metadata: &Metadata) -> Vec<Element<StaffSpaces>> {
+    fn staff_lines(&self, length: StaffSpaces, metadata: &Metadata) -> Vec<Element<StaffSpaces>> {
         let staff_line_thickness = metadata.engraving_defaults.staff_line_thickness();
 
-        (0..NUM_STAFF_LINES)
+        (0..self.geometry.lines)
             .map(move |number| {
                 Element::Line(Line {
                     from: Coord {
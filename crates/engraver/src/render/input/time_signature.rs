@@ -1,5 +1,6 @@
 use smufl::{Glyph, Metadata, StaffSpaces};
 
+use super::{duration, Duration};
 use crate::{
     render::{
         context::Context,
@@ -10,113 +11,282 @@ use crate::{
     Result,
 };
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-pub struct TimeSignature {
-    pub numerator: u8,
-    pub denominator: u8,
+/// The space between interchangeable meters which are written side by side.
+const SPACE_BETWEEN_METERS: StaffSpaces = StaffSpaces(1.0);
+
+/// The space between a note denominator and its augmentation dots.
+const NOTE_TO_DOT: StaffSpaces = StaffSpaces(0.2);
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TimeSignature {
+    /// Common time, which is written as C and means 4/4.
+    Common,
+    /// Cut time, which is written as a C with a vertical line through it and
+    /// means 2/2.
+    Cut,
+    /// One or more interchangeable meters, such as 3/4 and 6/8, which are
+    /// written side by side. The first meter gives the length of a measure.
+    Meters(Vec<Meter>),
+}
+
+/// A meter of a time signature, such as 3/4 or 3+2+2/8.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Meter {
+    pub beats: Beats,
+    /// The value of each beat, or `None` for a single-number time signature,
+    /// whose beats are quarter notes.
+    pub unit: Option<Unit>,
+}
+
+/// The number of beats in a measure.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Beats {
+    /// The numbers of beats which are added together, such as `[3, 2, 2]` for
+    /// an additive meter written as 3+2+2, or a single number.
+    Numbers(Vec<u8>),
+    /// An unspecified number of beats, written as X.
+    X,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Unit {
+    /// A number, such as 4 for quarter notes.
+    Number(u8),
+    /// A note, such as a dotted quarter note.
+    Note(Duration),
 }
 
 impl TimeSignature {
-    fn numerator_glyphs(&self) -> Vec<Glyph> {
-        Self::glyphs_for_number(self.numerator)
+    /// Returns a time signature with a single meter, such as 3/4.
+    pub fn new(numerator: u8, denominator: u8) -> Self {
+        Self::Meters(vec![Meter {
+            beats: Beats::Numbers(vec![numerator]),
+            unit: Some(Unit::Number(denominator)),
+        }])
+    }
+
+    /// Returns the numerator and denominator of the fraction of a whole note
+    /// which is the length of a measure, such as `(6, 8)` for 6/8 or `(9, 8)`
+    /// for three dotted quarter notes, or `None` if the number of beats is
+    /// unspecified.
+    pub fn fraction(&self) -> Option<(u32, u32)> {
+        match self {
+            Self::Common => Some((4, 4)),
+            Self::Cut => Some((2, 2)),
+            Self::Meters(meters) => meters.first().and_then(Meter::fraction),
+        }
     }
 
-    fn denominator_glyphs(&self) -> Vec<Glyph> {
-        Self::glyphs_for_number(self.denominator)
+    /// Returns the length of a measure, in
+    /// [`DIVISIONS_PER_QUARTER`](duration::DIVISIONS_PER_QUARTER) divisions of
+    /// a quarter note, or `None` if the number of beats is unspecified.
+    pub fn divisions(&self) -> Option<u32> {
+        let (numerator, denominator) = self.fraction()?;
+
+        Some(duration::Value::Whole.divisions() * numerator / denominator.max(1))
+    }
+
+    /// Returns the lengths of the groups of beats of a measure, in
+    /// [`DIVISIONS_PER_QUARTER`](duration::DIVISIONS_PER_QUARTER) divisions of
+    /// a quarter note, within which eighth notes are beamed together, or
+    /// `None` if the number of beats is unspecified.
+    ///
+    /// The groups of an additive meter such as 3+2+2/8 are its numbers of
+    /// beats. The beats of a compound meter such as 6/8 are grouped in threes,
+    /// and otherwise each beat is a group of its own.
+    pub fn beat_groups(&self) -> Option<Vec<u32>> {
+        let quarter = duration::Value::Quarter.divisions();
+
+        match self {
+            Self::Common => Some(vec![quarter; 4]),
+            Self::Cut => Some(vec![2 * quarter; 2]),
+            Self::Meters(meters) => meters.first().and_then(Meter::beat_groups),
+        }
+    }
+
+    /// Returns the rows of glyphs of the time signature, with their heights.
+    fn rows(&self) -> Vec<Vec<(StaffSpaces, Vec<Glyph>)>> {
+        match self {
+            Self::Common => vec![vec![(StaffSpaces(2.0), vec![Glyph::TimeSigCommon])]],
+            Self::Cut => vec![vec![(StaffSpaces(2.0), vec![Glyph::TimeSigCutCommon])]],
+            Self::Meters(meters) => meters.iter().map(Meter::rows).collect(),
+        }
+    }
+}
+
+impl Meter {
+    fn fraction(&self) -> Option<(u32, u32)> {
+        let Beats::Numbers(numbers) = &self.beats else {
+            return None;
+        };
+        let beats: u32 = numbers.iter().copied().map(u32::from).sum();
+
+        Some(match self.unit {
+            Some(Unit::Number(denominator)) => (beats, u32::from(denominator)),
+            Some(Unit::Note(duration)) => {
+                let whole = duration::Value::Whole.divisions();
+                let divisor = gcd(duration.divisions(), whole);
+
+                (beats * duration.divisions() / divisor, whole / divisor)
+            }
+            None => (beats, 4),
+        })
     }
 
-    fn glyphs_for_number(number: u8) -> Vec<Glyph> {
-        number
-            .to_string()
-            .chars()
-            .map(Self::glyph_for_char)
-            .collect()
+    fn beat_groups(&self) -> Option<Vec<u32>> {
+        let Beats::Numbers(numbers) = &self.beats else {
+            return None;
+        };
+        let unit = match self.unit {
+            Some(Unit::Number(denominator)) => {
+                duration::Value::Whole.divisions() / u32::from(denominator).max(1)
+            }
+            Some(Unit::Note(duration)) => duration.divisions(),
+            None => duration::Value::Quarter.divisions(),
+        };
+
+        Some(match numbers.as_slice() {
+            [number] if unit < duration::Value::Quarter.divisions() && number % 3 == 0 => {
+                vec![3 * unit; usize::from(number / 3)]
+            }
+            [number] => vec![unit; usize::from(*number)],
+            numbers => numbers
+                .iter()
+                .map(|number| u32::from(*number) * unit)
+                .collect(),
+        })
     }
 
-    fn glyph_for_char(c: char) -> Glyph {
-        match c {
-            '0' => Glyph::TimeSig0,
-            '1' => Glyph::TimeSig1,
-            '2' => Glyph::TimeSig2,
-            '3' => Glyph::TimeSig3,
-            '4' => Glyph::TimeSig4,
-            '5' => Glyph::TimeSig5,
-            '6' => Glyph::TimeSig6,
-            '7' => Glyph::TimeSig7,
-            '8' => Glyph::TimeSig8,
-            '9' => Glyph::TimeSig9,
-            _ => unreachable!("There should be no non-digit chars"),
+    fn rows(&self) -> Vec<(StaffSpaces, Vec<Glyph>)> {
+        let beats = match &self.beats {
+            Beats::Numbers(numbers) => numbers
+                .iter()
+                .map(|number| glyphs_for_number(u32::from(*number)))
+                .collect::<Vec<_>>()
+                .join(&Glyph::TimeSigPlus),
+            Beats::X => vec![Glyph::TimeSigX],
+        };
+
+        match self.unit {
+            Some(Unit::Number(denominator)) => vec![
+                (StaffSpaces(3.0), beats),
+                (StaffSpaces(1.0), glyphs_for_number(u32::from(denominator))),
+            ],
+            Some(Unit::Note(duration)) => {
+                let dots = match duration.dots {
+                    None => 0,
+                    Some(duration::Dots::Dot) => 1,
+                    Some(duration::Dots::DoubleDot) => 2,
+                };
+                let mut note = vec![note_glyph(duration.value)];
+                note.extend([Glyph::MetAugmentationDot].repeat(dots));
+
+                vec![(StaffSpaces(3.0), beats), (StaffSpaces(0.5), note)]
+            }
+            None => vec![(StaffSpaces(2.0), beats)],
         }
     }
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Returns the time signature digits of a number.
+pub(crate) fn glyphs_for_number(number: u32) -> Vec<Glyph> {
+    number.to_string().chars().map(glyph_for_char).collect()
+}
 
-    fn render_glyphs(
-        glyphs: &[Glyph],
-        x: StaffSpaces,
-        y: StaffSpaces,
-        mut offset: StaffSpaces,
-        metadata: &Metadata,
-    ) -> Result<Vec<Element<StaffSpaces>>> {
-        let mut elements = Vec::with_capacity(glyphs.len());
+fn glyph_for_char(c: char) -> Glyph {
+    match c {
+        '0' => Glyph::TimeSig0,
+        '1' => Glyph::TimeSig1,
+        '2' => Glyph::TimeSig2,
+        '3' => Glyph::TimeSig3,
+        '4' => Glyph::TimeSig4,
+        '5' => Glyph::TimeSig5,
+        '6' => Glyph::TimeSig6,
+        '7' => Glyph::TimeSig7,
+        '8' => Glyph::TimeSig8,
+        '9' => Glyph::TimeSig9,
+        _ => unreachable!("There should be no non-digit chars"),
+    }
+}
 
-        for glyph in glyphs {
-            let x = x + offset;
+fn note_glyph(value: duration::Value) -> Glyph {
+    match value {
+        duration::Value::Whole => Glyph::MetNoteWhole,
+        duration::Value::Half => Glyph::MetNoteHalfUp,
+        duration::Value::Quarter => Glyph::MetNoteQuarterUp,
+        duration::Value::Eighth => Glyph::MetNote8thUp,
+        duration::Value::Sixteenth => Glyph::MetNote16thUp,
+        duration::Value::ThirtySecond => Glyph::MetNote32ndUp,
+        duration::Value::SixtyFourth => Glyph::MetNote64thUp,
+        duration::Value::OneHundredTwentyEighth => Glyph::MetNote128thUp,
+        duration::Value::TwoHundredFiftySixth => Glyph::MetNote256thUp,
+    }
+}
 
-            elements.push(Element::Symbol(Symbol {
-                origin: Coord { x, y },
-                value: glyph.codepoint(),
-            }));
+/// Returns the offsets of the glyphs of a row from its start, and its width.
+fn layout_row(glyphs: &[Glyph], metadata: &Metadata) -> Result<(Vec<StaffSpaces>, StaffSpaces)> {
+    let mut offsets = Vec::with_capacity(glyphs.len());
+    let mut width = StaffSpaces::zero();
 
-            offset += metadata.width_of(*glyph)?;
+    for glyph in glyphs {
+        if *glyph == Glyph::MetAugmentationDot {
+            width += NOTE_TO_DOT;
         }
 
-        Ok(elements)
+        offsets.push(width);
+        width += metadata.width_of(*glyph)?;
     }
+
+    Ok((offsets, width))
 }
 
 impl Render for TimeSignature {
-    fn render(
-        &self,
-        x: StaffSpaces,
-        _context: &mut Context,
-        metadata: &Metadata,
-    ) -> Result<Output> {
-        let numerator_widths = self
-            .numerator_glyphs()
-            .into_iter()
-            .map(|glyph| metadata.width_of(glyph))
-            .collect::<Result<Vec<_>>>()?;
-        let numerator_width: StaffSpaces = numerator_widths.into_iter().sum();
-        let denominator_widths = self
-            .denominator_glyphs()
-            .into_iter()
-            .map(|glyph| metadata.width_of(glyph))
-            .collect::<Result<Vec<_>>>()?;
-        let denominator_width = denominator_widths.into_iter().sum();
-
-        let width = numerator_width.max(denominator_width);
-
-        let numerator_glyphs = self.numerator_glyphs();
-        let denominator_glyphs = self.denominator_glyphs();
-
-        let mut elements = Vec::with_capacity(numerator_glyphs.len() + denominator_glyphs.len());
-
-        let numerator_offset = (width - numerator_width) / 2.0;
-        elements.append(&mut Self::render_glyphs(
-            &numerator_glyphs,
-            x,
-            StaffSpaces(3.0),
-            numerator_offset,
-            metadata,
-        )?);
-
-        let denominator_offset = (width - denominator_width) / 2.0;
-        elements.append(&mut Self::render_glyphs(
-            &denominator_glyphs,
-            x,
-            StaffSpaces(1.0),
-            denominator_offset,
-            metadata,
-        )?);
+    fn render(&self, x: StaffSpaces, context: &mut Context, metadata: &Metadata) -> Result<Output> {
+        // The rows are positioned for a standard staff, whose middle line is 2
+        // spaces above the bottom line.
+        let y_offset = context.staff_geometry().middle() - StaffSpaces(2.0);
+        let mut elements = vec![];
+        let mut width = StaffSpaces::zero();
+
+        for (index, rows) in self.rows().iter().enumerate() {
+            if index > 0 {
+                width += SPACE_BETWEEN_METERS;
+            }
+
+            let layouts = rows
+                .iter()
+                .map(|(_, glyphs)| layout_row(glyphs, metadata))
+                .collect::<Result<Vec<_>>>()?;
+            let meter_width = layouts
+                .iter()
+                .map(|(_, row_width)| *row_width)
+                .fold(StaffSpaces::zero(), |width, row_width| width.max(row_width));
+
+            for ((y, glyphs), (offsets, row_width)) in rows.iter().zip(layouts) {
+                let start = x + width + (meter_width - row_width) / 2.0;
+
+                elements.extend(glyphs.iter().zip(offsets).map(|(glyph, offset)| {
+                    Element::Symbol(Symbol {
+                        origin: Coord {
+                            x: start + offset,
+                            y: *y + y_offset,
+                        },
+                        value: *glyph,
+                        size: 1.0,
+                    })
+                }));
+            }
+
+            width += meter_width;
+        }
 
         Ok(Output { elements, width })
     }
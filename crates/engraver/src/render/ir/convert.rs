@@ -1,5 +1,69 @@
+use smufl::StaffSpaces;
+
+use super::Coord;
+
 pub trait Convert<T, U> {
     fn convert_x(&self, x: T) -> U;
     fn convert_y(&self, y: T) -> U;
     fn convert_thickness(&self, thickness: T) -> U;
+
+    /// Converts the relative size of a glyph or text, which is unchanged by
+    /// default.
+    fn convert_size(&self, size: f64) -> f64 {
+        size
+    }
+}
+
+/// Scales elements drawn at the normal size around a point, which keeps the
+/// point in place.
+#[derive(Clone, Copy, Debug)]
+pub struct Scale {
+    pub origin: Coord<StaffSpaces>,
+    /// The size of the elements relative to the normal size.
+    pub size: f64,
+    /// Whether y-coordinates are scaled, rather than only x-coordinates.
+    pub scales_y: bool,
+}
+
+impl Convert<StaffSpaces, StaffSpaces> for Scale {
+    fn convert_x(&self, x: StaffSpaces) -> StaffSpaces {
+        self.origin.x + (x - self.origin.x) * self.size
+    }
+
+    fn convert_y(&self, y: StaffSpaces) -> StaffSpaces {
+        if self.scales_y {
+            self.origin.y + (y - self.origin.y) * self.size
+        } else {
+            y
+        }
+    }
+
+    fn convert_thickness(&self, thickness: StaffSpaces) -> StaffSpaces {
+        thickness * self.size
+    }
+
+    fn convert_size(&self, size: f64) -> f64 {
+        size * self.size
+    }
+}
+
+/// Moves elements by an offset, such as a system to its place below the
+/// previous system.
+#[derive(Clone, Copy, Debug)]
+pub struct Translate {
+    pub offset: Coord<StaffSpaces>,
+}
+
+impl Convert<StaffSpaces, StaffSpaces> for Translate {
+    fn convert_x(&self, x: StaffSpaces) -> StaffSpaces {
+        x + self.offset.x
+    }
+
+    fn convert_y(&self, y: StaffSpaces) -> StaffSpaces {
+        y + self.offset.y
+    }
+
+    fn convert_thickness(&self, thickness: StaffSpaces) -> StaffSpaces {
+        thickness
+    }
 }
@@ -1,3 +1,5 @@
+use smufl::{Metadata, StaffSpaces};
+
 use super::{Convert, Group, Line, Polygon, Symbol, Text};
 
 #[derive(Clone, Debug)]
@@ -33,3 +35,29 @@ impl<T> Element<T> {
         }
     }
 }
+
+impl Element<StaffSpaces> {
+    /// Returns the lowest point of the element, including the bounding boxes of
+    /// glyphs and the descenders of text.
+    pub fn min_y(&self, metadata: &Metadata) -> StaffSpaces {
+        match self {
+            Element::Line(line) => line.min_y(),
+            Element::Polygon(polygon) => polygon.min_y(),
+            Element::Symbol(symbol) => symbol.min_y(metadata),
+            Element::Text(text) => text.min_y(),
+            Element::Group(group) => group.min_y(metadata),
+        }
+    }
+
+    /// Returns the highest point of the element, including the bounding boxes
+    /// of glyphs and the ascenders of text.
+    pub fn max_y(&self, metadata: &Metadata) -> StaffSpaces {
+        match self {
+            Element::Line(line) => line.max_y(),
+            Element::Polygon(polygon) => polygon.max_y(),
+            Element::Symbol(symbol) => symbol.max_y(metadata),
+            Element::Text(text) => text.max_y(),
+            Element::Group(group) => group.max_y(metadata),
+        }
+    }
+}
@@ -1,15 +1,22 @@
+use smufl::{Metadata, StaffSpaces};
+
 use super::{Convert, Element};
+use crate::render::math;
 
 #[derive(Clone, Debug)]
 pub struct Group<T> {
     pub elements: Vec<Element<T>>,
     pub id: Option<String>,
+    /// Data about the elements, such as the number of the measure they belong
+    /// to, as pairs of names and values.
+    pub data: Vec<(&'static str, String)>,
 }
 
 impl<T> Group<T> {
     pub fn convert<U>(self, converter: &impl Convert<T, U>) -> Group<U> {
         Group {
             id: self.id,
+            data: self.data,
             elements: self
                 .elements
                 .into_iter()
@@ -29,3 +36,21 @@ impl<T> Group<T> {
             .max_x()
     }
 }
+
+impl Group<StaffSpaces> {
+    pub fn min_y(&self, metadata: &Metadata) -> StaffSpaces {
+        self.elements
+            .iter()
+            .map(|element| element.min_y(metadata))
+            .reduce(math::min)
+            .unwrap()
+    }
+
+    pub fn max_y(&self, metadata: &Metadata) -> StaffSpaces {
+        self.elements
+            .iter()
+            .map(|element| element.max_y(metadata))
+            .reduce(math::max)
+            .unwrap()
+    }
+}
@@ -31,4 +31,18 @@ impl<T> Line<T> {
     {
         math::max(self.from.x, self.to.x)
     }
+
+    pub fn min_y(&self) -> T
+    where
+        T: Copy + PartialOrd,
+    {
+        math::min(self.from.y, self.to.y)
+    }
+
+    pub fn max_y(&self) -> T
+    where
+        T: Copy + PartialOrd,
+    {
+        math::max(self.from.y, self.to.y)
+    }
 }
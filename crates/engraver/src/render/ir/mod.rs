@@ -8,7 +8,7 @@ mod size;
 mod symbol;
 mod text;
 
-pub use convert::Convert;
+pub use convert::{Convert, Scale, Translate};
 pub use coord::Coord;
 pub use element::Element;
 pub use group::Group;
@@ -16,4 +16,4 @@ pub use line::{Line, Linecap};
 pub use polygon::Polygon;
 pub use size::Size;
 pub use symbol::Symbol;
-pub use text::Text;
+pub use text::{FontStyle, FontWeight, Text, TextAnchor};
@@ -76,4 +76,36 @@ impl<T> Polygon<T> {
             .unwrap()
             .x
     }
+
+    pub fn min_y(&self) -> T
+    where
+        T: Copy + PartialOrd,
+    {
+        self.points
+            .iter()
+            .min_by(|point1, point2| {
+                point1
+                    .y
+                    .partial_cmp(&point2.y)
+                    .expect("Points must be comparable")
+            })
+            .unwrap()
+            .y
+    }
+
+    pub fn max_y(&self) -> T
+    where
+        T: Copy + PartialOrd,
+    {
+        self.points
+            .iter()
+            .max_by(|point1, point2| {
+                point1
+                    .y
+                    .partial_cmp(&point2.y)
+                    .expect("Points must be comparable")
+            })
+            .unwrap()
+            .y
+    }
 }
@@ -1,9 +1,14 @@
+use smufl::{Glyph, Metadata, StaffSpaces};
+
 use super::{Convert, Coord};
 
 #[derive(Clone, Debug)]
 pub struct Symbol<T> {
     pub origin: Coord<T>,
-    pub value: char,
+    pub value: Glyph,
+    /// The size of the glyph relative to its standard size, such as 0.6 for
+    /// grace notes.
+    pub size: f64,
 }
 
 impl<T> Symbol<T> {
@@ -11,6 +16,7 @@ impl<T> Symbol<T> {
         Symbol {
             origin: self.origin.convert(converter),
             value: self.value,
+            size: converter.convert_size(self.size),
         }
     }
 
@@ -21,3 +27,27 @@ impl<T> Symbol<T> {
         self.origin.x
     }
 }
+
+impl Symbol<StaffSpaces> {
+    /// Returns the bottom of the glyph's bounding box, or its origin if the
+    /// font has no bounding box for it.
+    pub fn min_y(&self, metadata: &Metadata) -> StaffSpaces {
+        metadata
+            .bounding_boxes
+            .get(self.value)
+            .map_or(self.origin.y, |bounding_box| {
+                self.origin.y + bounding_box.sw.y() * self.size
+            })
+    }
+
+    /// Returns the top of the glyph's bounding box, or its origin if the font
+    /// has no bounding box for it.
+    pub fn max_y(&self, metadata: &Metadata) -> StaffSpaces {
+        metadata
+            .bounding_boxes
+            .get(self.value)
+            .map_or(self.origin.y, |bounding_box| {
+                self.origin.y + bounding_box.ne.y() * self.size
+            })
+    }
+}
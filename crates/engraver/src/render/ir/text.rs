@@ -1,9 +1,41 @@
+use smufl::StaffSpaces;
+
 use super::{Convert, Coord};
+use crate::render::text_metrics::{ASCENDER, DESCENDER};
+
+/// Which end of a text is placed at its origin.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum TextAnchor {
+    #[default]
+    Start,
+    Middle,
+    End,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum FontStyle {
+    #[default]
+    Normal,
+    Italic,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum FontWeight {
+    #[default]
+    Normal,
+    Bold,
+}
 
 #[derive(Clone, Debug)]
 pub struct Text<T> {
     pub origin: Coord<T>,
     pub value: String,
+    pub anchor: TextAnchor,
+    pub style: FontStyle,
+    pub weight: FontWeight,
+    /// The size of the text relative to its standard size, such as 2.5 for the
+    /// title of a score.
+    pub size: f64,
 }
 
 impl<T> Text<T> {
@@ -11,6 +43,10 @@ impl<T> Text<T> {
         Text {
             origin: self.origin.convert(converter),
             value: self.value,
+            anchor: self.anchor,
+            style: self.style,
+            weight: self.weight,
+            size: converter.convert_size(self.size),
         }
     }
 
@@ -21,3 +57,15 @@ impl<T> Text<T> {
         self.origin.x
     }
 }
+
+impl Text<StaffSpaces> {
+    /// Returns the approximate bottom of the text's descenders.
+    pub fn min_y(&self) -> StaffSpaces {
+        self.origin.y - DESCENDER * self.size
+    }
+
+    /// Returns the approximate top of the text's ascenders.
+    pub fn max_y(&self) -> StaffSpaces {
+        self.origin.y + ASCENDER * self.size
+    }
+}
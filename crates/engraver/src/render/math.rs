@@ -6,6 +6,14 @@ pub fn max<T: PartialOrd>(v1: T, v2: T) -> T {
     }
 }
 
+pub fn min<T: PartialOrd>(v1: T, v2: T) -> T {
+    if v1 < v2 {
+        v1
+    } else {
+        v2
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -13,4 +21,10 @@ mod tests {
         assert_eq!(super::max(-1, 1), 1);
         assert_eq!(super::max(1, -1), 1);
     }
+
+    #[test]
+    fn min() {
+        assert_eq!(super::min(-1, 1), -1);
+        assert_eq!(super::min(1, -1), -1);
+    }
 }
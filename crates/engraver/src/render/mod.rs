@@ -7,10 +7,17 @@ mod engraving_defaults_extensions;
 mod glyph_data_extensions;
 mod math;
 mod metadata_extensions;
+mod text_metrics;
+
+use std::ops::Range;
 
 use smufl::{Metadata, StaffSpaces};
 
-use self::{context::Context, ir::Element};
+pub use self::text_metrics::TextMetrics;
+use self::{
+    context::Context,
+    ir::{Coord, Element, Group, Translate},
+};
 use crate::Result;
 
 pub trait Render {
@@ -23,6 +30,15 @@ pub struct Output {
     pub width: StaffSpaces,
 }
 
+/// The elements of a page, which extends from `top` to `bottom` above and
+/// below the bottom line of its staff.
+#[derive(Clone, Debug)]
+pub struct Page {
+    pub elements: Vec<Element<StaffSpaces>>,
+    pub top: StaffSpaces,
+    pub bottom: StaffSpaces,
+}
+
 pub struct Renderer<'m> {
     elements: Vec<Element<StaffSpaces>>,
     position: StaffSpaces,
@@ -46,6 +62,41 @@ impl<'m> Renderer<'m> {
         self
     }
 
+    /// Returns the number of elements rendered so far.
+    pub fn num_elements(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Replaces the elements rendered since there were `num_elements` with a
+    /// group of them, unless there are none.
+    pub fn group_elements_from(
+        &mut self,
+        num_elements: usize,
+        group: impl FnOnce(Vec<Element<StaffSpaces>>) -> Group<StaffSpaces>,
+    ) -> &mut Self {
+        if self.elements.len() > num_elements {
+            let elements = self.elements.split_off(num_elements);
+            self.elements.push(Element::Group(group(elements)));
+        }
+
+        self
+    }
+
+    /// Moves the elements rendered with the indices in `range` by `offset`.
+    pub fn translate_elements(
+        &mut self,
+        range: Range<usize>,
+        offset: Coord<StaffSpaces>,
+    ) -> &mut Self {
+        let translate = Translate { offset };
+
+        for element in &mut self.elements[range] {
+            *element = element.clone().convert(&translate);
+        }
+
+        self
+    }
+
     pub fn context(&mut self) -> &mut Context {
         &mut self.context
     }
@@ -65,10 +116,30 @@ impl<'m> Renderer<'m> {
         self
     }
 
+    pub fn metadata(&self) -> &'m Metadata {
+        self.metadata
+    }
+
     pub fn position(&self) -> StaffSpaces {
         self.position
     }
 
+    /// Returns the lowest point of the elements rendered so far, if any.
+    pub fn lowest_y(&self) -> Option<StaffSpaces> {
+        self.elements
+            .iter()
+            .map(|element| element.min_y(self.metadata))
+            .reduce(math::min)
+    }
+
+    /// Returns the highest point of the elements rendered so far, if any.
+    pub fn highest_y(&self) -> Option<StaffSpaces> {
+        self.elements
+            .iter()
+            .map(|element| element.max_y(self.metadata))
+            .reduce(math::max)
+    }
+
     pub fn to_elements(self) -> Vec<Element<StaffSpaces>> {
         self.elements
     }
@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+
+use smufl::StaffSpaces;
+
+use super::ir::FontWeight;
+
+/// The advance widths of the printable ASCII characters from `' '` to `'~'`, in
+/// thousandths of an em, from the metrics of Times Roman.
+#[rustfmt::skip]
+const TIMES_ROMAN_WIDTHS: [u16; 95] = [
+    250, 333, 408, 500, 500, 833, 778, 180, 333, 333, 500, 564, 250, 333, 250, 278,
+    500, 500, 500, 500, 500, 500, 500, 500, 500, 500, 278, 278, 564, 564, 564, 444,
+    921, 722, 667, 667, 722, 611, 556, 722, 722, 333, 389, 722, 611, 889, 722, 722,
+    556, 722, 667, 556, 611, 722, 722, 944, 722, 722, 611, 333, 278, 333, 469, 500,
+    333, 444, 500, 444, 500, 444, 333, 500, 500, 278, 278, 500, 278, 778, 500, 500,
+    500, 500, 333, 389, 278, 500, 500, 722, 500, 500, 444, 480, 200, 480, 541,
+];
+
+/// The advance widths of the printable ASCII characters from `' '` to `'~'`, in
+/// thousandths of an em, from the metrics of Times Bold.
+#[rustfmt::skip]
+const TIMES_BOLD_WIDTHS: [u16; 95] = [
+    250, 333, 555, 500, 500, 1000, 833, 278, 333, 333, 500, 570, 250, 333, 250, 278,
+    500, 500, 500, 500, 500, 500, 500, 500, 500, 500, 333, 333, 570, 570, 570, 500,
+    930, 722, 667, 722, 722, 667, 611, 778, 778, 389, 500, 778, 667, 944, 722, 778,
+    611, 778, 722, 556, 667, 722, 722, 1000, 722, 722, 667, 333, 278, 333, 581, 500,
+    333, 500, 556, 444, 556, 444, 333, 500, 556, 278, 333, 556, 278, 833, 556, 500,
+    556, 556, 444, 389, 333, 556, 500, 722, 500, 500, 444, 394, 220, 394, 520,
+];
+
+/// The letters without their diacritics of the letters from `'À'` to `'ÿ'`,
+/// where `'_'` stands for letters which are not accented forms of another.
+const LATIN_1_BASE_LETTERS: &str =
+    "AAAAAA_CEEEEIIIIDNOOOOO_OUUUUY__aaaaaa_ceeeeiiiidnooooo_ouuuuy_y";
+
+/// The width of the characters of East Asian scripts, which are as wide as
+/// they are high, in ems.
+const WIDE_CHARACTER_WIDTH: f64 = 1.0;
+
+/// The size of text, which is one staff space as in
+/// [`svg::Options`](crate::svg::Options).
+const TEXT_SIZE: StaffSpaces = StaffSpaces(1.0);
+
+/// The approximate height of ascenders above the baseline, from the metrics of
+/// Times Roman, which keeps other elements clear of text.
+pub const ASCENDER: StaffSpaces = StaffSpaces(0.683);
+
+/// The approximate depth of descenders below the baseline, from the metrics of
+/// Times Roman, which keeps other elements clear of text.
+pub const DESCENDER: StaffSpaces = StaffSpaces(0.217);
+
+/// The metrics of the text font, which text is measured with when it is laid
+/// out.
+///
+/// The text font is only chosen by the output format, such as with
+/// [`svg::Options::text_font_family`](crate::svg::Options::text_font_family),
+/// and may not be installed where the staff is rendered, so its metrics are
+/// given along with the staff. The default metrics are those of Times Roman and
+/// Times Bold, which are close to those of the serif fonts recommended by SMuFL
+/// fonts for text.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextMetrics {
+    /// The advance widths of the characters of regular text, in ems.
+    pub widths: HashMap<char, f64>,
+    /// The advance widths of the characters of bold text, in ems, where the
+    /// regular widths are used for characters without a bold width.
+    pub bold_widths: HashMap<char, f64>,
+    /// The width of characters without an advance width of their own, in ems.
+    ///
+    /// Accented Latin letters are measured as the letters without their
+    /// accents, combining diacritics have no width, and the characters of East
+    /// Asian scripts are one em wide, before falling back to this width.
+    pub default_width: f64,
+    /// The height of capital letters above the baseline, in ems.
+    pub cap_height: f64,
+    /// The depth of descenders below the baseline, in ems.
+    pub descender: f64,
+}
+
+impl Default for TextMetrics {
+    fn default() -> Self {
+        let widths = |thousandths: [u16; 95]| {
+            (' '..='~')
+                .zip(thousandths)
+                .map(|(character, width)| (character, f64::from(width) / 1000.0))
+                .collect()
+        };
+
+        Self {
+            widths: widths(TIMES_ROMAN_WIDTHS),
+            bold_widths: widths(TIMES_BOLD_WIDTHS),
+            default_width: 0.5,
+            cap_height: 0.662,
+            descender: DESCENDER.0,
+        }
+    }
+}
+
+impl TextMetrics {
+    /// Returns the width of a text of the relative `size` and the `weight`.
+    pub fn width_of(&self, text: &str, size: f64, weight: FontWeight) -> StaffSpaces {
+        let ems: f64 = text
+            .chars()
+            .map(|character| self.width_of_character(character, weight))
+            .sum();
+
+        TEXT_SIZE * size * ems
+    }
+
+    /// Returns the height of capital letters of the relative `size` above the
+    /// baseline.
+    pub fn cap_height(&self, size: f64) -> StaffSpaces {
+        TEXT_SIZE * size * self.cap_height
+    }
+
+    /// Returns the depth of descenders of the relative `size` below the
+    /// baseline.
+    pub fn descender(&self, size: f64) -> StaffSpaces {
+        TEXT_SIZE * size * self.descender
+    }
+
+    fn width_of_character(&self, character: char, weight: FontWeight) -> f64 {
+        let width = |character| match weight {
+            FontWeight::Normal => self.widths.get(&character),
+            FontWeight::Bold => self
+                .bold_widths
+                .get(&character)
+                .or_else(|| self.widths.get(&character)),
+        };
+
+        width(character)
+            .or_else(|| base_letter(character).and_then(width))
+            .copied()
+            .unwrap_or_else(|| {
+                if is_combining_diacritic(character) {
+                    0.0
+                } else if is_wide(character) {
+                    WIDE_CHARACTER_WIDTH
+                } else {
+                    self.default_width
+                }
+            })
+    }
+}
+
+/// Returns the letter without its diacritics of an accented Latin-1 letter,
+/// such as `'e'` for `'é'`.
+fn base_letter(character: char) -> Option<char> {
+    let index = (character as usize).checked_sub('À' as usize)?;
+
+    LATIN_1_BASE_LETTERS
+        .chars()
+        .nth(index)
+        .filter(|letter| *letter != '_')
+}
+
+fn is_combining_diacritic(character: char) -> bool {
+    matches!(character, '\u{300}'..='\u{36f}')
+}
+
+/// Returns whether the character is from an East Asian script, such as Chinese
+/// characters, kana or Hangul, or is a fullwidth form.
+fn is_wide(character: char) -> bool {
+    matches!(
+        character,
+        '\u{1100}'..='\u{115f}'
+            | '\u{2e80}'..='\u{a4cf}'
+            | '\u{ac00}'..='\u{d7a3}'
+            | '\u{f900}'..='\u{faff}'
+            | '\u{fe30}'..='\u{fe4f}'
+            | '\u{ff00}'..='\u{ff60}'
+            | '\u{ffe0}'..='\u{ffe6}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TextMetrics;
+    use crate::render::ir::FontWeight;
+
+    #[test]
+    fn width_of() {
+        let metrics = TextMetrics::default();
+        let assert_width = |text, size, weight, expected: f64| {
+            let width = metrics.width_of(text, size, weight).0;
+            assert!((width - expected).abs() < 1e-9, "{text:?} is {width} wide");
+        };
+
+        assert_width("", 1.0, FontWeight::Normal, 0.0);
+        assert_width("mi", 1.0, FontWeight::Normal, 1.056);
+        assert_width("mi", 2.0, FontWeight::Normal, 2.112);
+        assert_width("mi", 1.0, FontWeight::Bold, 1.111);
+        assert_width("é", 1.0, FontWeight::Normal, 0.444);
+        assert_width("e\u{301}", 1.0, FontWeight::Normal, 0.444);
+        assert_width("æ", 1.0, FontWeight::Normal, 0.5);
+        assert_width("夜", 1.0, FontWeight::Normal, 1.0);
+
+        let metrics = TextMetrics {
+            default_width: 0.6,
+            ..TextMetrics::default()
+        };
+        assert_eq!(metrics.width_of("ſ", 1.0, FontWeight::Bold).0, 0.6);
+    }
+}
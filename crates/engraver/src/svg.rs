@@ -1,16 +1,33 @@
 use smufl::StaffSpaces;
 
-use crate::render::ir::{Convert, Element, Group, Line, Linecap, Polygon, Symbol, Text};
+use crate::render::{
+    ir::{
+        Convert, Element, FontStyle, FontWeight, Group, Line, Linecap, Polygon, Symbol, Text,
+        TextAnchor,
+    },
+    Page,
+};
 
 const SYMBOL_CLASS_NAME: &str = "symbol";
 const TEXT_CLASS_NAME: &str = "text";
 
-const RATIO: f64 = 10.0;
+/// The font size of the document and its symbols, in staff spaces, which is
+/// the em of SMuFL fonts.
+const FONT_SIZE: f64 = 4.0;
+
+/// The font size of text, in staff spaces.
+const TEXT_FONT_SIZE: f64 = 1.0;
+
+/// The extent of documents of a staff above and below its bottom line.
+const DEFAULT_TOP: StaffSpaces = StaffSpaces(7.5);
+const DEFAULT_BOTTOM: StaffSpaces = StaffSpaces(-2.5);
 
 #[derive(Debug)]
 pub struct Options {
     pub symbol_font_name: String,
     pub text_font_family: Vec<String>,
+    /// The number of pixels in a staff space of a standard staff, which
+    /// rendered staves are already scaled to.
     pub staff_space_to_pixel_ratio: f64,
 }
 
@@ -37,22 +54,56 @@ pub fn elements_to_svg_document(
     elements: Vec<Element<StaffSpaces>>,
     options: &Options,
 ) -> svg::Document {
+    // The staff lines, which start at the left edge, of staves rendered as
+    // several systems extend the document down to the last system.
+    let last_system = elements
+        .iter()
+        .filter_map(|element| match element {
+            Element::Line(line) if line.from.x == StaffSpaces::zero() => Some(line.min_y()),
+            _ => None,
+        })
+        .fold(
+            StaffSpaces::zero(),
+            |lowest, y| if y < lowest { y } else { lowest },
+        );
+
+    page_to_svg_document(
+        Page {
+            elements,
+            top: DEFAULT_TOP,
+            bottom: last_system + DEFAULT_BOTTOM,
+        },
+        options,
+    )
+}
+
+/// Returns a document as high as the page, such as a rendered
+/// [`Score`](crate::render::input::Score) with its header and footer.
+pub fn page_to_svg_document(page: Page, options: &Options) -> svg::Document {
+    let Page {
+        elements,
+        top,
+        bottom,
+    } = page;
+
     let width = elements
         .iter()
         .map(|element| element.max_x())
         .max_by(|x1, x2| x1.partial_cmp(x2).expect("StaffSpaces must be orderable"))
         .unwrap_or(StaffSpaces::zero());
-    let height = StaffSpaces(10.0);
+    let height = top - bottom;
+
+    let ratio = options.staff_space_to_pixel_ratio;
 
     let document = svg::Document::new()
-        .set("width", width.0 * RATIO)
-        .set("height", height.0 * RATIO)
+        .set("width", width.0 * ratio)
+        .set("height", height.0 * ratio)
         .add(style_element(options));
 
-    let staff_origin = StaffSpaces(2.5) + StaffSpaces(5.0);
+    let staff_origin = top;
 
     let converter = Converter {
-        ratio: RATIO,
+        ratio,
         staff_origin,
     };
 
@@ -70,11 +121,14 @@ fn add_element_to_node<N: svg::node::Node>(element: Element<f64>, mut node: N) -
         Element::Polygon(polygon) => node.append::<svg::node::element::Polygon>(polygon.into()),
         Element::Symbol(symbol) => node.append::<svg::node::element::Text>(symbol.into()),
         Element::Text(text) => node.append::<svg::node::element::Text>(text.into()),
-        Element::Group(Group { id, elements }) => {
+        Element::Group(Group { id, data, elements }) => {
             let mut group = svg::node::element::Group::new();
             if let Some(id) = id {
                 group = group.set("id", id);
             }
+            for (name, value) in data {
+                group = group.set(format!("data-{name}"), value);
+            }
             for element in elements {
                 group = add_element_to_node(element, group);
             }
@@ -85,7 +139,8 @@ fn add_element_to_node<N: svg::node::Node>(element: Element<f64>, mut node: N) -
 }
 
 fn style_element(options: &Options) -> svg::node::element::Style {
-    let font_size = RATIO * 4.0;
+    let font_size = options.staff_space_to_pixel_ratio * FONT_SIZE;
+    let text_font_size = options.staff_space_to_pixel_ratio * TEXT_FONT_SIZE;
     let symbol_font_family = &options.symbol_font_name;
     let text_font_family = &options.text_font_family.join(", ");
 
@@ -101,7 +156,7 @@ svg {{
 }}
 .{TEXT_CLASS_NAME} {{
   font-family: "{text_font_family}";
-  font-size: 10px;
+  font-size: {text_font_size}px;
 }}
 "#
     );
@@ -142,20 +197,50 @@ impl From<Polygon<f64>> for svg::node::element::Polygon {
 
 impl From<Symbol<f64>> for svg::node::element::Text {
     fn from(symbol: Symbol<f64>) -> Self {
-        svg::node::element::Text::new()
+        let text = svg::node::element::Text::new()
             .set("x", symbol.origin.x)
             .set("y", symbol.origin.y)
-            .set("class", SYMBOL_CLASS_NAME)
-            .add(svg::node::Text::new(symbol.value))
+            .set("class", SYMBOL_CLASS_NAME);
+
+        let text = if symbol.size == 1.0 {
+            text
+        } else {
+            text.set("font-size", format!("{}em", symbol.size))
+        };
+
+        text.add(svg::node::Text::new(symbol.value.codepoint()))
     }
 }
 
 impl From<Text<f64>> for svg::node::element::Text {
     fn from(text: Text<f64>) -> Self {
-        svg::node::element::Text::new()
+        let anchor = match text.anchor {
+            TextAnchor::Start => "start",
+            TextAnchor::Middle => "middle",
+            TextAnchor::End => "end",
+        };
+
+        let mut element = svg::node::element::Text::new()
             .set("x", text.origin.x)
             .set("y", text.origin.y)
             .set("class", TEXT_CLASS_NAME)
-            .add(svg::node::Text::new(text.value))
+            .set("text-anchor", anchor);
+
+        if text.style == FontStyle::Italic {
+            element = element.set("font-style", "italic");
+        }
+
+        if text.weight == FontWeight::Bold {
+            element = element.set("font-weight", "bold");
+        }
+
+        if text.size != 1.0 {
+            // An em is the font size of the document, and the size of the
+            // class is only overridden by a style.
+            let size = text.size * TEXT_FONT_SIZE / FONT_SIZE;
+            element = element.set("style", format!("font-size: {size}em"));
+        }
+
+        element.add(svg::node::Text::new(text.value))
     }
 }
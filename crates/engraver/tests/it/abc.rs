@@ -0,0 +1,154 @@
+use engraver::model::Clef;
+use engraver_parser::{parse_abc, parse_staff};
+
+fn tune(header: &str, body: &str) -> String {
+    format!("X:1\nT:Test\n{header}\n{body}")
+}
+
+#[test]
+fn header_fields() {
+    assert_eq!(
+        parse_abc(&tune("M:3/4\nL:1/4\nK:D", "D E F |]")).unwrap(),
+        parse_staff("## 3/4 d e f# ||").unwrap()
+    );
+    assert_eq!(
+        parse_abc(&tune("M:C|\nL:1/2\nK:Bb clef=bass", "B, E, |")).unwrap(),
+        parse_staff("bass bb C| bb3[2] eb |").unwrap()
+    );
+}
+
+#[test]
+fn modes() {
+    let fifths = |key| {
+        parse_abc(&tune(&format!("K:{key}"), "C |"))
+            .unwrap()
+            .key_signature
+            .map(|key_signature| key_signature.fifths())
+            .unwrap_or_default()
+    };
+
+    assert_eq!(fifths("Am"), 0);
+    assert_eq!(fifths("F#m"), 3);
+    assert_eq!(fifths("D dorian"), 0);
+    assert_eq!(fifths("G Mix"), 0);
+    assert_eq!(fifths("Ebmaj"), -3);
+    assert_eq!(fifths("none"), 0);
+}
+
+#[test]
+fn default_unit_length() {
+    assert_eq!(
+        parse_abc(&tune("M:2/4\nK:C", "C2 D4 |")).unwrap(),
+        parse_staff("2/4 c[8] d[4] |").unwrap()
+    );
+    assert_eq!(
+        parse_abc(&tune("M:6/8\nK:C", "C3 |")).unwrap(),
+        parse_staff("6/8 c[4.] |").unwrap()
+    );
+}
+
+#[test]
+fn octaves() {
+    assert_eq!(
+        parse_abc(&tune("L:1/4\nK:C", "C, C c c' c'' |")).unwrap(),
+        parse_staff("c3 c4 c5 c6 c7 |").unwrap()
+    );
+}
+
+#[test]
+fn lengths() {
+    assert_eq!(
+        parse_abc(&tune("L:1/2\nK:C", "C2 C/2 C/ C// C3/2 C3/4 C7/4 |")).unwrap(),
+        parse_staff("c[1] c[4] c[4] c[8] c[2.] c[4.] c[2..] |").unwrap()
+    );
+    assert!(parse_abc(&tune("L:1/8\nK:C", "C5 |")).is_err());
+}
+
+#[test]
+fn accidentals() {
+    assert_eq!(
+        parse_abc(&tune("L:1/4\nK:G", "F ^C C =F | F _B __B ^^c |")).unwrap(),
+        parse_staff("# f# c# c# f | f# bb bbb cx5 |").unwrap()
+    );
+}
+
+#[test]
+fn chords_and_rests() {
+    assert_eq!(
+        parse_abc(&tune("L:1/4\nK:C", "[CEG]2 z [C2E2]/2 z/ |")).unwrap(),
+        parse_staff("{c e g}[2] r[4] {c e}[4] r[8] |").unwrap()
+    );
+}
+
+#[test]
+fn grace_notes() {
+    assert_eq!(
+        parse_abc(&tune("L:1/4\nK:D", "{/g}A {fg}A2 |")).unwrap(),
+        parse_staff(r"## \acciaccatura g5 a4 \appoggiatura (f#5[16] g) a4[2] |").unwrap()
+    );
+}
+
+#[test]
+fn barlines() {
+    let staff = parse_abc(&tune("L:1/4\nK:C", "C | D || E |] F")).unwrap();
+
+    assert_eq!(staff.measures.len(), 4);
+    assert_eq!(staff, parse_staff("c | d |[double] e || f |").unwrap());
+}
+
+#[test]
+fn repeats() {
+    assert_eq!(
+        parse_abc(&tune("L:1/4\nK:C", "|: C D :: E F :| G |: A :|")).unwrap(),
+        parse_staff("|: c d :|: e f :| g |: a :|").unwrap()
+    );
+}
+
+#[test]
+fn comments_and_body_fields() {
+    assert_eq!(
+        parse_abc("X:1 % first tune\nK:C clef=alto\nC2 D2 | % comment\nW:words\nE4 |]")
+            .unwrap()
+            .clef,
+        Clef::Alto
+    );
+}
+
+#[test]
+fn errors() {
+    assert!(parse_abc("X:1\nT:No key\nC D E |").is_err());
+    assert!(parse_abc(&tune("L:1/3\nK:C", "C |")).is_err());
+    assert!(parse_abc(&tune("K:H", "C |")).is_err());
+    assert!(parse_abc(&tune("K:C", "C ? D |")).is_err());
+}
+
+#[test]
+fn clef_without_key() {
+    assert_eq!(
+        parse_abc(&tune("L:1/4\nK: clef=bass", "C, D, |")).unwrap(),
+        parse_staff("bass c3 d3 |").unwrap()
+    );
+    assert_eq!(
+        parse_abc(&tune("L:1/4\nK:clef=alto", "C |")).unwrap(),
+        parse_staff("alto c |").unwrap()
+    );
+}
+
+#[test]
+fn fields_in_body() {
+    assert_eq!(
+        parse_abc(&tune(
+            "M:2/4\nL:1/4\nK:G",
+            "G F |\nK:D\nM:3/4\nL:1/8\nF2 C2 G2 |\nK:clef=bass\nF,4 z2 | [K:C][M:2/4] [L:1/2]F, |]"
+        ))
+        .unwrap(),
+        parse_staff(
+            "# 2/4 g f# | \\key ## \\time 3/4 f#[4] c# g | bass f#3[2] r[4] | \
+             \\key 0 \\time 2/4 f3[2] ||"
+        )
+        .unwrap()
+    );
+    assert!(parse_abc(&tune("L:1/4\nK:C", "C [K:D] D |")).is_err());
+    assert!(parse_abc(&tune("L:1/4\nK:C", "C\nM:3/4\nD |")).is_err());
+    assert!(parse_abc(&tune("L:1/4\nK:C", "C |\nM:none\nD |")).is_err());
+}
@@ -0,0 +1,124 @@
+use engraver::model::{
+    annotation::{MetronomeMark, Tempo},
+    duration, Annotation, Duration,
+};
+use engraver_parser::{parse_abc, parse_lilypond, parse_staff};
+
+#[test]
+fn tempo_markings() {
+    assert_staff_snapshot!(
+        "treble 4/4 \\tempo \"Allegro\" [4] = 120 c d e f | \\tempo \"Adagio\" g[1] | \\tempo [4.] = 60 a[2] b[2] ||"
+    );
+}
+
+#[test]
+fn expressions() {
+    assert_staff_snapshot!("treble \\text \"dolce\" c d e f | g a \\text \"rit.\" b c5 ||");
+}
+
+#[test]
+fn rehearsal_marks() {
+    assert_staff_snapshot!("treble \\mark \"A\" c d e f | \\mark \"B\" g a b c5 ||");
+}
+
+#[test]
+fn close_annotations_are_stacked() {
+    assert_staff_snapshot!(
+        "treble \\mark \"A\" \\tempo \"Allegro moderato\" [4] = 112 c[8] \\text \"dolce\" d e f g[2] ||"
+    );
+}
+
+#[test]
+fn parse_annotations() {
+    let staff = parse_staff(
+        "\\tempo \"Allegro\" [4] = 120 c \\tempo [4.] = 60 d \\tempo \"Adagio\" e \
+         \\text \"dolce\" \\mark \"A\" f |",
+    )
+    .unwrap();
+    let annotations: Vec<_> = staff.measures[0]
+        .elements
+        .iter()
+        .filter_map(|element| match element {
+            engraver::model::measure::Element::Annotation(annotation) => Some(annotation.clone()),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(
+        annotations,
+        [
+            Annotation::Tempo(Tempo {
+                text: Some("Allegro".to_owned()),
+                metronome_mark: Some(MetronomeMark {
+                    beat: Duration {
+                        value: duration::Value::Quarter,
+                        dots: None,
+                    },
+                    per_minute: 120,
+                }),
+            }),
+            Annotation::Tempo(Tempo {
+                text: None,
+                metronome_mark: Some(MetronomeMark {
+                    beat: Duration {
+                        value: duration::Value::Quarter,
+                        dots: Some(duration::Dots::Dot),
+                    },
+                    per_minute: 60,
+                }),
+            }),
+            Annotation::Tempo(Tempo {
+                text: Some("Adagio".to_owned()),
+                metronome_mark: None,
+            }),
+            Annotation::Expression("dolce".to_owned()),
+            Annotation::RehearsalMark("A".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn quarters_per_minute() {
+    let metronome_mark = |value, dots, per_minute| MetronomeMark {
+        beat: Duration { value, dots },
+        per_minute,
+    };
+
+    assert_eq!(
+        metronome_mark(duration::Value::Quarter, None, 120).quarters_per_minute(),
+        120
+    );
+    assert_eq!(
+        metronome_mark(duration::Value::Quarter, Some(duration::Dots::Dot), 60)
+            .quarters_per_minute(),
+        90
+    );
+    assert_eq!(
+        metronome_mark(duration::Value::Half, None, 50).quarters_per_minute(),
+        100
+    );
+}
+
+#[test]
+fn lilypond_tempo_and_marks() {
+    assert_eq!(
+        parse_lilypond(
+            r#"{ \tempo "Allegro" 4 = 120 \mark \default c'4 d' e' f' |
+                 \mark \default \tempo 4. = 60 g'1 | \mark "X" a'1 | \mark \default b'1 }"#
+        )
+        .unwrap(),
+        parse_staff(
+            "\\tempo \"Allegro\" [4] = 120 \\mark \"A\" c d e f | \
+             \\mark \"B\" \\tempo [4.] = 60 g[1] | \\mark \"X\" a[1] | \\mark \"C\" b[1] |"
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn abc_tempo_and_annotations() {
+    assert_eq!(
+        parse_abc("X:1\nQ:\"Allegro\" 3/8=60\nL:1/4\nK:C\n\"^dolce\"C D |]").unwrap(),
+        parse_staff("\\tempo \"Allegro\" [4.] = 60 \\text \"dolce\" c d ||").unwrap()
+    );
+}
@@ -0,0 +1,56 @@
+use engraver::model::{measure, Articulation};
+use engraver_parser::{parse_lilypond, parse_staff};
+
+#[test]
+fn stems_up() {
+    assert_staff_snapshot!("c-. d-! e-- f-' g-> a-^ |");
+}
+
+#[test]
+fn stems_down() {
+    assert_staff_snapshot!("c5-. d-! e-- f-' g-> a-^ |");
+}
+
+#[test]
+fn in_spaces_within_staff() {
+    assert_staff_snapshot!("f-. a-. c5-. e5-. |");
+}
+
+#[test]
+fn stacked() {
+    assert_staff_snapshot!("c-^-. e5->-- g[8]-.-> a[8]-.-> |");
+}
+
+#[test]
+fn chords() {
+    assert_staff_snapshot!("{c e g}-. {a c5 e}-> {c4 e5}[2]-- |");
+}
+
+#[test]
+fn whole_notes() {
+    assert_staff_snapshot!("c[1]-> | c5[1]-> |");
+}
+
+#[test]
+fn parse_articulations() {
+    let staff = parse_staff("c-.-> {e g}[2]-- r |").unwrap();
+    let elements = &staff.measures[0].elements;
+
+    assert!(matches!(
+        &elements[0],
+        measure::Element::Note(note)
+            if note.articulations == [Articulation::Staccato, Articulation::Accent]
+    ));
+    assert!(matches!(
+        &elements[1],
+        measure::Element::Chord(chord) if chord.articulations == [Articulation::Tenuto]
+    ));
+}
+
+#[test]
+fn parse_lilypond_articulations() {
+    assert_eq!(
+        parse_lilypond(r"\relative c' { c4-.-> <e g>2^- <e g> f_. g-! a-_ b-^ }").unwrap(),
+        parse_staff("c-.-> {e g}[2]-- {e g} f[2]-. g-! a---. b-^ |").unwrap()
+    );
+}
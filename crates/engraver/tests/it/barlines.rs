@@ -1,13 +1,17 @@
 use color_eyre::eyre::Result;
-use engraver::render::input::{Barline, Measure, Staff};
+use engraver::{
+    model::{Barline, Ending},
+    render::input::{self, Measure, Staff},
+};
+use engraver_parser::parse_staff;
 use strum::IntoEnumIterator;
 
 #[test]
 fn all() -> Result<()> {
-    let measures = Barline::iter()
+    let measures = input::Barline::iter()
         .map(|barline| Measure {
-            elements: vec![],
             barline,
+            ..Default::default()
         })
         .collect();
     let staff = Staff {
@@ -19,3 +23,104 @@ fn all() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn start_repeat_at_start_of_staff() {
+    assert_staff_snapshot!("treble # 3/4 |: g a b | c5[2.] :|");
+}
+
+#[test]
+fn start_repeat_replaces_thin_barline() {
+    assert_staff_snapshot!("c d e f |: g a b c5 :|: c5 b a g :| f[1] ||");
+}
+
+#[test]
+fn endings() {
+    assert_staff_snapshot!("|: c d e f | 1. g a b c5 | 1. b4 a g f :| 2. c[1] ||");
+}
+
+#[test]
+fn ending_across_system_break() {
+    assert_staff_snapshot!("|: c d e f | 1. g a b c5 \\break | 1. b4 a g f :| 2. c[1] ||");
+}
+
+#[test]
+fn ending_for_several_times() {
+    assert_staff_snapshot!("|: c[1] | 1,2. d[1] :| 3. e[1] ||");
+}
+
+#[test]
+fn ending_above_high_notes() {
+    assert_staff_snapshot!("|: c[1] | 1. a5[1] :| 2. c6[1] ||");
+}
+
+#[test]
+fn parse_barlines() {
+    let staff = parse_staff(
+        "c | c || c :| c :|: c |[double] c |[heavy] c |[dashed] c |[dotted] c |[invisible]",
+    )
+    .unwrap();
+    let barlines: Vec<_> = staff
+        .measures
+        .iter()
+        .map(|measure| measure.barline.clone())
+        .collect();
+
+    assert_eq!(
+        barlines,
+        [
+            Barline::Thin,
+            Barline::Final,
+            Barline::EndRepeat,
+            Barline::EndStartRepeat,
+            Barline::Double,
+            Barline::Heavy,
+            Barline::Dashed,
+            Barline::Dotted,
+            Barline::Invisible,
+        ]
+    );
+}
+
+#[test]
+fn parse_start_repeats() {
+    let staff = parse_staff("|: c | d |: e :| f ||").unwrap();
+    let start_barlines: Vec<_> = staff
+        .measures
+        .iter()
+        .map(|measure| measure.start_barline.clone())
+        .collect();
+
+    assert_eq!(
+        start_barlines,
+        [
+            Some(Barline::StartRepeat),
+            None,
+            Some(Barline::StartRepeat),
+            None
+        ]
+    );
+    assert_eq!(staff.measures[1].barline, Barline::Thin);
+}
+
+#[test]
+fn parse_endings() {
+    let staff = parse_staff("c | 1. d :| 1,2. e :| 3. f ||").unwrap();
+    let endings: Vec<_> = staff
+        .measures
+        .iter()
+        .map(|measure| measure.ending.clone())
+        .collect();
+
+    assert_eq!(
+        endings,
+        [
+            None,
+            Some(Ending { numbers: vec![1] }),
+            Some(Ending {
+                numbers: vec![1, 2]
+            }),
+            Some(Ending { numbers: vec![3] }),
+        ]
+    );
+}
@@ -0,0 +1,179 @@
+use engraver::model::{
+    chord_symbol::{Change, Degree, Extension, Quality, Root},
+    measure::Element,
+    Alteration, ChordSymbol, Step,
+};
+use engraver_parser::{parse_abc, parse_chord_symbol, parse_staff};
+
+#[test]
+fn lead_sheet() {
+    assert_staff_snapshot!(
+        "treble 4/4 \"Dm7\" d f \"G7\" g b | \"Cmaj7\" c[1] | \"A7\" a[2] \"D\" d[2] ||"
+    );
+}
+
+#[test]
+fn alterations_and_bass() {
+    assert_staff_snapshot!(
+        "treble \"F#m7b5/E\" e[2] \"Bb13(#11)\" f[2] | \"C7(b9,#11)\" c[2] \"G7sus4\" g[2] ||"
+    );
+}
+
+#[test]
+fn dense_symbols_widen_measure() {
+    assert_staff_snapshot!(
+        "treble \"Cmaj7\" c[8] \"Dm7\" d \"Em7\" e \"Fmaj7\" f \"G7\" g \"Am7\" a \"Bø\" b \"C6/9\" c5 ||"
+    );
+}
+
+fn root(step: Step, alteration: Alteration) -> Root {
+    Root { step, alteration }
+}
+
+fn extension(degree: u8, is_major: bool) -> Extension {
+    Extension { degree, is_major }
+}
+
+fn degree(degree: u8, change: Change) -> Degree {
+    Degree { degree, change }
+}
+
+#[test]
+fn parse_chord_symbols() {
+    let cases = [
+        (
+            "Cmaj7",
+            ChordSymbol {
+                root: root(Step::C, Alteration::Natural),
+                quality: Quality::Major,
+                extensions: vec![extension(7, true)],
+                alterations: vec![],
+                bass: None,
+            },
+        ),
+        (
+            "F#m7b5/E",
+            ChordSymbol {
+                root: root(Step::F, Alteration::Sharp),
+                quality: Quality::Minor,
+                extensions: vec![extension(7, false)],
+                alterations: vec![degree(5, Change::Flat)],
+                bass: Some(root(Step::E, Alteration::Natural)),
+            },
+        ),
+        (
+            "Bb13(#11)",
+            ChordSymbol {
+                root: root(Step::B, Alteration::Flat),
+                quality: Quality::Major,
+                extensions: vec![extension(13, false)],
+                alterations: vec![degree(11, Change::Sharp)],
+                bass: None,
+            },
+        ),
+        (
+            "C6/9",
+            ChordSymbol {
+                root: root(Step::C, Alteration::Natural),
+                quality: Quality::Major,
+                extensions: vec![extension(6, false), extension(9, false)],
+                alterations: vec![],
+                bass: None,
+            },
+        ),
+        (
+            "Dm(maj7)",
+            ChordSymbol {
+                root: root(Step::D, Alteration::Natural),
+                quality: Quality::Minor,
+                extensions: vec![extension(7, true)],
+                alterations: vec![],
+                bass: None,
+            },
+        ),
+        (
+            "G7sus4",
+            ChordSymbol {
+                root: root(Step::G, Alteration::Natural),
+                quality: Quality::SuspendedFourth,
+                extensions: vec![extension(7, false)],
+                alterations: vec![],
+                bass: None,
+            },
+        ),
+        (
+            "Cø",
+            ChordSymbol {
+                root: root(Step::C, Alteration::Natural),
+                quality: Quality::HalfDiminished,
+                extensions: vec![extension(7, false)],
+                alterations: vec![],
+                bass: None,
+            },
+        ),
+        (
+            "E5",
+            ChordSymbol {
+                root: root(Step::E, Alteration::Natural),
+                quality: Quality::Power,
+                extensions: vec![],
+                alterations: vec![],
+                bass: None,
+            },
+        ),
+        (
+            "C7(b9,#11)",
+            ChordSymbol {
+                root: root(Step::C, Alteration::Natural),
+                quality: Quality::Major,
+                extensions: vec![extension(7, false)],
+                alterations: vec![degree(9, Change::Flat), degree(11, Change::Sharp)],
+                bass: None,
+            },
+        ),
+        (
+            "Ebadd9",
+            ChordSymbol {
+                root: root(Step::E, Alteration::Flat),
+                quality: Quality::Major,
+                extensions: vec![],
+                alterations: vec![degree(9, Change::Add)],
+                bass: None,
+            },
+        ),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(parse_chord_symbol(input).unwrap(), expected, "{input}");
+    }
+}
+
+#[test]
+fn invalid_chord_symbols() {
+    assert!(parse_chord_symbol("H7").is_err());
+    assert!(parse_chord_symbol("Cmaj7 ").is_err());
+}
+
+#[test]
+fn chord_symbols_are_elements() {
+    let staff = parse_staff("\"Am\" a c5 \"E7\" e[2] |").unwrap();
+
+    assert!(matches!(
+        &staff.measures[0].elements[..],
+        [
+            Element::ChordSymbol(_),
+            Element::Note(_),
+            Element::Note(_),
+            Element::ChordSymbol(_),
+            Element::Note(_),
+        ]
+    ));
+}
+
+#[test]
+fn abc_chord_symbols() {
+    assert_eq!(
+        parse_abc("X:1\nL:1/4\nK:C\n\"Am7\"A c \"D7\"d2 |]").unwrap(),
+        parse_staff("\"Am7\" a4 c5 \"D7\" d5[2] ||").unwrap()
+    );
+}
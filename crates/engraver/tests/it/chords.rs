@@ -11,6 +11,7 @@ fn input_panics_with_no_notes() {
             value: input::duration::Value::Whole,
             dots: None,
         },
+        vec![],
         None,
         None,
     );
@@ -28,6 +29,7 @@ fn input_panics_with_one_note() {
             value: input::duration::Value::Whole,
             dots: None,
         },
+        vec![],
         None,
         None,
     );
@@ -42,6 +44,7 @@ fn model_panics_with_no_notes() {
             value: model::duration::Value::Whole,
             dots: None,
         },
+        vec![],
         None,
     );
 }
@@ -59,6 +62,7 @@ fn model_panics_with_one_note() {
             value: model::duration::Value::Whole,
             dots: None,
         },
+        vec![],
         None,
     );
 }
@@ -140,6 +144,7 @@ fn chord_with_id() -> Result<()> {
                     value: input::duration::Value::Whole,
                     dots: None,
                 },
+                vec![],
                 None,
                 Some("test_chord".to_string()),
             ))],
@@ -1,3 +1,12 @@
+use engraver::{
+    model::Clef,
+    render::{input, ir::Element},
+};
+use engraver_parser::{parse_abc, parse_lilypond, parse_staff};
+use smufl::{Glyph, StaffSpaces};
+
+use crate::helpers;
+
 #[test]
 fn treble() {
     assert_staff_snapshot!("treble c |");
@@ -17,3 +26,168 @@ fn tenor() {
 fn bass() {
     assert_staff_snapshot!("bass c |");
 }
+
+#[test]
+fn treble_8vb() {
+    assert_staff_snapshot!("treble_8 c |");
+}
+
+#[test]
+fn treble_8va() {
+    assert_staff_snapshot!("treble^8 c |");
+}
+
+#[test]
+fn french_violin() {
+    assert_staff_snapshot!("french c |");
+}
+
+#[test]
+fn soprano() {
+    assert_staff_snapshot!("soprano c |");
+}
+
+#[test]
+fn mezzo_soprano() {
+    assert_staff_snapshot!("mezzosoprano c |");
+}
+
+#[test]
+fn baritone() {
+    assert_staff_snapshot!("baritone c |");
+}
+
+#[test]
+fn bass_8vb() {
+    assert_staff_snapshot!("bass_8 c |");
+}
+
+#[test]
+fn percussion() {
+    assert_staff_snapshot!("percussion c |");
+}
+
+#[test]
+fn tab() {
+    assert_staff_snapshot!("tab c |");
+}
+
+#[test]
+fn middle_c_positions() {
+    let y = |input| {
+        let staff: input::Staff = parse_staff(input).unwrap().into();
+        staff.measures[0]
+            .elements
+            .iter()
+            .find_map(|element| match element {
+                input::measure::Element::Note(note) => Some(note.y),
+                _ => None,
+            })
+            .unwrap()
+    };
+
+    assert_eq!(y("treble_8 c |"), StaffSpaces(-1.0));
+    assert_eq!(y("french c |"), StaffSpaces(-2.0));
+    assert_eq!(y("soprano c |"), StaffSpaces(0.0));
+    assert_eq!(y("mezzosoprano c |"), StaffSpaces(1.0));
+    assert_eq!(y("baritone c |"), StaffSpaces(4.0));
+    assert_eq!(y("bass_8 c |"), StaffSpaces(5.0));
+}
+
+#[test]
+fn change_within_measure() {
+    assert_staff_snapshot!("treble c d bass c3 d3 |");
+}
+
+#[test]
+fn change_at_start_of_measure() {
+    assert_staff_snapshot!("treble c d e f | bass c3 d3 e3 f3 | treble alto c d e f ||");
+}
+
+#[test]
+fn change_at_system_break() {
+    assert_staff_snapshot!("treble c d e f \\break | bass c3 d3 e3 f3 ||");
+}
+
+#[test]
+fn courtesy_clef_at_system_break() {
+    let staff: input::Staff = parse_staff("treble c d e f \\break | bass c3 d3 e3 f3 ||")
+        .unwrap()
+        .into();
+    let elements = staff.render(helpers::metadata()).unwrap();
+
+    let symbol = |glyph| {
+        elements
+            .iter()
+            .find_map(|element| match element {
+                Element::Symbol(symbol) if symbol.value == glyph => Some(symbol.origin),
+                _ => None,
+            })
+            .unwrap()
+    };
+    let treble = symbol(Glyph::GClef);
+    let courtesy = symbol(Glyph::FClefChange);
+    let bass = symbol(Glyph::FClef);
+
+    // The courtesy clef ends the first system, and the bass clef starts the
+    // second system below it.
+    assert_eq!(courtesy.y - treble.y, StaffSpaces(2.0));
+    assert!(courtesy.x > treble.x);
+    assert_eq!(bass.x, treble.x);
+    assert!(bass.y < treble.y - StaffSpaces(4.0));
+
+    let num_staff_lines = elements
+        .iter()
+        .filter(
+            |element| matches!(element, Element::Line(line) if line.from.x == StaffSpaces::zero()),
+        )
+        .count();
+    assert_eq!(num_staff_lines, 10);
+}
+
+#[test]
+fn change_positions_following_pitches() {
+    let staff: input::Staff = parse_staff("c bass c | c ||").unwrap().into();
+    let positions: Vec<_> = staff
+        .measures
+        .iter()
+        .flat_map(|measure| &measure.elements)
+        .filter_map(|element| match element {
+            input::measure::Element::Note(note) => Some(note.y),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(
+        positions,
+        [StaffSpaces(-1.0), StaffSpaces(5.0), StaffSpaces(5.0)]
+    );
+}
+
+#[test]
+fn parse_lilypond_octave_clef() {
+    assert_eq!(
+        parse_lilypond(r#"{ \clef "treble_8" c d | \clef bass_8 c, }"#).unwrap(),
+        parse_staff("treble_8 c d | bass_8 c3 |").unwrap()
+    );
+}
+
+#[test]
+fn parse_abc_clefs() {
+    assert_eq!(
+        parse_abc("K:C clef=treble-8\nC |").unwrap().clef,
+        Clef::Treble8vb
+    );
+    assert_eq!(
+        parse_abc("K:C clef=perc\nC |").unwrap().clef,
+        Clef::Percussion
+    );
+}
+
+#[test]
+fn parse_lilypond_clef_change() {
+    assert_eq!(
+        parse_lilypond(r"{ c' d' \clef bass c d | \clef treble e' }").unwrap(),
+        parse_staff("c d bass c3 d3 | treble e4 |").unwrap()
+    );
+}
@@ -0,0 +1,59 @@
+use engraver::model::{hairpin, measure, Dynamic, Hairpin};
+use engraver_parser::{parse_lilypond, parse_staff};
+
+#[test]
+fn dynamics() {
+    assert_staff_snapshot!("\\pp c \\mp d \\mf e \\ff f | \\sfz g \\fp a \\rfz b \\n r |");
+}
+
+#[test]
+fn crescendo() {
+    assert_staff_snapshot!("\\p \\< c d e \\f f |");
+}
+
+#[test]
+fn diminuendo() {
+    assert_staff_snapshot!("\\f \\> c d e \\! f |");
+}
+
+#[test]
+fn hairpin_across_barline() {
+    assert_staff_snapshot!("\\< c d e f | g a b \\ff c5 ||");
+}
+
+#[test]
+fn hairpin_without_end() {
+    assert_staff_snapshot!("c d \\> e f ||");
+}
+
+#[test]
+fn common_baseline_below_low_notes() {
+    assert_staff_snapshot!("\\p c d \\< g3 a3 | \\f c[1] ||");
+}
+
+#[test]
+fn parse_dynamics() {
+    let staff = parse_staff("\\pp c \\< d \\sffz e \\> f \\! g |").unwrap();
+    let elements = &staff.measures[0].elements;
+
+    assert_eq!(elements[0], measure::Element::Dynamic(Dynamic::Pp));
+    assert_eq!(
+        elements[2],
+        measure::Element::Hairpin(Hairpin::Start(hairpin::Kind::Crescendo))
+    );
+    assert_eq!(elements[4], measure::Element::Dynamic(Dynamic::Sffz));
+    assert_eq!(
+        elements[6],
+        measure::Element::Hairpin(Hairpin::Start(hairpin::Kind::Diminuendo))
+    );
+    assert_eq!(elements[8], measure::Element::Hairpin(Hairpin::End));
+    assert_eq!(elements.len(), 10);
+}
+
+#[test]
+fn parse_lilypond_dynamics() {
+    assert_eq!(
+        parse_lilypond(r"\relative c' { c4\p\< d e-. f | g\f\> a b c\! }").unwrap(),
+        parse_staff("\\p \\< c d e-. f | \\f \\> g a b \\! c5 |").unwrap()
+    );
+}
@@ -0,0 +1,41 @@
+use engraver::model::{duration, grace, measure::Element};
+use engraver_parser::parse_staff;
+
+#[test]
+fn acciaccatura() {
+    assert_staff_snapshot!("treble c \\acciaccatura d c e \\acciaccatura f# g |");
+}
+
+#[test]
+fn appoggiaturas() {
+    assert_staff_snapshot!("treble c \\appoggiatura (d[16] e f) g[2] \\appoggiatura b a |");
+}
+
+#[test]
+fn at_start_of_measure() {
+    assert_staff_snapshot!("treble \\acciaccatura (a3[16] b) c4[2] c | \\appoggiatura d5 c[1] ||");
+}
+
+#[test]
+fn parse_grace_notes() {
+    let staff = parse_staff("c[2] \\appoggiatura (d e[16]) c |").unwrap();
+    let elements = &staff.measures[0].elements;
+
+    let Element::Grace(grace) = &elements[1] else {
+        panic!("expected grace notes, got {:?}", elements[1]);
+    };
+    assert_eq!(grace.kind, grace::Kind::Appoggiatura);
+    assert_eq!(
+        grace
+            .notes
+            .iter()
+            .map(|note| note.duration.value)
+            .collect::<Vec<_>>(),
+        [duration::Value::Eighth, duration::Value::Sixteenth]
+    );
+
+    let Element::Note(note) = &elements[2] else {
+        panic!("expected a note, got {:?}", elements[2]);
+    };
+    assert_eq!(note.duration.value, duration::Value::Half);
+}
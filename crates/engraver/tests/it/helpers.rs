@@ -6,10 +6,16 @@ use std::{
 };
 
 use color_eyre::{eyre::Context, Result};
-use engraver::{render::input::Staff, svg};
+use engraver::{
+    render::input::{Score, Staff},
+    svg,
+};
 use once_cell::sync::OnceCell;
 use smufl::Metadata;
 
+/// The number of pixels in a staff space of a standard staff in snapshots.
+const STAFF_SPACE_TO_PIXEL_RATIO: f64 = 10.0;
+
 macro_rules! function_name {
     () => {{
         fn f() {}
@@ -47,7 +53,18 @@ macro_rules! assert_staff_snapshot {
     }};
 }
 
-fn metadata() -> &'static Metadata {
+#[macro_export]
+macro_rules! assert_score_snapshot {
+    ($score:ident) => {{
+        if let $crate::helpers::SnapshotResult::Different { expected, actual } =
+            $crate::helpers::_assert_score_snapshot($score, function_name!()).unwrap()
+        {
+            panic!("snapshots do not match\n\nExpected: {expected}\nActual: {actual}",);
+        }
+    }};
+}
+
+pub fn metadata() -> &'static Metadata {
     static INSTANCE: OnceCell<Metadata> = OnceCell::new();
     INSTANCE.get_or_init(|| {
         let mut font_metadata_path = root_path();
@@ -120,14 +137,33 @@ pub fn _assert_staff_snapshot(staff: Staff, name: &str) -> Result<SnapshotResult
         .render(metadata)
         .wrap_err(format!("Failed to render '{}' snapshot", name))?;
 
-    let options = svg::Options {
+    let contents = svg::elements_to_svg_document(elements, &svg_options()).to_string();
+
+    assert_snapshot(contents, name)
+}
+
+pub fn _assert_score_snapshot(score: Score, name: &str) -> Result<SnapshotResult> {
+    let metadata = metadata();
+    let page = score
+        .render(metadata)
+        .wrap_err(format!("Failed to render '{}' snapshot", name))?;
+
+    let contents = svg::page_to_svg_document(page, &svg_options()).to_string();
+
+    assert_snapshot(contents, name)
+}
+
+fn svg_options() -> svg::Options {
+    let metadata = metadata();
+
+    svg::Options {
         symbol_font_name: metadata.font_name.clone(),
         text_font_family: metadata.engraving_defaults.text_font_family.clone(),
-        staff_space_to_pixel_ratio: 15.0,
-    };
-
-    let contents = svg::elements_to_svg_document(elements, &options).to_string();
+        staff_space_to_pixel_ratio: STAFF_SPACE_TO_PIXEL_RATIO,
+    }
+}
 
+fn assert_snapshot(contents: String, name: &str) -> Result<SnapshotResult> {
     let path = snapshot_path(name)?;
 
     match (should_overwrite_snapshots(), fs::read_to_string(&path)) {
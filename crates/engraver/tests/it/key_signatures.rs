@@ -1,3 +1,12 @@
+use engraver::{
+    model::{key_signature::Cancellation, Barline, Staff},
+    render::{input, ir::Element},
+};
+use engraver_parser::{parse_lilypond, parse_staff};
+use smufl::Glyph;
+
+use crate::helpers;
+
 #[test]
 fn treble_clef_sharps() {
     assert_staff_snapshot!("####### |");
@@ -37,3 +46,120 @@ fn bass_clef_sharps() {
 fn bass_clef_flats() {
     assert_staff_snapshot!("bass bbbbbbb |");
 }
+
+#[test]
+fn change_from_sharps_to_flats() {
+    assert_staff_snapshot!("#### c d e f | \\key b c d e f ||");
+}
+
+#[test]
+fn change_to_no_sharps_or_flats() {
+    assert_staff_snapshot!("bbb c d e f | \\key 0 c d e f ||");
+}
+
+#[test]
+fn cancellation_conventions() {
+    let naturals = |input: &'static str, key_cancellation| {
+        let staff = Staff {
+            key_cancellation,
+            ..parse_staff(input).unwrap()
+        };
+        let staff: input::Staff = staff.into();
+
+        staff.measures[1]
+            .key_signature
+            .as_ref()
+            .map_or(0, |key_signature| key_signature.naturals.len())
+    };
+
+    let to_two_sharps = "#### c | \\key ## c ||";
+    let to_no_sharps = "#### c | \\key 0 c ||";
+
+    assert_eq!(naturals(to_two_sharps, Cancellation::Removed), 2);
+    assert_eq!(naturals(to_no_sharps, Cancellation::Removed), 4);
+    assert_eq!(naturals("#### c | \\key b c ||", Cancellation::Removed), 4);
+    assert_eq!(naturals(to_two_sharps, Cancellation::OpenKeyOnly), 0);
+    assert_eq!(naturals(to_no_sharps, Cancellation::OpenKeyOnly), 4);
+    assert_eq!(naturals(to_no_sharps, Cancellation::Never), 0);
+}
+
+#[test]
+fn change_is_preceded_by_double_barline() {
+    let staff: input::Staff = parse_staff("c | \\key # c :| \\key b c ||").unwrap().into();
+    let barlines: Vec<_> = staff
+        .measures
+        .iter()
+        .map(|measure| measure.barline.clone())
+        .collect();
+
+    assert_eq!(
+        barlines,
+        [Barline::Double, Barline::EndRepeat, Barline::Final]
+    );
+}
+
+#[test]
+fn change_stops_accidentals_carrying_over() {
+    let accidentals = |input| {
+        let staff: input::Staff = parse_staff(input).unwrap().into();
+        staff.measures[1]
+            .elements
+            .iter()
+            .filter_map(|element| match element {
+                input::measure::Element::Note(note) => Some(note.accidental),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+    let natural = Some(input::Accidental::Natural);
+
+    // The sharps of the previous measure are cancelled in the same key.
+    assert_eq!(accidentals("c# f# | c f ||"), [natural, natural]);
+    assert_eq!(accidentals("## c# f# | c f ||"), [natural, natural]);
+
+    // After a key change, only the new key signature is cancelled.
+    assert_eq!(accidentals("c# f# | \\key # c f ||"), [None, natural]);
+    assert_eq!(accidentals("## c# f# | \\key 0 c f ||"), [None, None]);
+}
+
+#[test]
+fn change_at_system_break() {
+    assert_staff_snapshot!("## c d e f \\break | \\key b \\time 3/4 c d e ||");
+}
+
+#[test]
+fn courtesy_signatures_at_system_break() {
+    let staff: input::Staff = parse_staff("c d e f \\break | \\key ## \\time 3/4 c d e ||")
+        .unwrap()
+        .into();
+    let elements = staff.render(helpers::metadata()).unwrap();
+
+    let origins = |glyph| {
+        elements
+            .iter()
+            .filter_map(|element| match element {
+                Element::Symbol(symbol) if symbol.value == glyph => Some(symbol.origin),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+    let sharps = origins(Glyph::AccidentalSharp);
+    let threes = origins(Glyph::TimeSig3);
+
+    // The signatures are drawn at the end of the first system, and again at
+    // the start of the second system below it.
+    assert_eq!(sharps.len(), 4);
+    assert_eq!(threes.len(), 2);
+    assert!(sharps[0].x > sharps[2].x);
+    assert!(threes[0].x > threes[1].x);
+    assert_eq!(sharps[0].y - sharps[2].y, threes[0].y - threes[1].y);
+    assert!(sharps[0].y > sharps[2].y);
+}
+
+#[test]
+fn parse_lilypond_key_change() {
+    assert_eq!(
+        parse_lilypond(r"{ \key d \major d' e' | \key f \major f' g' }").unwrap(),
+        parse_staff("## d e | \\key b f g |").unwrap()
+    );
+}
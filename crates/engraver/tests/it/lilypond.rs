@@ -0,0 +1,131 @@
+use engraver::model::Clef;
+use engraver_parser::{parse_lilypond, parse_staff};
+
+#[test]
+fn absolute_pitches() {
+    assert_eq!(
+        parse_lilypond("{ c c' c'' c, fis' bes' eeses' aisis' ees' as' }").unwrap(),
+        parse_staff("c3 c4 c5 c2 f#4 bb ebb ax eb ab |").unwrap()
+    );
+}
+
+#[test]
+fn relative_pitches() {
+    assert_eq!(
+        parse_lilypond(r"\relative c' { c f b e, g' c,, | d' }").unwrap(),
+        parse_staff("c4 f b e g5 c4 | d5 |").unwrap()
+    );
+    assert_eq!(
+        parse_lilypond(r"\relative { c'' b }").unwrap(),
+        parse_staff("c5 b4 |").unwrap()
+    );
+}
+
+#[test]
+fn relative_chords() {
+    assert_eq!(
+        parse_lilypond(r"\relative c' { <c e g c> e }").unwrap(),
+        parse_staff("{c4 e g c5} e4 |").unwrap()
+    );
+}
+
+#[test]
+fn durations() {
+    assert_eq!(
+        parse_lilypond("{ c'2 d' e'8. f'32 g'2.. r64 r <c' e'>1 }").unwrap(),
+        parse_staff("c4[2] d e[8.] f[32] g[2..] r[64] r {c e}[1] |").unwrap()
+    );
+}
+
+#[test]
+fn full_measure_rests() {
+    assert_eq!(
+        parse_lilypond(r"{ \time 3/4 R2. | c'4 }").unwrap(),
+        parse_staff("3/4 R | c4[4] |").unwrap()
+    );
+}
+
+#[test]
+fn commands() {
+    assert_eq!(
+        parse_lilypond(
+            r#"\version "2.24.0"
+               \relative c {
+                 \clef bass \key bes \major \time 3/4 % comment
+                 bes4 c d | ees2. \bar "|."
+               }"#
+        )
+        .unwrap(),
+        parse_staff("bass bb 3/4 bb2 c3 d | eb[2.] ||").unwrap()
+    );
+    assert_eq!(
+        parse_lilypond(r#"{ \clef "alto" \key a \minor c }"#)
+            .unwrap()
+            .clef,
+        Clef::Alto
+    );
+    assert_eq!(
+        parse_lilypond(r"{ \key fis \minor c }")
+            .unwrap()
+            .key_signature
+            .map(|key_signature| key_signature.fifths()),
+        Some(3)
+    );
+}
+
+#[test]
+fn grace_notes() {
+    assert_eq!(
+        parse_lilypond(
+            r"\relative c' { c \acciaccatura d8 c4 \appoggiatura { e16 f } g4 \grace a8 g4 }"
+        )
+        .unwrap(),
+        parse_staff(r"c4 \acciaccatura d c \appoggiatura (e[16] f) g \appoggiatura a g |").unwrap()
+    );
+}
+
+#[test]
+fn ties_and_slurs_are_errors() {
+    let error = |input| parse_lilypond(input).unwrap_err().to_string();
+
+    assert_eq!(
+        error(r"\relative c' { c2~ c4 d | e }"),
+        "LilyPond ties are not supported"
+    );
+    assert_eq!(
+        error(r"\relative c' { c2 c4( d) | e }"),
+        "LilyPond slurs are not supported"
+    );
+    assert_eq!(
+        error(r"\relative c' { c2 c4 d | e~ e }"),
+        "LilyPond ties are not supported"
+    );
+}
+
+#[test]
+fn bars() {
+    assert_eq!(
+        parse_lilypond(r#"{ c' | d' \bar "||" e' | \bar "|." }"#).unwrap(),
+        parse_staff("c | d |[double] e ||").unwrap()
+    );
+    assert_eq!(
+        parse_lilypond(r#"{ c' \bar ":|." d' \bar ":..:" e' \bar "!" f' \bar "" }"#).unwrap(),
+        parse_staff("c :| d :|: e |[dashed] f |[invisible]").unwrap()
+    );
+}
+
+#[test]
+fn system_breaks() {
+    assert_eq!(
+        parse_lilypond(r"{ c' d' | \break e' f' \break g' | a' }").unwrap(),
+        parse_staff("c d \\break | e f g \\break | a |").unwrap()
+    );
+}
+
+#[test]
+fn errors() {
+    assert!(parse_lilypond("c d e").is_err());
+    assert!(parse_lilypond(r"{ c \time 3/4 d }").is_err());
+    assert!(parse_lilypond("{ c3 }").is_err());
+    assert!(parse_lilypond(r"{ \key gisis \major c }").is_err());
+}
@@ -0,0 +1,155 @@
+use engraver::{
+    model::{
+        lyric::{Connector, Syllable},
+        Lyric,
+    },
+    render::{
+        input::Staff,
+        ir::{Element, Group},
+        TextMetrics,
+    },
+};
+use engraver_parser::{parse_lilypond, parse_staff};
+use smufl::{Glyph, StaffSpaces};
+
+use crate::helpers;
+
+#[test]
+fn single_verse() {
+    assert_staff_snapshot!("treble c c g g \\lyrics \"Twin -- kle, twin -- kle,\" | a a g[2] \\lyrics \"lit -- tle star,\" ||");
+}
+
+#[test]
+fn verses() {
+    assert_staff_snapshot!(
+        "treble c d e c \\lyrics \"Frè -- re Jac -- ques,\" \\lyrics \"Are you sleep -- ing,\" ||"
+    );
+}
+
+#[test]
+fn melisma() {
+    assert_staff_snapshot!("treble g[8] a b c5 d[2] \\lyrics \"Glo -- _ _ _ ri\" | c5[4] b a g \\lyrics \"a __ _ _ _\" ||");
+}
+
+#[test]
+fn long_words_widen_measure() {
+    assert_staff_snapshot!("treble c[8] d e f g a b c5 \\lyrics \"in -- com -- pre -- hen -- si -- bil -- i -- ty\" ||");
+}
+
+fn syllable(text: &str, connector: Option<Connector>) -> Lyric {
+    Lyric::Syllable(Syllable {
+        text: text.to_owned(),
+        connector,
+    })
+}
+
+#[test]
+fn parse_lyrics() {
+    let staff = parse_staff("c d e f \\lyrics \"Ky -- ri e __ _\" \\lyrics \"Oh\" |").unwrap();
+
+    assert_eq!(
+        staff.measures[0].lyrics,
+        [
+            vec![
+                syllable("Ky", Some(Connector::Hyphen)),
+                syllable("ri", None),
+                syllable("e", Some(Connector::Extender)),
+                Lyric::Melisma,
+            ],
+            vec![syllable("Oh", None)],
+        ]
+    );
+}
+
+#[test]
+fn lilypond_verses_are_split_into_measures() {
+    assert_eq!(
+        parse_lilypond(
+            r"\relative c' { c d e r | <c e>1 }
+              \addlyrics { Ky -- ri e __ _ }
+              \addlyrics { Oh }"
+        )
+        .unwrap(),
+        parse_staff(
+            "c d e r \\lyrics \"Ky -- ri e __\" \\lyrics \"Oh\" | \
+             {c e}[1] \\lyrics \"_\" \\lyrics \"\" |"
+        )
+        .unwrap()
+    );
+}
+
+/// Returns the elements, with the elements of groups in place of the groups.
+fn flatten(elements: Vec<Element<StaffSpaces>>) -> Vec<Element<StaffSpaces>> {
+    elements
+        .into_iter()
+        .flat_map(|element| match element {
+            Element::Group(Group { elements, .. }) => flatten(elements),
+            element => vec![element],
+        })
+        .collect()
+}
+
+#[test]
+fn below_the_descenders_of_dynamics() {
+    let metadata = helpers::metadata();
+    let staff: Staff = parse_staff("\\f c4 \\p d e f \\lyrics \"Sing a song now\" ||")
+        .unwrap()
+        .into();
+    let elements = flatten(staff.render(metadata).unwrap());
+
+    let lowest_dynamic = elements
+        .iter()
+        .filter(|element| {
+            matches!(
+                element,
+                Element::Symbol(symbol)
+                    if matches!(symbol.value, Glyph::DynamicForte | Glyph::DynamicPiano)
+            )
+        })
+        .map(|element| element.min_y(metadata))
+        .reduce(|lowest, y| if y < lowest { y } else { lowest })
+        .unwrap();
+    let highest_lyric = elements
+        .iter()
+        .filter(|element| matches!(element, Element::Text(_)))
+        .map(|element| element.max_y(metadata))
+        .reduce(|highest, y| if y > highest { y } else { highest })
+        .unwrap();
+
+    assert!(highest_lyric < lowest_dynamic);
+}
+
+#[test]
+fn measured_with_text_metrics() {
+    let metadata = helpers::metadata();
+    let staff: Staff = parse_staff("c d \\lyrics \"Won -- der\" ||")
+        .unwrap()
+        .into();
+    let second_note = |staff: &Staff| {
+        flatten(staff.render(metadata).unwrap())
+            .iter()
+            .filter_map(|element| match element {
+                Element::Symbol(symbol) if symbol.value == Glyph::NoteheadBlack => {
+                    Some(symbol.origin.x)
+                }
+                _ => None,
+            })
+            .nth(1)
+            .unwrap()
+    };
+
+    let default_metrics = TextMetrics::default();
+    let wide = Staff {
+        text_metrics: TextMetrics {
+            widths: default_metrics
+                .widths
+                .iter()
+                .map(|(character, width)| (*character, width * 3.0))
+                .collect(),
+            ..default_metrics
+        },
+        ..staff.clone()
+    };
+
+    assert!(second_note(&wide) > second_note(&staff));
+}
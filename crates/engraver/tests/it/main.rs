@@ -1,11 +1,29 @@
 #[macro_use]
 mod helpers;
 
+mod abc;
 mod accidentals;
+mod annotations;
+mod articulations;
 mod barlines;
+mod chord_symbols;
 mod chords;
 mod clefs;
+mod dynamics;
+mod graces;
 mod key_signatures;
+mod lilypond;
+mod lyrics;
+mod measure_numbers;
+mod midi;
+mod multi_measure_rests;
+mod musicxml;
+mod navigation;
 mod notes;
+mod octave_lines;
+mod ornaments;
+mod pedals;
 mod rests;
+mod scores;
+mod staves;
 mod time_signatures;
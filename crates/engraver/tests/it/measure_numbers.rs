@@ -0,0 +1,122 @@
+use engraver::{
+    render::{
+        input::{
+            measure_numbering::{Display, MeasureNumbering},
+            Staff,
+        },
+        ir::Element,
+    },
+    svg,
+};
+use engraver_parser::parse_staff;
+
+use crate::helpers;
+
+fn staff(input: &'static str, measure_numbering: MeasureNumbering) -> Staff {
+    Staff {
+        measure_numbering,
+        ..parse_staff(input).unwrap().into()
+    }
+}
+
+#[test]
+fn every_two_measures() {
+    let staff = staff(
+        "treble 4/4 c[1] | d[1] | e[1] | f[1] | g[1] ||",
+        MeasureNumbering {
+            display: Display::Every(2),
+            ..MeasureNumbering::default()
+        },
+    );
+    assert_staff_snapshot!(staff);
+}
+
+#[test]
+fn start_of_system() {
+    let staff = staff(
+        "treble 3/4 c d e | f[2.] ||",
+        MeasureNumbering {
+            display: Display::System,
+            first: Some(17),
+            ..MeasureNumbering::default()
+        },
+    );
+    assert_staff_snapshot!(staff);
+}
+
+#[test]
+fn pickup_is_measure_zero() {
+    let numbering = MeasureNumbering::default();
+
+    assert_eq!(
+        staff("treble 3/4 g | c5 c5 c5 | c5[2.] ||", numbering).measure_numbers(),
+        vec![0, 1, 2]
+    );
+    assert_eq!(
+        staff("treble 3/4 c5 c5 c5 | c5[2.] ||", numbering).measure_numbers(),
+        vec![1, 2]
+    );
+    assert_eq!(
+        staff("treble g | c5 c5 c5 c5 ||", numbering).measure_numbers(),
+        vec![1, 2]
+    );
+    assert_eq!(
+        staff(
+            "treble 3/4 g | c5 c5 c5 ||",
+            MeasureNumbering {
+                first: Some(9),
+                ..numbering
+            }
+        )
+        .measure_numbers(),
+        vec![9, 10]
+    );
+}
+
+#[test]
+fn shown_numbers() {
+    assert!(!Display::Never.shows(0, &(5..=5)));
+    assert!(!Display::System.shows(0, &(1..=1)));
+    assert!(Display::System.shows(0, &(17..=17)));
+    assert!(!Display::System.shows(1, &(18..=18)));
+    assert!(Display::Every(5).shows(3, &(5..=5)));
+    assert!(!Display::Every(5).shows(0, &(0..=0)));
+    assert!(!Display::Every(5).shows(3, &(4..=4)));
+    assert!(!Display::Every(0).shows(3, &(4..=4)));
+}
+
+#[test]
+fn grouped_measures() {
+    let staff = staff(
+        "treble 3/4 g | c5 c5 c5 | c5[2.] ||",
+        MeasureNumbering {
+            grouped: true,
+            ..MeasureNumbering::default()
+        },
+    );
+    let elements = staff.render(helpers::metadata()).unwrap();
+
+    let measures: Vec<_> = elements
+        .iter()
+        .filter_map(|element| match element {
+            Element::Group(group) => Some(group.data.clone()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        measures,
+        vec![
+            vec![("measure", "0".to_owned())],
+            vec![("measure", "1".to_owned())],
+            vec![("measure", "2".to_owned())],
+        ]
+    );
+
+    let options = svg::Options {
+        symbol_font_name: "Bravura".to_owned(),
+        text_font_family: vec![],
+        staff_space_to_pixel_ratio: 10.0,
+    };
+    let document = svg::elements_to_svg_document(elements, &options).to_string();
+    assert!(document.contains(r#"<g data-measure="1">"#));
+}
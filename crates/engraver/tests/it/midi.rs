@@ -0,0 +1,536 @@
+use engraver::{
+    midi::{midi_file_to_staff, staff_to_midi_file, ImportOptions, Options},
+    model::{self, Alteration, Clef, Pitch, Step},
+    Error,
+};
+use engraver_parser::parse_staff;
+
+/// Returns the events in the track of a format 0 MIDI file, as absolute ticks
+/// paired with the bytes of the event.
+fn events(file: &[u8]) -> Vec<(u32, Vec<u8>)> {
+    let mut track = &file[22..];
+    let mut events = vec![];
+    let mut tick = 0;
+
+    while !track.is_empty() {
+        let (delta, rest) = variable_length_quantity(track);
+        tick += delta;
+
+        let length = match rest[0] {
+            0xff => {
+                let (length, data) = variable_length_quantity(&rest[2..]);
+                2 + (rest.len() - 2 - data.len()) + length as usize
+            }
+            _ => 3,
+        };
+
+        events.push((tick, rest[..length].to_vec()));
+        track = &rest[length..];
+    }
+
+    events
+}
+
+fn variable_length_quantity(bytes: &[u8]) -> (u32, &[u8]) {
+    let mut value = 0;
+
+    for (index, byte) in bytes.iter().enumerate() {
+        value = (value << 7) | (byte & 0x7f) as u32;
+
+        if byte & 0x80 == 0 {
+            return (value, &bytes[index + 1..]);
+        }
+    }
+
+    panic!("unterminated variable length quantity");
+}
+
+fn note_events(input: &'static str) -> Vec<(u32, Vec<u8>)> {
+    let file = staff_to_midi_file(&parse_staff(input).unwrap(), &Options::default());
+
+    events(&file)
+        .into_iter()
+        .filter(|(_, data)| data[0] != 0xff)
+        .collect()
+}
+
+#[test]
+fn header() {
+    let file = staff_to_midi_file(&parse_staff("c |").unwrap(), &Options::default());
+
+    assert_eq!(&file[0..4], b"MThd");
+    assert_eq!(&file[4..8], &[0, 0, 0, 6]);
+    assert_eq!(&file[8..10], &[0, 0]);
+    assert_eq!(&file[10..12], &[0, 1]);
+    assert_eq!(&file[12..14], &[1, 0]);
+    assert_eq!(&file[14..18], b"MTrk");
+    assert_eq!(
+        u32::from_be_bytes(file[18..22].try_into().unwrap()) as usize,
+        file.len() - 22
+    );
+}
+
+#[test]
+fn meta_events() {
+    let options = Options {
+        tempo: 90,
+        ..Default::default()
+    };
+    let file = staff_to_midi_file(&parse_staff("bb 6/8 c |").unwrap(), &options);
+    let events = events(&file);
+
+    assert!(events.contains(&(0, vec![0xff, 0x51, 3, 0x0a, 0x2c, 0x2a])));
+    assert!(events.contains(&(0, vec![0xff, 0x58, 4, 6, 3, 24, 8])));
+    assert!(events.contains(&(0, vec![0xff, 0x59, 2, 0xfe, 0])));
+    assert_eq!(events.last().unwrap(), &(256, vec![0xff, 0x2f, 0]));
+}
+
+#[test]
+fn time_signature_forms() {
+    let time_signature = |input: &'static str| {
+        let file = staff_to_midi_file(&parse_staff(input).unwrap(), &Options::default());
+
+        events(&file)
+            .into_iter()
+            .find(|(_, data)| data[..2] == [0xff, 0x58])
+            .map(|(_, data)| (data[3], data[4]))
+    };
+
+    assert_eq!(time_signature("C c |"), Some((4, 2)));
+    assert_eq!(time_signature("C| c |"), Some((2, 1)));
+    assert_eq!(time_signature("3+2+2/8 c |"), Some((7, 3)));
+    assert_eq!(time_signature("2/[4.] c |"), Some((6, 3)));
+    assert_eq!(time_signature("X/[4.] c |"), None);
+}
+
+#[test]
+fn durations() {
+    assert_eq!(
+        note_events("c[4.] d[8] e[2..] |"),
+        vec![
+            (0, vec![0x90, 60, 80]),
+            (384, vec![0x80, 60, 0]),
+            (384, vec![0x90, 62, 80]),
+            (512, vec![0x80, 62, 0]),
+            (512, vec![0x90, 64, 80]),
+            (1408, vec![0x80, 64, 0]),
+        ]
+    );
+}
+
+#[test]
+fn rests() {
+    assert_eq!(
+        note_events("r[2] c[4] |"),
+        vec![(512, vec![0x90, 60, 80]), (768, vec![0x80, 60, 0])]
+    );
+}
+
+#[test]
+fn full_measure_rests_last_for_their_measure() {
+    assert_eq!(
+        note_events("3/4 R | c[4] |"),
+        vec![(768, vec![0x90, 60, 80]), (1024, vec![0x80, 60, 0])]
+    );
+}
+
+#[test]
+fn octave_lines_keep_their_pitches() {
+    assert_eq!(
+        note_events("\\8va c6[2] \\loco c6[2] |"),
+        vec![
+            (0, vec![0x90, 84, 80]),
+            (512, vec![0x80, 84, 0]),
+            (512, vec![0x90, 84, 80]),
+            (1024, vec![0x80, 84, 0]),
+        ]
+    );
+}
+
+#[test]
+fn pedals_are_control_changes() {
+    assert_eq!(
+        note_events("\\ped c[2] \\ped-change-half d[2] | \\ped-up \\una-corda r[1] |")
+            .into_iter()
+            .filter(|(_, data)| data[0] == 0xb0)
+            .collect::<Vec<_>>(),
+        vec![
+            (0, vec![0xb0, 64, 127]),
+            (512, vec![0xb0, 64, 0]),
+            (512, vec![0xb0, 64, 64]),
+            (1024, vec![0xb0, 64, 0]),
+            (1024, vec![0xb0, 67, 127]),
+        ]
+    );
+}
+
+#[test]
+fn trills_are_realized() {
+    let options = Options {
+        realize_trills: true,
+        ..Default::default()
+    };
+    let file = staff_to_midi_file(&parse_staff("## b[8]-tr c[8]-tr^# d |").unwrap(), &options);
+    let note_ons: Vec<_> = events(&file)
+        .into_iter()
+        .filter(|(_, data)| data[0] == 0x90)
+        .map(|(tick, data)| (tick, data[1]))
+        .collect();
+
+    assert_eq!(
+        note_ons,
+        [
+            (0, 71),
+            (32, 73),
+            (64, 71),
+            (96, 73),
+            (128, 60),
+            (160, 63),
+            (192, 60),
+            (224, 63),
+            (256, 62),
+        ]
+    );
+}
+
+#[test]
+fn trills_are_not_realized_by_default() {
+    assert_eq!(
+        note_events("c-tr~ |"),
+        vec![(0, vec![0x90, 60, 80]), (256, vec![0x80, 60, 0])]
+    );
+}
+
+#[test]
+fn repeats_and_navigation_are_unrolled() {
+    let keys: Vec<_> = note_events("|: c[1] :| d[1] \\dc ||")
+        .into_iter()
+        .filter(|(_, data)| data[0] == 0x90)
+        .map(|(_, data)| data[1])
+        .collect();
+
+    assert_eq!(keys, [60, 60, 62, 60, 62]);
+}
+
+#[test]
+fn chords() {
+    assert_eq!(
+        note_events("{c e g5} |"),
+        vec![
+            (0, vec![0x90, 60, 80]),
+            (0, vec![0x90, 64, 80]),
+            (0, vec![0x90, 79, 80]),
+            (256, vec![0x80, 60, 0]),
+            (256, vec![0x80, 64, 0]),
+            (256, vec![0x80, 79, 0]),
+        ]
+    );
+}
+
+#[test]
+fn pitches_sound_regardless_of_engraved_accidentals() {
+    let keys = |input| {
+        note_events(input)
+            .into_iter()
+            .filter(|(_, data)| data[0] == 0x90)
+            .map(|(_, data)| data[1])
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(keys("## f f# c# | f# f |"), vec![65, 66, 61, 66, 65]);
+    assert_eq!(keys("c bx3 ebb5 |"), vec![60, 61, 74]);
+}
+
+#[test]
+fn octave_clefs_sound_an_octave_away() {
+    let keys = |input| {
+        note_events(input)
+            .into_iter()
+            .filter(|(_, data)| data[0] == 0x90)
+            .map(|(_, data)| data[1])
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(keys("treble_8 c | treble c |"), vec![48, 60]);
+    assert_eq!(keys("treble^8 c bass_8 c3 |"), vec![72, 36]);
+}
+
+#[test]
+fn grace_notes_take_time_from_main_note() {
+    assert_eq!(
+        note_events("\\acciaccatura d c \\appoggiatura d c | \\appoggiatura d[4] c[8] |"),
+        vec![
+            (0, vec![0x90, 62, 80]),
+            (32, vec![0x80, 62, 0]),
+            (32, vec![0x90, 60, 80]),
+            (256, vec![0x80, 60, 0]),
+            (256, vec![0x90, 62, 80]),
+            (384, vec![0x80, 62, 0]),
+            (384, vec![0x90, 60, 80]),
+            (512, vec![0x80, 60, 0]),
+            (512, vec![0x90, 62, 80]),
+            (576, vec![0x80, 62, 0]),
+            (576, vec![0x90, 60, 80]),
+            (640, vec![0x80, 60, 0]),
+        ]
+    );
+}
+
+#[test]
+fn metronome_marks_change_tempo() {
+    let file = staff_to_midi_file(
+        &parse_staff("c[1] | \\tempo \"Adagio\" e[1] | \\tempo [4.] = 60 g[1] |").unwrap(),
+        &Options::default(),
+    );
+    let tempos: Vec<_> = events(&file)
+        .into_iter()
+        .filter(|(_, data)| data[..2] == [0xff, 0x51])
+        .map(|(tick, data)| (tick, data[3..].to_vec()))
+        .collect();
+
+    assert_eq!(
+        tempos,
+        [(0, vec![0x07, 0xa1, 0x20]), (2048, vec![0x0a, 0x2c, 0x2a])]
+    );
+}
+
+#[test]
+fn key_changes_follow_playback_order() {
+    let file = staff_to_midi_file(
+        &parse_staff("|: c[1] | \\key # d[1] :|").unwrap(),
+        &Options::default(),
+    );
+    let key_signatures: Vec<_> = events(&file)
+        .into_iter()
+        .filter(|(_, data)| data[..2] == [0xff, 0x59])
+        .map(|(tick, data)| (tick, data[3]))
+        .collect();
+
+    assert_eq!(key_signatures, [(1024, 1), (2048, 0), (3072, 1)]);
+}
+
+/// Returns a format 0 MIDI file with 480 ticks per quarter note and a single
+/// track containing `events`.
+fn midi_file(events: &[u8]) -> Vec<u8> {
+    let mut file = b"MThd".to_vec();
+    file.extend_from_slice(&[0, 0, 0, 6, 0, 0, 0, 1, 0x01, 0xe0]);
+    file.extend_from_slice(b"MTrk");
+    file.extend_from_slice(&(events.len() as u32 + 4).to_be_bytes());
+    file.extend_from_slice(events);
+    file.extend_from_slice(&[0, 0xff, 0x2f, 0]);
+    file
+}
+
+fn import(file: &[u8]) -> model::Staff {
+    midi_file_to_staff(file, &ImportOptions::default()).unwrap()
+}
+
+#[test]
+fn import_round_trip() {
+    for input in [
+        "## 3/4 d e f# | {d f# a}[2] r[4] ||",
+        "bass bbb 4/4 c3[8] d[8] eb[4.] r[8] ab2[4] ||",
+        "6/8 g[4.] {c5 e}[4.] ||",
+        "## 4/4 d[1] | \\key b \\time 3/4 f[2.] | \\key 0 c[2.] ||",
+    ] {
+        let staff = parse_staff(input).unwrap();
+        let file = staff_to_midi_file(&staff, &Options::default());
+
+        assert_eq!(import(&file), staff, "input: '{input}'");
+    }
+}
+
+#[test]
+fn import_quantizes_onsets_and_lengths() {
+    // C4 played slightly late and released early, then E4 with running status
+    // and a zero velocity note on as its note off.
+    let file = midi_file(&[
+        0x0a, 0x90, 60, 100, //
+        0x83, 0x4a, 0x80, 60, 0, //
+        0x0c, 0x90, 64, 100, //
+        0x83, 0x60, 64, 0,
+    ]);
+    let staff = import(&file);
+
+    assert_eq!(staff, parse_staff("c[4] e[4] r[2] ||").unwrap());
+}
+
+#[test]
+fn import_splits_notes_at_barlines() {
+    // 2/4, with a C4 lasting 3 quarter notes, which is played again in the
+    // second measure since staves have no ties.
+    let file = midi_file(&[
+        0x00, 0xff, 0x58, 4, 2, 2, 24, 8, //
+        0x00, 0x90, 60, 100, //
+        0x8b, 0x20, 0x80, 60, 0,
+    ]);
+
+    assert_eq!(
+        import(&file),
+        parse_staff("2/4 c[2] | c[4] r[4] ||").unwrap()
+    );
+}
+
+#[test]
+fn import_splits_lengths_into_representable_durations() {
+    // A C4 lasting 5 eighth notes.
+    let file = midi_file(&[
+        0x00, 0x90, 60, 100, //
+        0x89, 0x60, 0x80, 60, 0,
+    ]);
+
+    assert_eq!(import(&file), parse_staff("c[2] c[8] r[4.] ||").unwrap());
+}
+
+#[test]
+fn import_spells_pitches_in_key() {
+    let spell = |fifths: i8, key: u8| {
+        let file = midi_file(&[
+            0x00,
+            0xff,
+            0x59,
+            2,
+            fifths as u8,
+            0, //
+            0x00,
+            0x90,
+            key,
+            100, //
+            0x83,
+            0x60,
+            0x80,
+            key,
+            0,
+        ]);
+
+        match &import(&file).measures[0].elements[0] {
+            model::measure::Element::Note(note) => note.pitch,
+            element => panic!("expected note, found {element:?}"),
+        }
+    };
+    let pitch = |step, alteration, octave| Pitch {
+        step,
+        alteration,
+        octave,
+    };
+
+    assert_eq!(spell(0, 61), pitch(Step::C, Alteration::Sharp, 4));
+    assert_eq!(spell(-1, 61), pitch(Step::D, Alteration::Flat, 4));
+    assert_eq!(spell(-1, 70), pitch(Step::B, Alteration::Flat, 4));
+    assert_eq!(spell(2, 65), pitch(Step::F, Alteration::Natural, 4));
+    assert_eq!(spell(6, 65), pitch(Step::E, Alteration::Sharp, 4));
+    assert_eq!(spell(-6, 59), pitch(Step::C, Alteration::Flat, 4));
+}
+
+#[test]
+fn import_infers_clef() {
+    let clef = |key: u8| {
+        let file = midi_file(&[0x00, 0x90, key, 100, 0x83, 0x60, 0x80, key, 0]);
+        import(&file).clef
+    };
+
+    assert_eq!(clef(60), Clef::Treble);
+    assert_eq!(clef(48), Clef::Bass);
+}
+
+#[test]
+fn import_rejects_invalid_files() {
+    assert!(matches!(
+        midi_file_to_staff(b"RIFF", &ImportOptions::default()),
+        Err(Error::UnexpectedEndOfMidiFile)
+    ));
+    assert!(matches!(
+        midi_file_to_staff(b"RIFF\0\0\0\0", &ImportOptions::default()),
+        Err(Error::InvalidMidiChunkType { .. })
+    ));
+    assert!(matches!(
+        midi_file_to_staff(&midi_file(&[0x00, 0x3c, 100]), &ImportOptions::default()),
+        Err(Error::MissingMidiRunningStatus)
+    ));
+
+    let time_signature = |numerator, exponent| {
+        midi_file_to_staff(
+            &midi_file(&[0x00, 0xff, 0x58, 4, numerator, exponent, 24, 8]),
+            &ImportOptions::default(),
+        )
+    };
+    assert!(time_signature(3, 7).is_ok());
+    assert!(matches!(
+        time_signature(0, 2),
+        Err(Error::InvalidMidiTimeSignature {
+            numerator: 0,
+            exponent: 2
+        })
+    ));
+    assert!(matches!(
+        time_signature(4, 8),
+        Err(Error::InvalidMidiTimeSignature {
+            numerator: 4,
+            exponent: 8
+        })
+    ));
+}
+
+#[test]
+fn grace_notes_at_end_of_measure_take_time_from_next_note() {
+    assert_eq!(
+        note_events("c[2.] \\acciaccatura d | c[1] |"),
+        vec![
+            (0, vec![0x90, 60, 80]),
+            (768, vec![0x80, 60, 0]),
+            (768, vec![0x90, 62, 80]),
+            (800, vec![0x80, 62, 0]),
+            (800, vec![0x90, 60, 80]),
+            (1792, vec![0x80, 60, 0]),
+        ]
+    );
+}
+
+#[test]
+fn grace_notes_after_last_note_take_time_from_its_end() {
+    assert_eq!(
+        note_events("c[1] \\acciaccatura d ||"),
+        vec![
+            (0, vec![0x90, 60, 80]),
+            (992, vec![0x80, 60, 0]),
+            (992, vec![0x90, 62, 80]),
+            (1024, vec![0x80, 62, 0]),
+        ]
+    );
+}
+
+#[test]
+fn grace_notes_last_at_least_one_division() {
+    assert_eq!(
+        note_events("\\acciaccatura (d e f) c[256] |"),
+        vec![
+            (0, vec![0x90, 62, 80]),
+            (1, vec![0x80, 62, 0]),
+            (1, vec![0x90, 64, 80]),
+            (2, vec![0x80, 64, 0]),
+            (2, vec![0x90, 65, 80]),
+            (3, vec![0x80, 65, 0]),
+            (3, vec![0x90, 60, 80]),
+            (4, vec![0x80, 60, 0]),
+        ]
+    );
+}
+
+#[test]
+fn trills_follow_accidentals_earlier_in_measure() {
+    let options = Options {
+        realize_trills: true,
+        ..Default::default()
+    };
+    let file = staff_to_midi_file(
+        &parse_staff("f#[2] e[8]-tr r[8] r[4] | e[8]-tr |").unwrap(),
+        &options,
+    );
+    let keys: Vec<_> = events(&file)
+        .into_iter()
+        .filter(|(_, data)| data[0] == 0x90)
+        .map(|(_, data)| data[1])
+        .collect();
+
+    assert_eq!(keys, [66, 64, 66, 64, 66, 64, 65, 64, 65]);
+}
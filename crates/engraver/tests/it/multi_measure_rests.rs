@@ -0,0 +1,125 @@
+use engraver::render::{
+    input::{
+        measure_numbering::{Display, MeasureNumbering},
+        multi_measure_rest::Style,
+        Staff,
+    },
+    ir::Element,
+};
+use engraver_parser::parse_staff;
+use smufl::StaffSpaces;
+
+use crate::helpers;
+
+const PART: &str = "treble 4/4 c d e f | r[1] | r[1] | r[1] | r[1] | r[1] | g[1] ||";
+
+fn staff(input: &'static str, multi_measure_rests: Option<Style>) -> Staff {
+    Staff {
+        multi_measure_rests,
+        ..parse_staff(input).unwrap().into()
+    }
+}
+
+#[test]
+fn h_bar() {
+    let staff = staff(PART, Some(Style::HBar));
+    assert_staff_snapshot!(staff);
+}
+
+#[test]
+fn church_rests() {
+    let staff = staff(PART, Some(Style::Church));
+    assert_staff_snapshot!(staff);
+}
+
+#[test]
+fn many_church_rests_are_an_h_bar() {
+    let staff = staff(
+        "treble 4/4 r[1] | r[1] | r[1] | r[1] | r[1] | r[1] | r[1] | r[1] | r[1] | c[1] ||",
+        Some(Style::Church),
+    );
+    assert_staff_snapshot!(staff);
+}
+
+#[test]
+fn runs_are_broken_by_barlines_and_time_signature_changes() {
+    let staff = staff(
+        "treble 4/4 r[1] | r[1] || r[1] | r[1] | 3/4 r[2.] | r[2.] | c[2.] ||",
+        Some(Style::HBar),
+    );
+    assert_staff_snapshot!(staff);
+}
+
+#[test]
+fn numbered_multi_measure_rest() {
+    let staff = Staff {
+        measure_numbering: MeasureNumbering {
+            display: Display::Every(4),
+            grouped: true,
+            ..MeasureNumbering::default()
+        },
+        ..staff(PART, Some(Style::HBar))
+    };
+
+    let elements = staff.render(helpers::metadata()).unwrap();
+    let measures: Vec<_> = elements
+        .iter()
+        .filter_map(|element| match element {
+            Element::Group(group) => Some(group.data.clone()),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(
+        measures,
+        vec![
+            vec![("measure", "1".to_owned())],
+            vec![
+                ("measure", "2".to_owned()),
+                ("last-measure", "6".to_owned())
+            ],
+            vec![("measure", "7".to_owned())],
+        ]
+    );
+    assert!(elements.iter().any(|element| matches!(
+        element,
+        Element::Group(group) if group.elements.iter().any(|element| matches!(
+            element,
+            Element::Text(text) if text.value == "2–6"
+        ))
+    )));
+
+    assert_staff_snapshot!(staff);
+}
+
+#[test]
+fn full_score_is_not_collapsed() {
+    let metadata = helpers::metadata();
+    let score = staff(PART, None).render(metadata).unwrap();
+    let part = staff(PART, Some(Style::HBar)).render(metadata).unwrap();
+
+    let width = |elements: &[Element<StaffSpaces>]| {
+        elements
+            .iter()
+            .map(Element::max_x)
+            .reduce(|x1, x2| x1.max(x2))
+            .unwrap()
+    };
+
+    assert!(width(&part) < width(&score));
+}
+
+#[test]
+fn empty_measures() {
+    let staff = staff(
+        "treble 4/4 r[1] | r r[2] | c[1] | r[1] \\fine | r[1] |",
+        None,
+    );
+    let empty: Vec<_> = staff
+        .measures
+        .iter()
+        .map(|measure| measure.is_empty())
+        .collect();
+
+    assert_eq!(empty, vec![true, true, false, false, true]);
+}
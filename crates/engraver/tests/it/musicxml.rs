@@ -0,0 +1,366 @@
+use engraver::{
+    model::{duration, Alteration, Clef, Duration, Measure, Note, Pitch, Rest, Staff, Step},
+    musicxml::staff_to_musicxml_document,
+    Error,
+};
+use engraver_parser::parse_staff;
+
+fn document(input: &'static str) -> String {
+    staff_to_musicxml_document(&parse_staff(input).unwrap()).unwrap()
+}
+
+#[test]
+fn partwise_header() {
+    let document = document("c |");
+
+    assert!(document.starts_with(r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>"#));
+    assert!(document.contains(r#"<score-partwise version="4.0">"#));
+    assert!(document.contains(r#"<score-part id="P1">"#));
+    assert!(document.contains(r#"<part id="P1">"#));
+}
+
+#[test]
+fn attributes() {
+    let document = document("bass bbb 3/4 c |");
+
+    assert!(document.contains("<divisions>1</divisions>"));
+    assert!(document.contains("<fifths>-3</fifths>"));
+    assert!(document.contains("<beats>3</beats>"));
+    assert!(document.contains("<beat-type>4</beat-type>"));
+    assert!(document.contains("<sign>F</sign>"));
+    assert!(document.contains("<line>4</line>"));
+}
+
+#[test]
+fn divisions_from_shortest_duration() {
+    assert!(document("c[2] d[8] |").contains("<divisions>2</divisions>"));
+    assert!(document("c[8.] |").contains("<divisions>4</divisions>"));
+    assert!(document("c[1] |").contains("<divisions>1</divisions>"));
+}
+
+#[test]
+fn note_durations() {
+    let document = document("c[4..] d[32] |");
+
+    assert!(document.contains("<divisions>8</divisions>"));
+    assert!(document.contains("<duration>14</duration>"));
+    assert!(document.contains("<type>quarter</type>"));
+    assert_eq!(document.matches("<dot/>").count(), 2);
+    assert!(document.contains("<duration>1</duration>"));
+    assert!(document.contains("<type>32nd</type>"));
+}
+
+#[test]
+fn full_measure_rests() {
+    let document = document("3/4 R | c[4] d e |");
+
+    assert!(document.contains("<divisions>1</divisions>"));
+    assert!(document.contains(r#"<rest measure="yes"/>"#));
+    assert!(document.contains("<duration>3</duration>"));
+}
+
+#[test]
+fn octave_shifts() {
+    let document = document("\\8va c6 \\loco c5 \\15mb c2 |");
+
+    assert!(document.contains(r#"<octave-shift type="down" size="8"/>"#));
+    assert!(document.contains(r#"<octave-shift type="stop" size="8"/>"#));
+    assert!(document.contains(r#"<octave-shift type="up" size="15"/>"#));
+    assert!(document.contains("<octave>6</octave>"));
+}
+
+#[test]
+fn pedals() {
+    let document = document("\\ped c \\ped-change-half d \\ped-up \\una-corda e |");
+
+    assert!(document.contains(r#"<pedal type="start"/>"#));
+    assert!(document.contains(r#"<sound damper-pedal="yes"/>"#));
+    assert!(document.contains(r#"<pedal type="change"/>"#));
+    assert!(document.contains(r#"<sound damper-pedal="50"/>"#));
+    assert!(document.contains(r#"<pedal type="stop"/>"#));
+    assert!(document.contains(r#"<words font-style="italic">una corda</words>"#));
+    assert!(document.contains(r#"<sound soft-pedal="yes"/>"#));
+}
+
+#[test]
+fn ornaments() {
+    let document = document("c-tr~^# d-.-mor e-turn_b {f a}-prall g-rturn |");
+
+    assert_eq!(document.matches("<ornaments>").count(), 5);
+    assert!(document.contains("<trill-mark/>"));
+    assert!(document.contains(r#"<wavy-line type="start"/>"#));
+    assert!(document.contains(r#"<wavy-line type="stop"/>"#));
+    assert!(document.contains(r#"<accidental-mark placement="above">sharp</accidental-mark>"#));
+    assert!(document.contains("<mordent/>"));
+    assert!(document.contains("<staccato/>"));
+    assert!(document.contains(r#"<accidental-mark placement="below">flat</accidental-mark>"#));
+    assert!(document.contains("<turn/>"));
+    assert_eq!(document.matches("<inverted-mordent/>").count(), 1);
+    assert!(document.contains("<inverted-turn/>"));
+}
+
+#[test]
+fn measures_and_barlines() {
+    let document = document("c | d ||");
+
+    assert!(document.contains(r#"<measure number="1">"#));
+    assert!(document.contains(r#"<measure number="2">"#));
+    assert_eq!(document.matches("<barline").count(), 1);
+    assert!(document.contains("<bar-style>light-heavy</bar-style>"));
+}
+
+#[test]
+fn repeats_and_endings() {
+    let document = document("|: c | 1. d :| 2. e |[double] f ||");
+
+    assert_eq!(document.matches("<barline").count(), 6);
+    assert!(document.contains(r#"<barline location="left">"#));
+    assert!(document.contains(r#"<repeat direction="forward"/>"#));
+    assert!(document.contains(r#"<repeat direction="backward"/>"#));
+    assert!(document.contains(r#"<ending number="1" type="start">1.</ending>"#));
+    assert!(document.contains(r#"<ending number="1" type="stop">1.</ending>"#));
+    assert!(document.contains(r#"<ending number="2" type="start">2.</ending>"#));
+    assert!(document.contains(r#"<ending number="2" type="discontinue">2.</ending>"#));
+    assert!(document.contains("<bar-style>light-light</bar-style>"));
+}
+
+#[test]
+fn navigation() {
+    let document = document("\\segno c | d \\tocoda | e \\ds-coda || \\coda f ||");
+
+    assert_eq!(
+        document.matches(r#"<direction placement="above">"#).count(),
+        4
+    );
+    assert!(document.contains("<segno/>"));
+    assert!(document.contains("<coda/>"));
+    assert!(document.contains("<words>To Coda</words>"));
+    assert!(document.contains("<words>D.S. al Coda</words>"));
+    assert!(document.contains(r#"<sound segno="segno"/>"#));
+    assert!(document.contains(r#"<sound dalsegno="segno"/>"#));
+    assert!(document.contains(r#"<sound tocoda="coda"/>"#));
+    assert!(document.contains(r#"<sound coda="coda"/>"#));
+}
+
+#[test]
+fn clef_change() {
+    let document = document("c bass c3 |");
+
+    assert_eq!(document.matches("<attributes>").count(), 2);
+    assert!(document.contains("<sign>F</sign>"));
+}
+
+#[test]
+fn clef_signs_and_octave_changes() {
+    let octave = document("treble_8 c |");
+    assert!(octave.contains("<sign>G</sign>"));
+    assert!(octave.contains("<clef-octave-change>-1</clef-octave-change>"));
+
+    let baritone = document("baritone c3 |");
+    assert!(baritone.contains("<sign>C</sign>"));
+    assert!(baritone.contains("<line>5</line>"));
+
+    assert!(document("percussion c |").contains("<sign>percussion</sign>"));
+    assert!(document("tab c |").contains("<sign>TAB</sign>"));
+}
+
+#[test]
+fn staff_details() {
+    assert!(!document("c |").contains("<staff-details>"));
+
+    let document = document("\\lines 1 \\scale 0.75 percussion c |");
+    assert!(document.contains("<staff-lines>1</staff-lines>"));
+    assert!(document.contains("<staff-size>75</staff-size>"));
+}
+
+#[test]
+fn chords() {
+    let document = document("{c e g} |");
+
+    assert_eq!(document.matches("<note>").count(), 3);
+    assert_eq!(document.matches("<chord/>").count(), 2);
+}
+
+#[test]
+fn alterations_and_accidentals() {
+    let document = document("## f# c f |");
+
+    assert_eq!(document.matches("<alter>1</alter>").count(), 1);
+    assert_eq!(
+        document.matches("<accidental>natural</accidental>").count(),
+        2
+    );
+    assert!(!document.contains("<accidental>sharp</accidental>"));
+}
+
+#[test]
+fn articulations() {
+    let document = document("c-.-^ {e g}-- |");
+
+    assert_eq!(document.matches("<notations>").count(), 2);
+    assert!(document.contains("<staccato/>"));
+    assert!(document.contains("<strong-accent/>"));
+    assert_eq!(document.matches("<tenuto/>").count(), 1);
+}
+
+#[test]
+fn grace_notes() {
+    let document = document("\\acciaccatura d c \\appoggiatura (e[16] f) g |");
+
+    assert!(document.contains(r#"<grace slash="yes"/>"#));
+    assert_eq!(document.matches(r#"<grace slash="no"/>"#).count(), 2);
+    assert_eq!(document.matches("<duration>").count(), 2);
+    assert_eq!(document.matches("<type>16th</type>").count(), 2);
+}
+
+#[test]
+fn lyrics() {
+    let document =
+        document("c d e {f a} \\lyrics \"Hal -- le -- lu -- jah\" \\lyrics \"A -- men __ _ _\" |");
+
+    assert!(document.contains(r#"<lyric number="1">"#));
+    assert_eq!(document.matches(r#"<lyric number="2">"#).count(), 2);
+    assert_eq!(document.matches("<syllabic>begin</syllabic>").count(), 2);
+    assert_eq!(document.matches("<syllabic>middle</syllabic>").count(), 2);
+    assert_eq!(document.matches("<syllabic>end</syllabic>").count(), 2);
+    assert!(document.contains("<text>jah</text>"));
+    assert_eq!(document.matches("<extend/>").count(), 1);
+    assert_eq!(document.matches("<lyric ").count(), 6);
+}
+
+#[test]
+fn harmony() {
+    let document = document("\"Cmaj7\" c \"F#m7b5/E\" e \"Bb13(#11)\" f \"E5\" g |");
+
+    assert_eq!(document.matches("<harmony>").count(), 4);
+    assert!(document.contains("<kind>major-seventh</kind>"));
+    assert!(document.contains("<kind>minor-seventh</kind>"));
+    assert!(document.contains("<kind>dominant-13th</kind>"));
+    assert!(document.contains("<kind>power</kind>"));
+    assert!(document.contains("<bass-step>E</bass-step>"));
+    assert!(document.contains("<root-alter>-1</root-alter>"));
+    assert_eq!(
+        document.matches("<degree-type>alter</degree-type>").count(),
+        2
+    );
+    assert_eq!(document.matches("<duration>").count(), 4);
+}
+
+#[test]
+fn annotations() {
+    let document = document(
+        "\\mark \"A\" \\tempo \"Allegro\" [4.] = 60 c \\text \"dolce\" d \\tempo \"Adagio\" e f |",
+    );
+
+    assert_eq!(
+        document.matches(r#"<direction placement="above">"#).count(),
+        4
+    );
+    assert!(document.contains(r#"<rehearsal enclosure="square">A</rehearsal>"#));
+    assert!(document.contains(r#"<words font-weight="bold">Allegro</words>"#));
+    assert!(document.contains("<beat-unit>quarter</beat-unit>"));
+    assert!(document.contains("<beat-unit-dot/>"));
+    assert!(document.contains("<per-minute>60</per-minute>"));
+    assert!(document.contains(r#"<sound tempo="90"/>"#));
+    assert!(document.contains(r#"<words font-style="italic">dolce</words>"#));
+    assert_eq!(document.matches("<sound ").count(), 1);
+}
+
+#[test]
+fn dynamics() {
+    let document = document("\\p \\< c d \\! e \\sfz f |");
+
+    assert_eq!(
+        document.matches(r#"<direction placement="below">"#).count(),
+        4
+    );
+    assert!(document.contains("<p/>"));
+    assert!(document.contains("<sfz/>"));
+    assert!(document.contains(r#"<wedge type="crescendo"/>"#));
+    assert!(document.contains(r#"<wedge type="stop"/>"#));
+    assert!(document.contains("<divisions>1</divisions>"));
+}
+
+#[test]
+fn ids() {
+    let duration = Duration {
+        value: duration::Value::Quarter,
+        dots: None,
+    };
+    let staff = |rest_id: &str| Staff {
+        geometry: Default::default(),
+        clef: Clef::Treble,
+        key_signature: None,
+        time_signature: None,
+        key_cancellation: Default::default(),
+        measures: vec![Measure {
+            elements: vec![
+                engraver::model::measure::Element::Note(Note {
+                    pitch: Pitch {
+                        step: Step::C,
+                        alteration: Alteration::Natural,
+                        octave: 4,
+                    },
+                    duration,
+                    articulations: vec![],
+                    ornament: None,
+                    id: Some("first".to_owned()),
+                }),
+                engraver::model::measure::Element::Rest(Rest {
+                    duration,
+                    id: Some(rest_id.to_owned()),
+                }),
+            ],
+            ..Default::default()
+        }],
+    };
+
+    let document = staff_to_musicxml_document(&staff("_second-3.rest")).unwrap();
+
+    assert!(document.contains(r#"<note id="first">"#));
+    assert!(document.contains(r#"<note id="_second-3.rest">"#));
+
+    for id in ["second & third", "2nd", "", "a:b"] {
+        assert!(matches!(
+            staff_to_musicxml_document(&staff(id)),
+            Err(Error::InvalidMusicXmlId(invalid)) if invalid == id
+        ));
+    }
+
+    for id in ["first", "P1"] {
+        assert!(matches!(
+            staff_to_musicxml_document(&staff(id)),
+            Err(Error::DuplicateMusicXmlId(duplicate)) if duplicate == id
+        ));
+    }
+}
+
+#[test]
+fn signature_changes() {
+    let document = document("#### 4/4 c | \\key b \\time 3/4 c | \\key 0 c ||");
+
+    assert!(document.contains("<cancel>4</cancel>"));
+    assert!(document.contains("<fifths>-1</fifths>"));
+    assert!(document.contains("<beats>3</beats>"));
+    assert!(document.contains("<cancel>-1</cancel>"));
+    assert!(document.contains("<fifths>0</fifths>"));
+}
+
+#[test]
+fn time_signature_forms() {
+    let common = document("C c d e f ||");
+    assert!(common.contains(r#"<time symbol="common">"#));
+    assert!(common.contains("<beats>4</beats>"));
+
+    let additive = document("3+2+2/8 c[8] d e f g a b ||");
+    assert!(additive.contains("<beats>3+2+2</beats>"));
+    assert!(additive.contains("<beat-type>8</beat-type>"));
+
+    let interchangeable = document("3/4 6/8 c d e ||");
+    assert!(interchangeable.contains("<interchangeable>"));
+    assert!(interchangeable.contains("<beats>6</beats>"));
+
+    let note = document("2/[4.] c[4.] d ||");
+    assert!(note.contains(r#"<time symbol="dotted-note">"#));
+    assert!(note.contains("<beats>6</beats>"));
+}
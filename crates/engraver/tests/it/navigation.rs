@@ -0,0 +1,93 @@
+use engraver::model::{navigation::Target, Navigation};
+use engraver_parser::parse_staff;
+
+fn playback_order(input: &'static str) -> Vec<usize> {
+    parse_staff(input).unwrap().playback_order()
+}
+
+#[test]
+fn segno_and_coda() {
+    assert_staff_snapshot!(
+        "\\segno c d e f | g a b c5 \\tocoda | c[1] \\ds-coda || \\coda c5[1] ||"
+    );
+}
+
+#[test]
+fn da_capo_al_fine() {
+    assert_staff_snapshot!("c d e f | g a b c5 \\fine || c5 b4 a g | f e d c \\dc-fine ||");
+}
+
+#[test]
+fn above_endings() {
+    assert_staff_snapshot!("|: c[1] | 1. d[1] :| 2. e[1] \\dc ||");
+}
+
+#[test]
+fn parse_navigation() {
+    let staff = parse_staff("c \\segno d | \\coda e \\fine | f \\tocoda | \\dc-coda g ||").unwrap();
+    let navigation: Vec<_> = staff
+        .measures
+        .iter()
+        .map(|measure| measure.navigation.clone())
+        .collect();
+
+    assert_eq!(
+        navigation,
+        [
+            vec![Navigation::Segno],
+            vec![Navigation::Coda, Navigation::Fine],
+            vec![Navigation::ToCoda],
+            vec![Navigation::DaCapo(Some(Target::Coda))],
+        ]
+    );
+    assert_eq!(staff.measures[0].elements.len(), 2);
+}
+
+#[test]
+fn playback_without_repeats() {
+    assert_eq!(playback_order("c | d | e ||"), [0, 1, 2]);
+}
+
+#[test]
+fn playback_of_repeats() {
+    assert_eq!(playback_order("c |: d | e :| f ||"), [0, 1, 2, 1, 2, 3]);
+    assert_eq!(playback_order("c | d :| e ||"), [0, 1, 0, 1, 2]);
+    assert_eq!(playback_order("|: c :|: d :| e ||"), [0, 0, 1, 1, 2]);
+}
+
+#[test]
+fn playback_of_endings() {
+    assert_eq!(
+        playback_order("|: c | 1. d | 1. e :| 2. f ||"),
+        [0, 1, 2, 0, 3]
+    );
+    assert_eq!(
+        playback_order("|: c | 1,2. d :| 3. e ||"),
+        [0, 1, 0, 1, 0, 2]
+    );
+}
+
+#[test]
+fn playback_of_da_capo() {
+    assert_eq!(playback_order("c | d \\dc ||"), [0, 1, 0, 1]);
+    assert_eq!(
+        playback_order("c | d \\fine || e | f \\dc-fine ||"),
+        [0, 1, 2, 3, 0, 1]
+    );
+    assert_eq!(
+        playback_order("|: c | 1. d :| 2. e \\dc ||"),
+        [0, 1, 0, 2, 0, 2]
+    );
+}
+
+#[test]
+fn playback_of_dal_segno() {
+    assert_eq!(
+        playback_order("c | \\segno d | e \\tocoda | f \\ds-coda || \\coda g ||"),
+        [0, 1, 2, 3, 1, 2, 4]
+    );
+    assert_eq!(
+        playback_order("c | \\segno d \\fine || e \\ds-fine ||"),
+        [0, 1, 2, 1]
+    );
+}
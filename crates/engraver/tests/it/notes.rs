@@ -13,6 +13,8 @@ macro_rules! assert_notes_with_positions_snapshot {
                         y: StaffSpaces($position),
                         accidental: None,
                         duration: Duration { value, dots: None },
+                        articulations: vec![],
+                        ornament: None,
                         beam: None,
                         id: None,
                     })
@@ -54,6 +56,8 @@ fn accidentals() -> Result<()> {
                     value: duration::Value::Quarter,
                     dots: None,
                 },
+                articulations: vec![],
+                ornament: None,
                 beam: None,
                 id: None,
             })
@@ -84,6 +88,8 @@ fn note_with_id() -> Result<()> {
                     value: duration::Value::Whole,
                     dots: None,
                 },
+                articulations: vec![],
+                ornament: None,
                 beam: None,
                 id: Some("test_note".to_string()),
             })],
@@ -0,0 +1,73 @@
+use engraver::{
+    model::{measure, octave_line, OctaveLine},
+    render::input::{self, Staff},
+};
+use engraver_parser::{parse_lilypond, parse_staff};
+use smufl::StaffSpaces;
+
+/// Returns the positions of the notes of the staff.
+fn note_positions(input: &'static str) -> Vec<StaffSpaces> {
+    let staff: Staff = parse_staff(input).unwrap().into();
+
+    staff
+        .measures
+        .iter()
+        .flat_map(|measure| &measure.elements)
+        .filter_map(|element| match element {
+            input::measure::Element::Note(note) => Some(note.y),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn octave_above() {
+    assert_staff_snapshot!("c5 \\8va e6 g a c7 | b6 g \\loco e5 c ||");
+}
+
+#[test]
+fn octave_below() {
+    assert_staff_snapshot!("bass c3 \\8vb a1 f d c | c3 ||");
+}
+
+#[test]
+fn two_octaves() {
+    assert_staff_snapshot!("\\15ma c7 d e f | \\15mb c2 d e f ||");
+}
+
+#[test]
+fn line_without_end() {
+    assert_staff_snapshot!("\\8va c6 d e f | g a b c7 ||");
+}
+
+#[test]
+fn notes_are_drawn_away_from_their_pitch() {
+    assert_eq!(
+        note_positions("\\8va c6 \\8vb c3 \\15ma c7 \\15mb c2 \\loco c5 |"),
+        note_positions("c5 c4 c5 c4 c5 |")
+    );
+}
+
+#[test]
+fn parse_octave_lines() {
+    let staff = parse_staff("\\8va c6 \\15mb d \\loco e |").unwrap();
+    let elements = &staff.measures[0].elements;
+
+    assert_eq!(
+        elements[0],
+        measure::Element::OctaveLine(OctaveLine::Start(octave_line::Kind::OctaveAbove))
+    );
+    assert_eq!(
+        elements[2],
+        measure::Element::OctaveLine(OctaveLine::Start(octave_line::Kind::TwoOctavesBelow))
+    );
+    assert_eq!(elements[4], measure::Element::OctaveLine(OctaveLine::End));
+}
+
+#[test]
+fn lilypond_ottava() {
+    assert_eq!(
+        parse_lilypond(r"{ \ottava #1 c''' d''' \ottava #0 c'' \ottava #0 }").unwrap(),
+        parse_staff("\\8va c6 d \\loco c5 |").unwrap()
+    );
+}
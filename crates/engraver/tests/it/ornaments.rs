@@ -0,0 +1,76 @@
+use engraver::model::{measure, ornament, Alteration, Ornament};
+use engraver_parser::{parse_lilypond, parse_staff};
+
+#[test]
+fn kinds() {
+    assert_staff_snapshot!("c5-tr d5-mor e5-prall f5-turn | g5-rturn a b[2] ||");
+}
+
+#[test]
+fn accidentals() {
+    assert_staff_snapshot!("c5-tr^# d5-mor_b e5-turn^n_bb f5-prall^x ||");
+}
+
+#[test]
+fn trill_extensions() {
+    assert_staff_snapshot!("c5[2]-tr~ d5 e5[4]-tr~ | f5[1]-tr~ ||");
+}
+
+#[test]
+fn above_articulations_and_high_notes() {
+    assert_staff_snapshot!("b4-^-tr a5-mor {c5 e5 g5}-turn c6[8]-tr d6-tr ||");
+}
+
+#[test]
+fn parse_ornaments() {
+    let staff = parse_staff("c-tr~^# d-.-rturn_b {e g}-mor f |").unwrap();
+    let elements = &staff.measures[0].elements;
+
+    let measure::Element::Note(note) = &elements[0] else {
+        panic!("expected a note");
+    };
+    assert_eq!(
+        note.ornament,
+        Some(Ornament {
+            kind: ornament::Kind::Trill { extended: true },
+            accidental_above: Some(Alteration::Sharp),
+            accidental_below: None,
+        })
+    );
+
+    let measure::Element::Note(note) = &elements[1] else {
+        panic!("expected a note");
+    };
+    assert_eq!(
+        note.ornament,
+        Some(Ornament {
+            kind: ornament::Kind::InvertedTurn,
+            accidental_above: None,
+            accidental_below: Some(Alteration::Flat),
+        })
+    );
+
+    let measure::Element::Chord(chord) = &elements[2] else {
+        panic!("expected a chord");
+    };
+    assert_eq!(
+        chord.ornament.map(|ornament| ornament.kind),
+        Some(ornament::Kind::Mordent)
+    );
+
+    let measure::Element::Note(note) = &elements[3] else {
+        panic!("expected a note");
+    };
+    assert_eq!(note.ornament, None);
+}
+
+#[test]
+fn lilypond_ornaments() {
+    assert_eq!(
+        parse_lilypond(
+            r"{ c'\trill d'\startTrillSpan e'\stopTrillSpan\mordent f'\trill\startTrillSpan g'\prall <a' c''>\turn b'\reverseturn }"
+        )
+        .unwrap(),
+        parse_staff("c-tr d-tr~ e-mor f-tr~ g-prall {a c5}-turn b4-rturn |").unwrap()
+    );
+}
@@ -0,0 +1,82 @@
+use engraver::{
+    model::{measure, pedal, Pedal},
+    render::input::Staff,
+};
+use engraver_parser::{parse_lilypond, parse_staff};
+
+const PEDALING: &str =
+    "\\ped c d \\ped-change e f | \\ped-change-half g \\ped-up a \\ped-half b c5 | \\ped-up c[1] ||";
+
+fn staff(input: &'static str, pedal_style: pedal::Style) -> Staff {
+    Staff {
+        pedal_style,
+        ..parse_staff(input).unwrap().into()
+    }
+}
+
+#[test]
+fn text() {
+    let staff = staff(PEDALING, pedal::Style::Text);
+    assert_staff_snapshot!(staff);
+}
+
+#[test]
+fn bracket() {
+    let staff = staff(PEDALING, pedal::Style::Bracket);
+    assert_staff_snapshot!(staff);
+}
+
+#[test]
+fn bracket_without_end() {
+    let staff = staff("c \\ped d e f | g a b c5 ||", pedal::Style::Bracket);
+    assert_staff_snapshot!(staff);
+}
+
+#[test]
+fn bracket_across_system_break() {
+    let staff = staff(
+        "\\ped c d e f \\break | g a b \\ped-up c5 ||",
+        pedal::Style::Bracket,
+    );
+    assert_staff_snapshot!(staff);
+}
+
+#[test]
+fn una_corda_below_sustain_pedal() {
+    assert_staff_snapshot!("\\una-corda \\ped c d e \\ped-up f | \\tre-corde g[1] ||");
+}
+
+#[test]
+fn below_dynamics_and_low_notes() {
+    assert_staff_snapshot!("\\p \\ped c3 d3 e3 \\ped-up f3 ||");
+}
+
+#[test]
+fn parse_pedals() {
+    let staff =
+        parse_staff("\\ped c \\ped-change-half d \\ped-up \\una-corda e \\tre-corde f |").unwrap();
+    let elements = &staff.measures[0].elements;
+
+    assert_eq!(
+        elements[0],
+        measure::Element::Pedal(Pedal::Down(pedal::Depth::Full))
+    );
+    assert_eq!(
+        elements[2],
+        measure::Element::Pedal(Pedal::Change(pedal::Depth::Half))
+    );
+    assert_eq!(elements[4], measure::Element::Pedal(Pedal::Up));
+    assert_eq!(elements[5], measure::Element::Pedal(Pedal::UnaCorda));
+    assert_eq!(elements[7], measure::Element::Pedal(Pedal::TreCorde));
+}
+
+#[test]
+fn lilypond_pedals() {
+    assert_eq!(
+        parse_lilypond(
+            r"{ c'\sustainOn d'\sustainOff\sustainOn e'\unaCorda f'\sustainOff\treCorde }"
+        )
+        .unwrap(),
+        parse_staff("\\ped c \\ped-change d \\una-corda e \\ped-up \\tre-corde f |").unwrap()
+    );
+}
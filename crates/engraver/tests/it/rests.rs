@@ -1,7 +1,14 @@
 use color_eyre::eyre::Result;
-use engraver::render::input::{duration, measure, Duration, Measure, Rest, Staff};
+use engraver::render::{
+    input::{duration, measure, Duration, FullMeasureRest, Measure, Rest, Staff},
+    ir::Element,
+};
+use engraver_parser::parse_staff;
+use smufl::{Glyph, StaffSpaces};
 use strum::IntoEnumIterator;
 
+use crate::helpers;
+
 #[test]
 fn all() -> Result<()> {
     let elements = duration::Value::iter()
@@ -46,3 +53,71 @@ fn rest_with_id() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn full_measure_rests() -> Result<()> {
+    let staff: Staff = parse_staff("3/4 c d e | R | \\time 4/2 R | c[1] d[1] ||")?.into();
+
+    let full_measure_rests: Vec<_> = staff
+        .measures
+        .iter()
+        .flat_map(|measure| &measure.elements)
+        .filter_map(|element| match element {
+            measure::Element::FullMeasureRest(rest) => Some(*rest),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        full_measure_rests,
+        vec![FullMeasureRest::Whole, FullMeasureRest::Breve]
+    );
+
+    assert_staff_snapshot!(staff);
+
+    Ok(())
+}
+
+#[test]
+fn full_measure_rest_centered_in_measure() -> Result<()> {
+    // The clef change after the rest makes the measure wider than the rest.
+    let staff: Staff = parse_staff("c d e f | R bass | c3 ||")?.into();
+    let metadata = helpers::metadata();
+    let elements = staff.render(metadata)?;
+
+    let rest = elements
+        .iter()
+        .find_map(|element| match element {
+            Element::Symbol(symbol) if symbol.value == Glyph::RestWhole => Some(symbol.origin.x),
+            _ => None,
+        })
+        .unwrap();
+    let rest_width = metadata.advance_widths.get(Glyph::RestWhole).unwrap();
+
+    let mut barlines: Vec<_> = elements
+        .iter()
+        .filter_map(|element| match element {
+            Element::Line(line)
+                if line.from.x == line.to.x
+                    && (line.to.y - line.from.y).abs() == StaffSpaces(4.0) =>
+            {
+                Some(line.from.x)
+            }
+            _ => None,
+        })
+        .collect();
+    barlines.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let middle = (barlines[0] + barlines[1]) / 2.0;
+    assert!((rest + rest_width / 2.0 - middle).abs() < StaffSpaces(1e-9));
+
+    let clef = elements
+        .iter()
+        .find_map(|element| match element {
+            Element::Symbol(symbol) if symbol.value == Glyph::FClefChange => Some(symbol.origin.x),
+            _ => None,
+        })
+        .unwrap();
+    assert!(rest + rest_width < clef);
+
+    Ok(())
+}
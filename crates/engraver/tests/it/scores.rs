@@ -0,0 +1,93 @@
+use engraver::render::{
+    input::{
+        score::{Alignment, ScoreStyle, TextFormat},
+        Score, ScoreMetadata,
+    },
+    ir::{Element, FontWeight},
+};
+use engraver_parser::parse_staff;
+
+use crate::helpers;
+
+fn metadata() -> ScoreMetadata {
+    ScoreMetadata {
+        title: Some("Sonatina".to_owned()),
+        subtitle: Some("for solo flute".to_owned()),
+        composer: Some("M. Clementi".to_owned()),
+        lyricist: Some("Anonymous".to_owned()),
+        arranger: Some("arr. A. Player".to_owned()),
+        copyright: Some("© 2024 Engraver".to_owned()),
+    }
+}
+
+fn score(metadata: ScoreMetadata) -> Score {
+    Score {
+        metadata,
+        style: ScoreStyle::default(),
+        staff: parse_staff("treble 4/4 c d e f | g a b c5 | c5[1] ||")
+            .unwrap()
+            .into(),
+    }
+}
+
+#[test]
+fn header_and_footer() {
+    let score = score(metadata());
+    assert_score_snapshot!(score);
+}
+
+#[test]
+fn title_only() {
+    let score = score(ScoreMetadata {
+        title: Some("Sonatina".to_owned()),
+        ..ScoreMetadata::default()
+    });
+    assert_score_snapshot!(score);
+}
+
+#[test]
+fn custom_style() {
+    let mut score = score(metadata());
+    score.style.title = TextFormat {
+        size: 2.0,
+        alignment: Alignment::Left,
+        ..score.style.title
+    };
+    score.style.composer.weight = FontWeight::Bold;
+    score.style.copyright.alignment = Alignment::Right;
+    assert_score_snapshot!(score);
+}
+
+#[test]
+fn page_extends_around_header_and_footer() {
+    let metadata = helpers::metadata();
+
+    let plain = score(ScoreMetadata::default()).render(metadata).unwrap();
+    let full = score(self::metadata()).render(metadata).unwrap();
+
+    assert!(full.top > plain.top);
+    assert!(full.bottom < plain.bottom);
+
+    let texts: Vec<_> = full
+        .elements
+        .iter()
+        .filter_map(|element| match element {
+            Element::Text(text) => Some(text),
+            _ => None,
+        })
+        .collect();
+    let title = texts.iter().find(|text| text.value == "Sonatina").unwrap();
+    let composer = texts
+        .iter()
+        .find(|text| text.value == "M. Clementi")
+        .unwrap();
+    let copyright = texts
+        .iter()
+        .find(|text| text.value == "© 2024 Engraver")
+        .unwrap();
+
+    assert!(title.origin.y > composer.origin.y);
+    assert!(title.origin.y < full.top);
+    assert!(copyright.origin.y > full.bottom);
+    assert!(copyright.origin.y < plain.bottom);
+}
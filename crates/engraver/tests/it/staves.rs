@@ -0,0 +1,124 @@
+use engraver::{
+    model::StaffGeometry,
+    render::{input, ir::Element},
+    svg,
+};
+use engraver_parser::parse_staff;
+use smufl::StaffSpaces;
+
+use crate::helpers;
+
+#[test]
+fn one_line() {
+    assert_staff_snapshot!("\\lines 1 percussion c c c[8] c c c | c[2] r :|");
+}
+
+#[test]
+fn three_lines() {
+    assert_staff_snapshot!("\\lines 3 percussion c e g r | c[1] ||");
+}
+
+#[test]
+fn six_line_tab() {
+    assert_staff_snapshot!("\\lines 6 tab |: c e g r :|");
+}
+
+#[test]
+fn scaled() {
+    assert_staff_snapshot!("\\scale 0.75 treble c d e f | g[1] ||");
+}
+
+#[test]
+fn scaled_staff_is_smaller() {
+    let metadata = helpers::metadata();
+    let render = |input: &'static str| {
+        let staff: input::Staff = parse_staff(input).unwrap().into();
+        staff.render(metadata).unwrap()
+    };
+    let standard = render("treble c d e f ||");
+    let scaled = render("\\scale 0.75 treble c d e f ||");
+
+    assert_eq!(standard.len(), scaled.len());
+
+    for (standard, scaled) in standard.iter().zip(&scaled) {
+        assert_eq!(scaled.max_x(), standard.max_x() * 0.75);
+
+        match (standard, scaled) {
+            (Element::Symbol(standard), Element::Symbol(scaled)) => {
+                assert_eq!(scaled.origin.y, standard.origin.y * 0.75);
+                assert_eq!(scaled.size, 0.75);
+            }
+            (Element::Line(standard), Element::Line(scaled)) => {
+                assert_eq!(scaled.from.y, standard.from.y * 0.75);
+                assert_eq!(scaled.thickness, standard.thickness * 0.75);
+            }
+            _ => {}
+        }
+    }
+
+    let options = svg::Options {
+        symbol_font_name: "Bravura".to_owned(),
+        text_font_family: vec![],
+        staff_space_to_pixel_ratio: 10.0,
+    };
+    let width = |elements| {
+        svg::elements_to_svg_document(elements, &options)
+            .get_attributes()
+            .get("width")
+            .unwrap()
+            .to_string()
+            .parse::<f64>()
+            .unwrap()
+    };
+    let standard_width = width(standard);
+
+    assert!((width(scaled) - standard_width * 0.75).abs() < 1e-9);
+}
+
+#[test]
+fn parse_geometry() {
+    let staff = parse_staff("\\lines 6 \\scale 0.75 tab c |").unwrap();
+
+    assert_eq!(
+        staff.geometry,
+        StaffGeometry {
+            lines: 6,
+            scale: 0.75
+        }
+    );
+    assert_eq!(
+        parse_staff("c |").unwrap().geometry,
+        StaffGeometry::default()
+    );
+}
+
+#[test]
+fn geometry() {
+    let single = StaffGeometry {
+        lines: 1,
+        scale: 1.0,
+    };
+    assert_eq!(single.top(), StaffSpaces(0.0));
+    assert_eq!(single.middle(), StaffSpaces(0.0));
+    assert_eq!(
+        single.barline_extent(),
+        (StaffSpaces(-1.0), StaffSpaces(1.0))
+    );
+
+    let tab = StaffGeometry {
+        lines: 6,
+        scale: 1.0,
+    };
+    assert_eq!(tab.top(), StaffSpaces(5.0));
+    assert_eq!(tab.middle(), StaffSpaces(2.5));
+    assert!(tab.contains(StaffSpaces(5.0)));
+    assert!(!tab.contains(StaffSpaces(5.5)));
+    assert_eq!(tab.barline_extent(), (StaffSpaces(0.0), StaffSpaces(5.0)));
+}
+
+#[test]
+fn tab_clef_is_centered() {
+    let staff: input::Staff = parse_staff("\\lines 6 tab c |").unwrap().into();
+
+    assert_eq!(staff.clef.unwrap().y, StaffSpaces(2.5));
+}
@@ -1,3 +1,19 @@
+use engraver::{
+    model::{
+        duration,
+        time_signature::{Beats, Meter, Unit},
+        Duration, TimeSignature,
+    },
+    render::{
+        input::{self, Beam},
+        ir::{Element, Polygon},
+    },
+};
+use engraver_parser::{parse_lilypond, parse_staff};
+use smufl::StaffSpaces;
+
+use crate::helpers;
+
 #[test]
 fn even_top_and_bottom() {
     assert_staff_snapshot!("4/4");
@@ -22,3 +38,221 @@ fn very_wide_on_top() {
 fn very_wide_on_bottom() {
     assert_staff_snapshot!("1/128");
 }
+
+#[test]
+fn change() {
+    assert_staff_snapshot!("4/4 c d e f | \\time 3/4 c d e ||");
+}
+
+#[test]
+fn parse_lilypond_time_change() {
+    assert_eq!(
+        parse_lilypond(r"{ \time 4/4 c'1 | \time 3/4 c'2. }").unwrap(),
+        parse_staff("4/4 c[1] | \\time 3/4 c[2.] |").unwrap()
+    );
+}
+
+#[test]
+fn common() {
+    assert_staff_snapshot!("C c d e f ||");
+}
+
+#[test]
+fn cut() {
+    assert_staff_snapshot!("C| c[2] d ||");
+}
+
+#[test]
+fn additive() {
+    assert_staff_snapshot!("3+2+2/8 c[8] d e f g a b ||");
+}
+
+#[test]
+fn interchangeable() {
+    assert_staff_snapshot!("3/4 6/8 c[4] d e ||");
+}
+
+#[test]
+fn single_number() {
+    assert_staff_snapshot!("3 c d e ||");
+}
+
+#[test]
+fn note_denominator() {
+    assert_staff_snapshot!("2/[4.] c[4.] d ||");
+}
+
+#[test]
+fn unspecified_beats() {
+    assert_staff_snapshot!("X/[4.] c[4.] d e ||");
+}
+
+#[test]
+fn measure_lengths() {
+    let divisions = |input: &'static str| {
+        parse_staff(input)
+            .unwrap()
+            .time_signature
+            .and_then(|time_signature| time_signature.divisions())
+    };
+
+    assert_eq!(divisions("C"), Some(1024));
+    assert_eq!(divisions("C|"), Some(1024));
+    assert_eq!(divisions("3/4"), Some(768));
+    assert_eq!(divisions("3+2+2/8"), Some(896));
+    assert_eq!(divisions("3/4 6/8"), Some(768));
+    assert_eq!(divisions("3"), Some(768));
+    assert_eq!(divisions("2/[4.]"), Some(768));
+    assert_eq!(divisions("X/[4.]"), None);
+}
+
+#[test]
+fn beat_groups() {
+    let beat_groups = |input: &'static str| {
+        parse_staff(input)
+            .unwrap()
+            .time_signature
+            .and_then(|time_signature| time_signature.beat_groups())
+    };
+
+    assert_eq!(beat_groups("C"), Some(vec![256, 256, 256, 256]));
+    assert_eq!(beat_groups("C|"), Some(vec![512, 512]));
+    assert_eq!(beat_groups("3/4"), Some(vec![256, 256, 256]));
+    assert_eq!(beat_groups("6/8"), Some(vec![384, 384]));
+    assert_eq!(beat_groups("3/8"), Some(vec![384]));
+    assert_eq!(beat_groups("3+2+2/8"), Some(vec![384, 256, 256]));
+    assert_eq!(beat_groups("3"), Some(vec![256, 256, 256]));
+    assert_eq!(beat_groups("2/[4.]"), Some(vec![384, 384]));
+    assert_eq!(beat_groups("X/[4.]"), None);
+}
+
+#[test]
+fn beamed_by_beat_groups() {
+    let beams = |input: &'static str| {
+        let staff: input::Staff = parse_staff(input).unwrap().into();
+
+        staff.measures[0]
+            .elements
+            .iter()
+            .filter_map(|element| match element {
+                input::measure::Element::Note(note) => Some(note.beam),
+                input::measure::Element::Chord(chord) => Some(chord.beam),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+    let (begin, end) = (Some(Beam::Begin), Some(Beam::End));
+
+    assert_eq!(
+        beams("3+2+2/8 c[8] d e f g a b ||"),
+        [begin, None, end, begin, end, begin, end]
+    );
+    assert_eq!(
+        beams("6/8 c[8] d e f g a ||"),
+        [begin, None, end, begin, None, end]
+    );
+    assert_eq!(beams("2/4 c[8] r d {e g} ||"), [None, begin, end]);
+    assert_eq!(beams("2/4 c[8.] d[16] e[8] f ||"), [None, None, begin, end]);
+    assert_eq!(beams("X/[4.] c[8] d e f ||"), [None, None, None, None]);
+}
+
+#[test]
+fn beamed_down() {
+    assert_staff_snapshot!("6/8 c6[8] b a g f e ||");
+}
+
+#[test]
+fn beam_meets_stems_pointing_down() {
+    let staff: input::Staff = parse_staff("6/8 c6[8] b5 a g f e ||").unwrap().into();
+    let elements = staff.render(helpers::metadata()).unwrap();
+
+    let min_x = |polygon: &Polygon<StaffSpaces>| {
+        polygon
+            .points()
+            .iter()
+            .map(|point| point.x)
+            .fold(
+                polygon.max_x(),
+                |min_x, x| if x < min_x { x } else { min_x },
+            )
+    };
+    let polygons: Vec<_> = elements
+        .iter()
+        .filter_map(|element| match element {
+            Element::Polygon(polygon) => Some(polygon),
+            _ => None,
+        })
+        .collect();
+    let (beams, stems): (Vec<_>, Vec<_>) = polygons
+        .into_iter()
+        .filter(|polygon| polygon.max_y() - polygon.min_y() > StaffSpaces(0.4))
+        .partition(|polygon| polygon.max_x() - min_x(polygon) > StaffSpaces(2.0));
+
+    assert_eq!(beams.len(), 2);
+    assert_eq!(stems.len(), 6);
+
+    for (beam, stems) in beams.iter().zip(stems.chunks(3)) {
+        for stem in [stems[0], stems[2]] {
+            let ys: Vec<_> = beam
+                .points()
+                .iter()
+                .filter(|point| point.x >= min_x(stem) && point.x <= stem.max_x())
+                .map(|point| point.y)
+                .collect();
+
+            assert_eq!(ys.len(), 2);
+            assert!(ys.iter().any(|y| *y < stem.min_y()));
+            assert!(ys.iter().any(|y| *y > stem.min_y()));
+        }
+    }
+}
+
+#[test]
+fn measures_of_wrong_length() {
+    let measures = |input: &'static str| parse_staff(input).unwrap().measures_of_wrong_length();
+
+    assert_eq!(measures("3/4 c d e | c d e ||"), Vec::<usize>::new());
+    assert_eq!(measures("3/4 c | c d e | c d ||"), Vec::<usize>::new());
+    assert_eq!(measures("3/4 c d e | c d | c d e f ||"), [1, 2]);
+    assert_eq!(measures("3/4 c d e | R | c d e ||"), Vec::<usize>::new());
+    assert_eq!(measures("C c d e f g ||"), [0]);
+    assert_eq!(measures("X/[4.] c d e f g ||"), Vec::<usize>::new());
+}
+
+#[test]
+fn parse_forms() {
+    let time_signature = |input: &'static str| parse_staff(input).unwrap().time_signature;
+
+    assert_eq!(time_signature("C"), Some(TimeSignature::Common));
+    assert_eq!(time_signature("C|"), Some(TimeSignature::Cut));
+    assert_eq!(
+        time_signature("3+2/8 6/8"),
+        Some(TimeSignature::Meters(vec![
+            Meter {
+                beats: Beats::Numbers(vec![3, 2]),
+                unit: Some(Unit::Number(8)),
+            },
+            Meter {
+                beats: Beats::Numbers(vec![6]),
+                unit: Some(Unit::Number(8)),
+            },
+        ]))
+    );
+    assert_eq!(
+        time_signature("X/[4.]"),
+        Some(TimeSignature::Meters(vec![Meter {
+            beats: Beats::X,
+            unit: Some(Unit::Note(Duration {
+                value: duration::Value::Quarter,
+                dots: Some(duration::Dots::Dot),
+            })),
+        }]))
+    );
+    assert_eq!(
+        time_signature("3 1. c |"),
+        Some(TimeSignature::Meters(vec![Meter {
+            beats: Beats::Numbers(vec![3]),
+            unit: None,
+        }]))
+    );
+}